@@ -55,6 +55,7 @@ type State =
 interface AddRepository {
 	dialog: React.ReactNode;
 	startImportingRepositories: () => void;
+	importRepositories: (repositories: TauriRepositoryDescriptor[]) => void;
 }
 
 export function useImportRepositories({
@@ -96,6 +97,15 @@ export function useImportRepositories({
 		[importRepositoryPick],
 	);
 
+	const importRepositories = useCallback(function importRepositories(
+		repositories: TauriRepositoryDescriptor[],
+	) {
+		setState({
+			type: "confirmingRepositories",
+			pickResult: { repositories, unparsable_lines: [] },
+		});
+	}, []);
+
 	const downloadRepositories = useCallback(async function downloadRepositories(
 		repositories: TauriRepositoryDescriptor[],
 	) {
@@ -202,6 +212,7 @@ export function useImportRepositories({
 			</>
 		),
 		startImportingRepositories,
+		importRepositories,
 	};
 }
 
@@ -25,6 +25,7 @@ import type {
 	TauriCopyProjectForMigrationProgress,
 	TauriCreateBackupProgress,
 	TauriProject,
+	TauriProjectAdvisories,
 	TauriProjectType,
 } from "@/lib/bindings";
 import { commands } from "@/lib/bindings";
@@ -39,6 +40,7 @@ import {
 	CircleUserRound,
 	Ellipsis,
 	Globe,
+	ShieldAlert,
 	Star,
 } from "lucide-react";
 import React, {
@@ -75,12 +77,14 @@ const LegacyProjectTypes = [
 
 export function ProjectRow({
 	project,
+	advisories,
 	openUnity,
 	onRemoved,
 	loading,
 	refresh,
 }: {
 	project: TauriProject;
+	advisories?: TauriProjectAdvisories;
 	openUnity: OpenUnityFunction;
 	onRemoved?: () => void;
 	loading?: boolean;
@@ -146,14 +150,19 @@ export function ProjectRow({
 							className={"text-left select-text cursor-auto w-full"}
 						>
 							<div className="flex flex-col">
-								<Tooltip>
-									<TooltipTriggerIfExists
-										className={"text-left select-text cursor-auto w-full"}
-									>
-										<p className="font-normal whitespace-pre">{project.name}</p>
-									</TooltipTriggerIfExists>
-									<TooltipContent>{project.name}</TooltipContent>
-								</Tooltip>
+								<div className="flex flex-row items-center gap-1">
+									<Tooltip>
+										<TooltipTriggerIfExists
+											className={"text-left select-text cursor-auto min-w-0"}
+										>
+											<p className="font-normal whitespace-pre">
+												{project.name}
+											</p>
+										</TooltipTriggerIfExists>
+										<TooltipContent>{project.name}</TooltipContent>
+									</Tooltip>
+									{advisories && <AdvisoriesBadge advisories={advisories} />}
+								</div>
 								<Tooltip>
 									<TooltipTriggerIfExists
 										className={"text-left select-text cursor-auto w-full"}
@@ -264,6 +273,34 @@ export function ProjectRow({
 	);
 }
 
+function AdvisoriesBadge({
+	advisories,
+}: {
+	advisories: TauriProjectAdvisories;
+}) {
+	const severe =
+		advisories.severity === "High" || advisories.severity === "Critical";
+	return (
+		<Tooltip>
+			<TooltipTrigger className={"shrink-0"}>
+				<ShieldAlert
+					className={`size-4 ${severe ? "text-destructive" : "text-warning"}`}
+				/>
+			</TooltipTrigger>
+			<TooltipPortal>
+				<TooltipContent>
+					{tc("projects:tooltip:affected by advisories", {
+						count: advisories.affected_packages,
+						severity: tt(
+							`projects:advisory severity:${advisories.severity.toLowerCase()}`,
+						),
+					})}
+				</TooltipContent>
+			</TooltipPortal>
+		</Tooltip>
+	);
+}
+
 function ManageOrMigrateButton({
 	project,
 	refresh,
@@ -4,12 +4,17 @@ import Loading from "@/app/-loading";
 import { ScrollableCardTable } from "@/components/ScrollableCardTable";
 import { Card } from "@/components/ui/card";
 import { assertNever } from "@/lib/assert-never";
-import type { TauriProject, TauriProjectType } from "@/lib/bindings";
+import type {
+	TauriProject,
+	TauriProjectAdvisories,
+	TauriProjectType,
+} from "@/lib/bindings";
 import { commands } from "@/lib/bindings";
 import { tc } from "@/lib/i18n";
 import { toastThrownError } from "@/lib/toast";
 import type { OpenUnityFunction, Result } from "@/lib/use-open-unity";
 import { compareUnityVersionString } from "@/lib/version";
+import { useQuery } from "@tanstack/react-query";
 import { ChevronDown, ChevronUp, ChevronsUpDown, Star } from "lucide-react";
 import type React from "react";
 import { useEffect, useMemo, useState } from "react";
@@ -134,6 +139,19 @@ function ProjectsTableCard({
 }) {
 	const [sorting, setSortingState] = useState<Sorting>("lastModified");
 
+	// advisories are loaded after the project list since loading all projects takes time
+	const advisoriesResult = useQuery({
+		queryKey: ["environmentProjectAdvisories", projects[0]?.list_version],
+		queryFn: commands.environmentProjectAdvisories,
+	});
+	const advisoriesByPath = useMemo(
+		() =>
+			new Map<string, TauriProjectAdvisories>(
+				advisoriesResult.data?.map((x) => [x.path, x]) ?? [],
+			),
+		[advisoriesResult.data],
+	);
+
 	useEffect(() => {
 		(async () => {
 			let newSorting = await commands.environmentGetProjectSorting();
@@ -289,6 +307,7 @@ function ProjectsTableCard({
 					<ProjectRow
 						key={project.index}
 						project={project}
+						advisories={advisoriesByPath.get(project.path)}
 						loading={loading}
 						refresh={refresh}
 						onRemoved={onRemoved}
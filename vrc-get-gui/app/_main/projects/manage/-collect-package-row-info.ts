@@ -42,6 +42,8 @@ export interface PackageRowInfo {
 		version: TauriVersion;
 		yanked: boolean;
 	};
+	// the version range in dependencies if it's not a single version
+	dependencyRange: string | null;
 	latest: PackageLatestInfo;
 	stableLatest: PackageLatestInfo;
 }
@@ -131,6 +133,7 @@ export function combinePackagesAndProjectDetails(
 					sources: new Set(),
 					isThereSource: false,
 					installed: null,
+					dependencyRange: null,
 					latest: { status: "none" },
 					stableLatest: { status: "none" },
 				}),
@@ -307,6 +310,13 @@ export function combinePackagesAndProjectDetails(
 		}
 	}
 
+	if (project) {
+		for (const [name, range] of project.dependency_ranges) {
+			const packageRowInfo = packagesTable.get(name);
+			if (packageRowInfo) packageRowInfo.dependencyRange = range;
+		}
+	}
+
 	const isAvatarsSdkInstalled =
 		packagesTable.get("com.vrchat.avatars")?.installed != null;
 	const isWorldsSdkInstalled =
@@ -5,12 +5,19 @@ import { SearchBox } from "@/components/SearchBox";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent } from "@/components/ui/card";
 import { Checkbox } from "@/components/ui/checkbox";
+import {
+	DialogDescription,
+	DialogFooter,
+	DialogOpen,
+	DialogTitle,
+} from "@/components/ui/dialog";
 import {
 	DropdownMenu,
 	DropdownMenuContent,
 	DropdownMenuItem,
 	DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
+import { Input } from "@/components/ui/input";
 import {
 	Select,
 	SelectContent,
@@ -43,6 +50,8 @@ import {
 	CirclePlus,
 	Ellipsis,
 	RefreshCw,
+	SlidersHorizontal,
+	Unlink,
 } from "lucide-react";
 import type React from "react";
 import { useRef } from "react";
@@ -138,6 +147,8 @@ export const PackageListCard = memo(function PackageListCard({
 				let envVersion: number | undefined = undefined;
 				let hasUnityIncompatibleLatest = false;
 				for (const packageRow of packageRowsData) {
+					// upgrading all should not go out of the version range in dependencies
+					if (packageRow.dependencyRange != null) continue;
 					const latestInfo = packageRow[latestKey];
 					if (latestInfo.status === "upgradable") {
 						if (envVersion == null) envVersion = latestInfo.pkg.env_version;
@@ -185,6 +196,30 @@ export const PackageListCard = memo(function PackageListCard({
 		[createChanges, projectPath],
 	);
 
+	const onEjectRequested = useCallback(
+		async (pkg: PackageRowInfo) =>
+			createChanges(
+				{ type: "eject", displayName: pkg.displayName },
+				commands.projectEjectPackages(projectPath, [pkg.id]),
+			),
+		[createChanges, projectPath],
+	);
+
+	const [editingRangeOf, setEditingRangeOf] = useState<PackageRowInfo | null>(
+		null,
+	);
+
+	const onSetRangeRequested = useCallback(
+		(pkg: PackageRowInfo, range: string) => {
+			setEditingRangeOf(null);
+			createChanges(
+				{ type: "setRange", displayName: pkg.displayName, range },
+				commands.projectInstallPackageWithRange(projectPath, pkg.id, range),
+			);
+		},
+		[createChanges, projectPath],
+	);
+
 	const onInstallOrUpgradeBulkRequested = useCallback(
 		(stable: boolean) => {
 			const latestKey = stable ? "stableLatest" : "latest";
@@ -266,7 +301,13 @@ export const PackageListCard = memo(function PackageListCard({
 		setBulkUpdatePackageIds((prev) => prev.filter(([id, _]) => id !== row.id));
 	}, []);
 
-	const dialogForState: React.ReactNode = null;
+	const dialogForState: React.ReactNode = editingRangeOf && (
+		<VersionRangeDialog
+			pkg={editingRangeOf}
+			close={() => setEditingRangeOf(null)}
+			submit={onSetRangeRequested}
+		/>
+	);
 
 	const TABLE_HEAD = [
 		"projects:manage:package",
@@ -334,6 +375,8 @@ export const PackageListCard = memo(function PackageListCard({
 									pkg={row}
 									onInstallRequested={onInstallRequested}
 									onRemoveRequested={onRemoveRequested}
+									onEjectRequested={onEjectRequested}
+									onEditRangeRequested={setEditingRangeOf}
 									bulkUpdateSelected={bulkUpdatePackageIds.some(
 										([id, _]) => id === row.id,
 									)}
@@ -377,15 +420,18 @@ function ManagePackagesHeading({
 }) {
 	const { isLoading } = usePageContext();
 
-	const upgradableToLatest = packageRowsData.some(
+	const upgradableRows = packageRowsData.filter(
+		(row) => row.dependencyRange == null,
+	);
+	const upgradableToLatest = upgradableRows.some(
 		(row) => row.latest.status === "upgradable",
 	);
-	const upgradingToPrerelease = packageRowsData.some(
+	const upgradingToPrerelease = upgradableRows.some(
 		(row) =>
 			row.latest.status === "upgradable" && row.latest.pkg.version.pre !== "",
 	);
 	const upgradableToStable =
-		packageRowsData.some((row) => row.stableLatest.status === "upgradable") &&
+		upgradableRows.some((row) => row.stableLatest.status === "upgradable") &&
 		upgradingToPrerelease;
 
 	const searchRef = useRef<HTMLInputElement>(null);
@@ -739,6 +785,8 @@ const PackageRow = memo(function PackageRow({
 	pkg,
 	onInstallRequested,
 	onRemoveRequested,
+	onEjectRequested,
+	onEditRangeRequested,
 	bulkUpdateSelected,
 	bulkUpdateAvailable,
 	addBulkUpdatePackage,
@@ -750,6 +798,8 @@ const PackageRow = memo(function PackageRow({
 		hasUnityIncompatibleLatest?: boolean,
 	) => void;
 	onRemoveRequested: (pkgId: PackageRowInfo) => void;
+	onEjectRequested: (pkgId: PackageRowInfo) => void;
+	onEditRangeRequested: (pkg: PackageRowInfo) => void;
 	bulkUpdateSelected: boolean;
 	bulkUpdateAvailable: boolean;
 	addBulkUpdatePackage: (pkg: PackageRowInfo) => void;
@@ -782,6 +832,32 @@ const PackageRow = memo(function PackageRow({
 		onRemoveRequested(pkg);
 	};
 
+	const eject = () => {
+		onEjectRequested(pkg);
+	};
+
+	const editRange = () => {
+		onEditRangeRequested(pkg);
+	};
+
+	const editRangeButton = (
+		<Tooltip>
+			<TooltipTrigger asChild>
+				<ButtonDisabledIfLoading
+					variant={"ghost"}
+					size={"icon"}
+					disabled={!pkg.isThereSource}
+					onClick={editRange}
+				>
+					<SlidersHorizontal className={"size-5 text-secondary-foreground"} />
+				</ButtonDisabledIfLoading>
+			</TooltipTrigger>
+			<TooltipContent>
+				{tc("projects:manage:tooltip:set version range")}
+			</TooltipContent>
+		</Tooltip>
+	);
+
 	const onClickBulkUpdate = () => {
 		if (bulkUpdateSelected) {
 			removeBulkUpdatePackage(pkg);
@@ -863,41 +939,61 @@ const PackageRow = memo(function PackageRow({
 			<td className={noGrowCellClass}>
 				<div className="flex flex-row gap-2 max-w-min">
 					{pkg.installed ? (
-						<Tooltip>
-							<TooltipTrigger asChild>
-								<ButtonDisabledIfLoading
-									variant={"ghost"}
-									size={"icon"}
-									onClick={remove}
-								>
-									<CircleMinus className={"size-5 text-destructive"} />
-								</ButtonDisabledIfLoading>
-							</TooltipTrigger>
-							<TooltipContent>
-								{tc("projects:manage:tooltip:remove packages")}
-							</TooltipContent>
-						</Tooltip>
+						<>
+							{editRangeButton}
+							<Tooltip>
+								<TooltipTrigger asChild>
+									<ButtonDisabledIfLoading
+										variant={"ghost"}
+										size={"icon"}
+										onClick={eject}
+									>
+										<Unlink className={"size-5 text-secondary-foreground"} />
+									</ButtonDisabledIfLoading>
+								</TooltipTrigger>
+								<TooltipContent>
+									{tc("projects:manage:tooltip:eject package")}
+								</TooltipContent>
+							</Tooltip>
+							<Tooltip>
+								<TooltipTrigger asChild>
+									<ButtonDisabledIfLoading
+										variant={"ghost"}
+										size={"icon"}
+										onClick={remove}
+									>
+										<CircleMinus className={"size-5 text-destructive"} />
+									</ButtonDisabledIfLoading>
+								</TooltipTrigger>
+								<TooltipContent>
+									{tc("projects:manage:tooltip:remove packages")}
+								</TooltipContent>
+							</Tooltip>
+						</>
 					) : (
-						<Tooltip>
-							<TooltipTrigger asChild>
-								<ButtonDisabledIfLoading
-									variant={"ghost"}
-									size={"icon"}
-									disabled={!latestVersion}
-									className={
-										!latestVersion ? "disabled:pointer-events-auto" : ""
-									}
-									onClick={installLatest}
-								>
-									<CirclePlus className={"size-5 text-secondary-foreground"} />
-								</ButtonDisabledIfLoading>
-							</TooltipTrigger>
-							<TooltipContent>
-								{!latestVersion
-									? tc("projects:manage:tooltip:incompatible with unity")
-									: tc("projects:manage:tooltip:add package")}
-							</TooltipContent>
-						</Tooltip>
+						<>
+							{editRangeButton}
+							<Tooltip>
+								<TooltipTrigger asChild>
+									<ButtonDisabledIfLoading
+										variant={"ghost"}
+										size={"icon"}
+										disabled={!latestVersion}
+										className={
+											!latestVersion ? "disabled:pointer-events-auto" : ""
+										}
+										onClick={installLatest}
+									>
+										<CirclePlus className={"size-5 text-secondary-foreground"} />
+									</ButtonDisabledIfLoading>
+								</TooltipTrigger>
+								<TooltipContent>
+									{!latestVersion
+										? tc("projects:manage:tooltip:incompatible with unity")
+										: tc("projects:manage:tooltip:add package")}
+								</TooltipContent>
+							</Tooltip>
+						</>
 					)}
 				</div>
 			</td>
@@ -1067,3 +1163,48 @@ function LatestPackageInfo({
 			assertNever(info);
 	}
 }
+
+function VersionRangeDialog({
+	pkg,
+	close,
+	submit,
+}: {
+	pkg: PackageRowInfo;
+	close: () => void;
+	submit: (pkg: PackageRowInfo, range: string) => void;
+}) {
+	const [range, setRange] = useState(
+		pkg.dependencyRange ??
+			(pkg.installed ? `^${toVersionString(pkg.installed.version)}` : ""),
+	);
+
+	return (
+		<DialogOpen>
+			<DialogTitle>
+				{tc("projects:manage:dialog:set version range title", {
+					name: pkg.displayName,
+				})}
+			</DialogTitle>
+			<DialogDescription>
+				<p>{tc("projects:manage:dialog:set version range description")}</p>
+				<Input
+					className={"w-full mt-2"}
+					value={range}
+					onChange={(e) => setRange(e.target.value)}
+					placeholder={"^1.2.0"}
+				/>
+			</DialogDescription>
+			<DialogFooter>
+				<Button onClick={close} variant={"destructive"}>
+					{tc("general:button:cancel")}
+				</Button>
+				<Button
+					onClick={() => submit(pkg, range.trim())}
+					disabled={range.trim() === ""}
+				>
+					{tc("general:button:continue")}
+				</Button>
+			</DialogFooter>
+		</DialogOpen>
+	);
+}
@@ -13,6 +13,7 @@ import type {
 	TauriPackageChange,
 	TauriPendingProjectChanges,
 	TauriRemoveReason,
+	TauriVersion,
 } from "@/lib/bindings";
 import { commands } from "@/lib/bindings";
 import { isHandleable } from "@/lib/errors";
@@ -44,6 +45,19 @@ export type RequestedOperation =
 			type: "remove";
 			displayName: string;
 	  }
+	| {
+			type: "eject";
+			displayName: string;
+	  }
+	| {
+			type: "adopt";
+			displayName: string;
+	  }
+	| {
+			type: "setRange";
+			displayName: string;
+			range: string;
+	  }
 	| {
 			type: "bulkInstalled";
 			hasUnityIncompatibleLatest: boolean;
@@ -171,6 +185,28 @@ export function usePackageChangeDialog({
 								}),
 							);
 							break;
+						case "eject":
+							toastSuccess(
+								tt("projects:manage:toast:package ejected", {
+									name: requested.displayName,
+								}),
+							);
+							break;
+						case "adopt":
+							toastSuccess(
+								tt("projects:manage:toast:package adopted", {
+									name: requested.displayName,
+								}),
+							);
+							break;
+						case "setRange":
+							toastSuccess(
+								tt("projects:manage:toast:version range changed", {
+									name: requested.displayName,
+									range: requested.range,
+								}),
+							);
+							break;
 						case "resolve":
 							toastSuccess(tt("projects:manage:toast:resolved"));
 							break;
@@ -235,6 +271,7 @@ export function usePackageChangeDialog({
 				<ProjectChangesDialog
 					packages={packageRowsData}
 					changes={installStatus.changes}
+					requested={installStatus.requested}
 					existingPackages={existingPackages}
 					cancel={cancel}
 					apply={() => applyChanges(installStatus)}
@@ -262,12 +299,14 @@ export function usePackageChangeDialog({
 
 function ProjectChangesDialog({
 	changes,
+	requested,
 	packages,
 	existingPackages,
 	cancel,
 	apply,
 }: {
 	changes: TauriPendingProjectChanges;
+	requested: RequestedOperation;
 	packages: PackageRowInfo[];
 	existingPackages?: [string, TauriBasePackageInfo][];
 	cancel: () => void;
@@ -304,10 +343,27 @@ function ProjectChangesDialog({
 		return "Remove" in pair[1];
 	}
 
+	function isEject(
+		pair: [string, TauriPackageChange],
+	): pair is [string, { Eject: TauriVersion }] {
+		return "Eject" in pair[1];
+	}
+
+	function isAdopt(pair: [string, TauriPackageChange]): pair is [
+		string,
+		{
+			Adopt: { version: TauriVersion; modified_files: string[] | null };
+		},
+	] {
+		return "Adopt" in pair[1];
+	}
+
 	const existingPackageMap = new Map(existingPackages ?? []);
 
 	const installingPackages = changes.package_changes.filter(isInstallNew);
 	const removingPackages = changes.package_changes.filter(isRemove);
+	const ejectingPackages = changes.package_changes.filter(isEject);
+	const adoptingPackages = changes.package_changes.filter(isAdopt);
 
 	const reInstallingPackages = installingPackages.filter(([pkgId, c]) => {
 		const info = existingPackageMap.get(pkgId);
@@ -339,6 +395,8 @@ function ProjectChangesDialog({
 	removingRequestedPackages.sort(comparePackageChangeByName);
 	removingLegacyPackages.sort(comparePackageChangeByName);
 	removingUnusedPackages.sort(comparePackageChangeByName);
+	ejectingPackages.sort(comparePackageChangeByName);
+	adoptingPackages.sort(comparePackageChangeByName);
 
 	const ChangelogButton = ({ url }: { url?: string | null }) => {
 		if (url == null) return null;
@@ -369,6 +427,14 @@ function ProjectChangesDialog({
 			<DialogDescription className={"overflow-y-auto max-h-[50vh]"}>
 				<p>{tc("projects:manage:dialog:confirm changes description")}</p>
 				<div className={"flex flex-col gap-1 p-2"}>
+					{requested.type === "setRange" && (
+						<TypographyItem>
+							{tc("projects:manage:dialog:set version range", {
+								name: requested.displayName,
+								range: requested.range,
+							})}
+						</TypographyItem>
+					)}
 					{installingNewPackages.map(([pkgId, pkgChange]) => {
 						const name =
 							pkgChange.InstallNew.display_name ?? pkgChange.InstallNew.name;
@@ -435,6 +501,53 @@ function ProjectChangesDialog({
 							</TypographyItem>
 						);
 					})}
+					{ejectingPackages.map(([pkgId, pkgChange]) => {
+						const name = getPackageDisplayName(pkgId);
+						const version = toVersionString(pkgChange.Eject);
+						return (
+							<TypographyItem key={pkgId}>
+								{tc("projects:manage:dialog:eject package", {
+									name,
+									version,
+								})}
+							</TypographyItem>
+						);
+					})}
+					{adoptingPackages.map(([pkgId, pkgChange]) => {
+						const name = getPackageDisplayName(pkgId);
+						const version = toVersionString(pkgChange.Adopt.version);
+						const modifiedFiles = pkgChange.Adopt.modified_files;
+						return (
+							<div key={pkgId} className={"p-3"}>
+								<p className={"font-normal"}>
+									{tc("projects:manage:dialog:adopt package", {
+										name,
+										version,
+									})}
+								</p>
+								{modifiedFiles == null ? (
+									<p className={"text-warning"}>
+										{tc(
+											"projects:manage:dialog:modifications of the package cannot be checked",
+										)}
+									</p>
+								) : modifiedFiles.length > 0 ? (
+									<>
+										<p className={"text-warning"}>
+											{tc(
+												"projects:manage:dialog:the following files are modified locally",
+											)}
+										</p>
+										<ul className={"list-disc ml-4"}>
+											{modifiedFiles.map((f) => (
+												<li key={f}>{f}</li>
+											))}
+										</ul>
+									</>
+								) : null}
+							</div>
+						);
+					})}
 				</div>
 				{versionConflicts.length > 0 ? (
 					<>
@@ -510,6 +623,20 @@ function ProjectChangesDialog({
 						</div>
 					</>
 				) : null}
+				{changes.dependency_overrides.length > 0 ? (
+					<>
+						<p className={"text-warning"}>
+							{tc("projects:manage:dialog:dependency overrides are active")}
+						</p>
+						<div className={"flex flex-col gap-1 p-2"}>
+							{changes.dependency_overrides.map(([key, range]) => (
+								<TypographyItem key={key}>
+									{key}: {range}
+								</TypographyItem>
+							))}
+						</div>
+					</>
+				) : null}
 			</DialogDescription>
 			<DialogFooter>
 				<Button onClick={cancel} className="mr-1">
@@ -1,5 +1,6 @@
 "use client";
 
+import { useImportRepositories } from "@/app/_main/packages/repositories/-use-import-repositories";
 import { HNavBar, VStack } from "@/components/layout";
 import { Button } from "@/components/ui/button";
 import { Card } from "@/components/ui/card";
@@ -34,15 +35,24 @@ import {
 	useUnityArgumentsSettings,
 } from "@/components/unity-arguments-settings";
 import { useBackupProjectModal } from "@/lib/backup-project";
-import type { TauriProjectDetails, TauriUnityVersions } from "@/lib/bindings";
+import type {
+	TauriProjectDetails,
+	TauriRepositoryDescriptor,
+	TauriUnityVersions,
+	TauriVersion,
+} from "@/lib/bindings";
 import { commands } from "@/lib/bindings";
 import { VRCSDK_PACKAGES, VRCSDK_UNITY_VERSIONS } from "@/lib/constants";
-import { tc } from "@/lib/i18n";
+import { tc, tt } from "@/lib/i18n";
 import { nameFromPath } from "@/lib/os";
 import { useRemoveProjectModal } from "@/lib/remove-project";
 import { toastSuccess, toastThrownError } from "@/lib/toast";
 import { useOpenUnity } from "@/lib/use-open-unity";
-import { compareUnityVersionString, parseUnityVersion } from "@/lib/version";
+import {
+	compareUnityVersionString,
+	parseUnityVersion,
+	toVersionString,
+} from "@/lib/version";
 import {
 	type UseQueryResult,
 	useQueries,
@@ -92,30 +102,41 @@ function PageBody() {
 	// packagesResult: list of packages
 	// detailsResult: project details including installed packages
 	// unityVersionsResult: list of unity versions installed
-	const [repositoriesInfo, packagesResult, detailsResult, unityVersionsResult] =
-		useQueries({
-			queries: [
-				{
-					queryKey: ["environmentRepositoriesInfo"],
-					queryFn: commands.environmentRepositoriesInfo,
-					refetchOnWindowFocus: false,
-				},
-				{
-					queryKey: ["environmentPackages"],
-					queryFn: commands.environmentPackages,
-					refetchOnWindowFocus: false,
-				},
-				{
-					queryKey: ["projectDetails", projectPath],
-					queryFn: () => commands.projectDetails(projectPath),
-					refetchOnWindowFocus: false,
-				},
-				{
-					queryKey: ["environmentUnityVersions"],
-					queryFn: () => commands.environmentUnityVersions(),
-				},
-			],
-		});
+	// missingRepositoriesResult: repositories listed in the project but not added
+	const [
+		repositoriesInfo,
+		packagesResult,
+		detailsResult,
+		unityVersionsResult,
+		missingRepositoriesResult,
+	] = useQueries({
+		queries: [
+			{
+				queryKey: ["environmentRepositoriesInfo"],
+				queryFn: commands.environmentRepositoriesInfo,
+				refetchOnWindowFocus: false,
+			},
+			{
+				queryKey: ["environmentPackages"],
+				queryFn: commands.environmentPackages,
+				refetchOnWindowFocus: false,
+			},
+			{
+				queryKey: ["projectDetails", projectPath],
+				queryFn: () => commands.projectDetails(projectPath),
+				refetchOnWindowFocus: false,
+			},
+			{
+				queryKey: ["environmentUnityVersions"],
+				queryFn: () => commands.environmentUnityVersions(),
+			},
+			{
+				queryKey: ["projectMissingRepositories", projectPath],
+				queryFn: () => commands.projectMissingRepositories(projectPath),
+				refetchOnWindowFocus: false,
+			},
+		],
+	});
 
 	const [manualRefetching, setManualRefething] = useState<boolean>(false);
 
@@ -193,6 +214,13 @@ function PageBody() {
 		repositoriesInfo.refetch();
 	}, [repositoriesInfo]);
 
+	const importRepositories = useImportRepositories({
+		refetch: () => {
+			missingRepositoriesResult.refetch();
+			onRefresh();
+		},
+	});
+
 	const onRemoveProject = useCallback(() => {
 		projectRemoveModal.startRemove({
 			path: projectPath,
@@ -215,6 +243,44 @@ function PageBody() {
 		);
 	}, [packageChangeDialog, projectPath]);
 
+	const onAdoptRequest = useCallback(
+		(name: string) => {
+			packageChangeDialog.createChanges(
+				{ type: "adopt", displayName: name },
+				commands.projectAdoptPackages(projectPath, [name]),
+			);
+		},
+		[packageChangeDialog, projectPath],
+	);
+
+	const onRollForwardUnfinishedChanges = useCallback(async () => {
+		try {
+			setManualRefething(true);
+			await commands.projectRollForwardUnfinishedChanges(projectPath);
+			toastSuccess(tt("projects:manage:toast:unfinished changes finished"));
+		} catch (e) {
+			console.error(e);
+			toastThrownError(e);
+		} finally {
+			setManualRefething(false);
+			onRefreshProject();
+		}
+	}, [onRefreshProject, projectPath]);
+
+	const onRollbackUnfinishedChanges = useCallback(async () => {
+		try {
+			setManualRefething(true);
+			await commands.projectRollbackUnfinishedChanges(projectPath);
+			toastSuccess(tt("projects:manage:toast:unfinished changes reverted"));
+		} catch (e) {
+			console.error(e);
+			toastThrownError(e);
+		} finally {
+			setManualRefething(false);
+			onRefreshProject();
+		}
+	}, [onRefreshProject, projectPath]);
+
 	const isLoading =
 		packagesResult.isFetching ||
 		detailsResult.isFetching ||
@@ -241,12 +307,34 @@ function PageBody() {
 					onRemoveProject={onRemoveProject}
 					onBackupProject={onBackupProject}
 				/>
+				{detailsResult?.data?.unfinished_changes && (
+					<UnfinishedChangesCard
+						disabled={isLoading}
+						canRollback={detailsResult.data.unfinished_changes.can_rollback}
+						onRollForwardRequested={onRollForwardUnfinishedChanges}
+						onRollbackRequested={onRollbackUnfinishedChanges}
+					/>
+				)}
 				{detailsResult?.data?.should_resolve && (
 					<SuggestResolveProjectCard
 						disabled={isLoading}
 						onResolveRequested={onResolveRequest}
 					/>
 				)}
+				{(missingRepositoriesResult.data?.length ?? 0) > 0 && (
+					<MissingRepositoriesCard
+						disabled={isLoading}
+						repositories={missingRepositoriesResult.data ?? []}
+						onAddRequested={importRepositories.importRepositories}
+					/>
+				)}
+				{(detailsResult?.data?.ejected_packages.length ?? 0) > 0 && (
+					<EjectedPackagesCard
+						disabled={isLoading}
+						packages={detailsResult.data?.ejected_packages ?? []}
+						onAdoptRequested={onAdoptRequest}
+					/>
+				)}
 				<MigrationCards
 					isLoading={isLoading}
 					detailsResult={detailsResult.data}
@@ -267,6 +355,7 @@ function PageBody() {
 				{unityChangeVersion.dialog}
 				{projectRemoveModal.dialog}
 				{backupProjectModal.dialog}
+				{importRepositories.dialog}
 			</VStack>
 		</PageContextProvider>
 	);
@@ -390,6 +479,109 @@ function SuggestResolveProjectCard({
 	);
 }
 
+function MissingRepositoriesCard({
+	disabled,
+	repositories,
+	onAddRequested,
+}: {
+	disabled?: boolean;
+	repositories: TauriRepositoryDescriptor[];
+	onAddRequested: (repositories: TauriRepositoryDescriptor[]) => void;
+}) {
+	return (
+		<Card className={"shrink-0 p-2 flex flex-row items-center"}>
+			<div className="grow-0 shrink overflow-hidden text-sm">
+				<p className="py-1.5 font-bold whitespace-normal">
+					{tc("projects:manage:suggest add project repositories")}
+				</p>
+				<ul className={"list-disc pl-6"}>
+					{repositories.map((repo) => (
+						<li key={repo.url}>{repo.url}</li>
+					))}
+				</ul>
+			</div>
+			<div className={"grow shrink-0 w-2"} />
+			<Button
+				variant={"ghost"}
+				onClick={() => onAddRequested(repositories)}
+				disabled={disabled}
+			>
+				{tc("projects:manage:button:add repositories")}
+			</Button>
+		</Card>
+	);
+}
+
+function EjectedPackagesCard({
+	disabled,
+	packages,
+	onAdoptRequested,
+}: {
+	disabled?: boolean;
+	packages: [string, TauriVersion][];
+	onAdoptRequested: (name: string) => void;
+}) {
+	return (
+		<Card className={"shrink-0 p-2 flex flex-col"}>
+			<p className="py-1.5 font-bold whitespace-normal text-sm">
+				{tc("projects:manage:ejected packages")}
+			</p>
+			{packages.map(([name, version]) => (
+				<div key={name} className={"flex flex-row items-center text-sm"}>
+					<p className="grow-0 shrink overflow-hidden whitespace-normal">
+						{name} {toVersionString(version)}
+					</p>
+					<div className={"grow shrink-0 w-2"} />
+					<Button
+						variant={"ghost"}
+						onClick={() => onAdoptRequested(name)}
+						disabled={disabled}
+					>
+						{tc("projects:manage:button:adopt")}
+					</Button>
+				</div>
+			))}
+		</Card>
+	);
+}
+
+function UnfinishedChangesCard({
+	disabled,
+	canRollback,
+	onRollForwardRequested,
+	onRollbackRequested,
+}: {
+	disabled?: boolean;
+	canRollback: boolean;
+	onRollForwardRequested: () => void;
+	onRollbackRequested: () => void;
+}) {
+	return (
+		<Card className={"shrink-0 p-2 flex flex-row items-center"}>
+			<p className="cursor-pointer py-1.5 font-bold grow-0 shrink overflow-hidden whitespace-normal text-sm">
+				{tc("projects:manage:suggest recover unfinished changes")}
+			</p>
+			<div className={"grow shrink-0 w-2"} />
+			{canRollback && (
+				<Button
+					variant={"ghost"}
+					onClick={onRollbackRequested}
+					disabled={disabled}
+				>
+					{tc("projects:manage:button:revert changes")}
+				</Button>
+			)}
+			<Button
+				variant={"ghost-destructive"}
+				onClick={onRollForwardRequested}
+				disabled={disabled}
+			>
+				{tc("projects:manage:button:finish changes")}
+			</Button>
+		</Card>
+	);
+}
+
 function MigrationCards({
 	isLoading,
 	detailsResult,
@@ -157,6 +157,7 @@ function Settings({
 					updateUnityPaths={updateUnityPaths}
 					unityPaths={settings.unity_paths}
 					unityHubAccessMethod={settings.unity_hub_access_method}
+					unitySearchRoots={settings.unity_search_roots}
 				/>
 				<UnityLaunchArgumentsCard
 					refetch={refetch}
@@ -182,6 +183,7 @@ function Settings({
 				/>
 				<PackagesCard
 					showPrereleasePackages={settings.show_prerelease_packages}
+					autoResolveProjects={settings.auto_resolve_projects}
 					refetch={refetch}
 				/>
 				<AppearanceCard refetch={refetch} />
@@ -201,18 +203,57 @@ function UnityInstallationsCard({
 	refetch,
 	unityPaths,
 	unityHubAccessMethod,
+	unitySearchRoots,
 	updatingUnityPaths,
 	updateUnityPaths,
 }: {
 	refetch: () => void;
 	unityPaths: [path: string, version: string, fromHub: boolean][];
 	unityHubAccessMethod: UnityHubAccessMethod;
+	unitySearchRoots: string[];
 	updatingUnityPaths: boolean;
 	updateUnityPaths: () => void;
 }) {
 	const [pickUnity, unityDialog] = useFilePickerFunction(
 		commands.environmentPickUnity,
 	);
+	const [pickUnitySearchRoot, unitySearchRootDialog] = useFilePickerFunction(
+		commands.environmentPickUnitySearchRoot,
+	);
+
+	const addUnitySearchRoot = async () => {
+		try {
+			const result = await pickUnitySearchRoot();
+			switch (result) {
+				case "NoFolderSelected":
+					// no-op
+					break;
+				case "AlreadyAdded":
+					toastError(tt("settings:toast:unity search root already added"));
+					break;
+				case "Successful":
+					toastSuccess(tt("settings:toast:unity search root added"));
+					refetch();
+					updateUnityPaths();
+					break;
+				default:
+					assertNever(result);
+			}
+		} catch (e) {
+			console.error(e);
+			toastThrownError(e);
+		}
+	};
+
+	const removeUnitySearchRoot = async (path: string) => {
+		try {
+			await commands.environmentRemoveUnitySearchRoot(path);
+			refetch();
+		} catch (e) {
+			console.error(e);
+			toastThrownError(e);
+		}
+	};
 
 	const addUnity = async () => {
 		try {
@@ -337,7 +378,38 @@ function UnityInstallationsCard({
 					{tc("settings:use legacy unity hub loading description")}
 				</p>
 			</div>
+			<div className={"flex flex-col gap-1"}>
+				<div className={"flex align-middle"}>
+					<div className={"grow flex items-center"}>
+						<h3>{tc("settings:unity search roots")}</h3>
+					</div>
+					<Button
+						disabled={updatingUnityPaths}
+						onClick={addUnitySearchRoot}
+						size={"sm"}
+						className={"m-1"}
+					>
+						{tc("settings:button:add unity search root")}
+					</Button>
+				</div>
+				<p className={"text-sm whitespace-normal"}>
+					{tc("settings:unity search roots description")}
+				</p>
+				{unitySearchRoots.map((path) => (
+					<div key={path} className={"flex items-center gap-2"}>
+						<Input className="flex-auto" value={path} disabled />
+						<Button
+							variant={"destructive"}
+							size={"sm"}
+							onClick={() => removeUnitySearchRoot(path)}
+						>
+							{tc("settings:button:remove unity search root")}
+						</Button>
+					</div>
+				))}
+			</div>
 			{unityDialog}
+			{unitySearchRootDialog}
 		</Card>
 	);
 }
@@ -482,9 +554,11 @@ function BackupCard({
 
 function PackagesCard({
 	showPrereleasePackages,
+	autoResolveProjects,
 	refetch,
 }: {
 	showPrereleasePackages: boolean;
+	autoResolveProjects: boolean;
 	refetch: () => void;
 }) {
 	const clearPackageCache = async () => {
@@ -507,6 +581,16 @@ function PackagesCard({
 		}
 	};
 
+	const toggleAutoResolveProjects = async (e: "indeterminate" | boolean) => {
+		try {
+			await commands.environmentSetAutoResolveProjects(e === true);
+			refetch();
+		} catch (e) {
+			console.error(e);
+			toastThrownError(e);
+		}
+	};
+
 	return (
 		<Card className={"shrink-0 p-4 flex flex-col gap-4"}>
 			<h2>{tc("settings:packages")}</h2>
@@ -527,6 +611,18 @@ function PackagesCard({
 					{tc("settings:show prerelease description")}
 				</p>
 			</div>
+			<div>
+				<label className={"flex items-center gap-2"}>
+					<Checkbox
+						checked={autoResolveProjects}
+						onCheckedChange={(e) => toggleAutoResolveProjects(e)}
+					/>
+					{tc("settings:auto resolve projects")}
+				</label>
+				<p className={"text-sm whitespace-normal"}>
+					{tc("settings:auto resolve projects description")}
+				</p>
+			</div>
 		</Card>
 	);
 }
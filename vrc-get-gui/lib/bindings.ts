@@ -47,9 +47,15 @@ async environmentSetGuiAnimation(guiAnimation: boolean) : Promise<null> {
 async environmentSetUnityHubAccessMethod(unityHubAccessMethod: UnityHubAccessMethod) : Promise<null> {
     return await TAURI_INVOKE("environment_set_unity_hub_access_method", { unityHubAccessMethod });
 },
+async environmentSetAutoResolveProjects(autoResolveProjects: boolean) : Promise<null> {
+    return await TAURI_INVOKE("environment_set_auto_resolve_projects", { autoResolveProjects });
+},
 async environmentProjects() : Promise<TauriProject[]> {
     return await TAURI_INVOKE("environment_projects");
 },
+async environmentProjectAdvisories() : Promise<TauriProjectAdvisories[]> {
+    return await TAURI_INVOKE("environment_project_advisories");
+},
 async environmentAddProjectWithPicker() : Promise<TauriAddProjectWithPickerResult> {
     return await TAURI_INVOKE("environment_add_project_with_picker");
 },
@@ -137,6 +143,12 @@ async environmentPickUnityHub() : Promise<TauriPickUnityHubResult> {
 async environmentPickUnity() : Promise<TauriPickUnityResult> {
     return await TAURI_INVOKE("environment_pick_unity");
 },
+async environmentPickUnitySearchRoot() : Promise<TauriPickUnitySearchRootResult> {
+    return await TAURI_INVOKE("environment_pick_unity_search_root");
+},
+async environmentRemoveUnitySearchRoot(path: string) : Promise<null> {
+    return await TAURI_INVOKE("environment_remove_unity_search_root", { path });
+},
 async environmentPickProjectDefaultPath() : Promise<TauriPickProjectDefaultPathResult> {
     return await TAURI_INVOKE("environment_pick_project_default_path");
 },
@@ -173,9 +185,15 @@ async environmentWaitForUnityHubUpdate() : Promise<void> {
 async projectDetails(projectPath: string) : Promise<TauriProjectDetails> {
     return await TAURI_INVOKE("project_details", { projectPath });
 },
+async projectMissingRepositories(projectPath: string) : Promise<TauriRepositoryDescriptor[]> {
+    return await TAURI_INVOKE("project_missing_repositories", { projectPath });
+},
 async projectInstallPackages(projectPath: string, envVersion: number, packageIndices: number[]) : Promise<TauriPendingProjectChanges> {
     return await TAURI_INVOKE("project_install_packages", { projectPath, envVersion, packageIndices });
 },
+async projectInstallPackageWithRange(projectPath: string, packageId: string, range: string) : Promise<TauriPendingProjectChanges> {
+    return await TAURI_INVOKE("project_install_package_with_range", { projectPath, packageId, range });
+},
 async projectReinstallPackages(projectPath: string, packageIds: string[]) : Promise<TauriPendingProjectChanges> {
     return await TAURI_INVOKE("project_reinstall_packages", { projectPath, packageIds });
 },
@@ -185,9 +203,21 @@ async projectResolve(projectPath: string) : Promise<TauriPendingProjectChanges>
 async projectRemovePackages(projectPath: string, names: string[]) : Promise<TauriPendingProjectChanges> {
     return await TAURI_INVOKE("project_remove_packages", { projectPath, names });
 },
+async projectEjectPackages(projectPath: string, names: string[]) : Promise<TauriPendingProjectChanges> {
+    return await TAURI_INVOKE("project_eject_packages", { projectPath, names });
+},
+async projectAdoptPackages(projectPath: string, names: string[]) : Promise<TauriPendingProjectChanges> {
+    return await TAURI_INVOKE("project_adopt_packages", { projectPath, names });
+},
 async projectApplyPendingChanges(projectPath: string, changesVersion: number) : Promise<null> {
     return await TAURI_INVOKE("project_apply_pending_changes", { projectPath, changesVersion });
 },
+async projectRollForwardUnfinishedChanges(projectPath: string) : Promise<null> {
+    return await TAURI_INVOKE("project_roll_forward_unfinished_changes", { projectPath });
+},
+async projectRollbackUnfinishedChanges(projectPath: string) : Promise<null> {
+    return await TAURI_INVOKE("project_rollback_unfinished_changes", { projectPath });
+},
 async projectClearPendingChanges() : Promise<null> {
     return await TAURI_INVOKE("project_clear_pending_changes");
 },
@@ -280,6 +310,7 @@ export type SetupPages = "Appearance" | "UnityHub" | "ProjectPath" | "Backups" |
 export type TauriAddProjectWithPickerResult = "NoFolderSelected" | "InvalidSelection" | "AlreadyAdded" | "Successful"
 export type TauriAddRepositoryResult = "BadUrl" | "Success"
 export type TauriAddUserPackageWithPickerResult = "NoFolderSelected" | "InvalidSelection" | "AlreadyAdded" | "Successful"
+export type TauriAdvisorySeverity = "Low" | "Moderate" | "High" | "Critical"
 export type TauriBasePackageInfo = { name: string; display_name: string | null; description: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; legacy_packages: string[]; is_yanked: boolean }
 export type TauriCallUnityForMigrationResult = { type: "ExistsWithNonZero"; status: string } | { type: "FinishedSuccessfully" }
 export type TauriConflictInfo = { packages: string[]; unity_conflict: boolean; unlocked_names: string[] }
@@ -288,19 +319,21 @@ export type TauriCreateBackupProgress = { total: number; proceed: number; last_p
 export type TauriCreateProjectResult = "AlreadyExists" | "TemplateNotFound" | "Successful"
 export type TauriDownloadRepository = { type: "BadUrl" } | { type: "Duplicated"; reason: TauriDuplicatedReason; duplicated_name: string } | { type: "DownloadError"; message: string } | { type: "Success"; value: TauriRemoteRepositoryInfo }
 export type TauriDuplicatedReason = "URLDuplicated" | "IDDuplicated"
-export type TauriEnvironmentSettings = { default_project_path: string; project_backup_path: string; unity_hub: string; unity_paths: ([string, string, boolean])[]; show_prerelease_packages: boolean; backup_format: string; release_channel: string; use_alcom_for_vcc_protocol: boolean; default_unity_arguments: string[] | null; gui_animation: boolean; unity_hub_access_method: UnityHubAccessMethod }
+export type TauriEnvironmentSettings = { default_project_path: string; project_backup_path: string; unity_hub: string; unity_paths: ([string, string, boolean])[]; show_prerelease_packages: boolean; backup_format: string; release_channel: string; use_alcom_for_vcc_protocol: boolean; default_unity_arguments: string[] | null; gui_animation: boolean; unity_hub_access_method: UnityHubAccessMethod; unity_search_roots: string[]; auto_resolve_projects: boolean }
 export type TauriImportRepositoryPickResult = { type: "NoFilePicked" } | { type: "ParsedRepositories"; repositories: TauriRepositoryDescriptor[]; unparsable_lines: string[] }
 export type TauriPackage = ({ name: string; display_name: string | null; description: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; legacy_packages: string[]; is_yanked: boolean }) & { env_version: number; index: number; source: TauriPackageSource }
-export type TauriPackageChange = { InstallNew: TauriBasePackageInfo } | { Remove: TauriRemoveReason }
+export type TauriPackageChange = { InstallNew: TauriBasePackageInfo } | { Remove: TauriRemoveReason } | { Eject: TauriVersion } | { Adopt: { version: TauriVersion; modified_files: string[] | null } }
 export type TauriPackageSource = "LocalUser" | { Remote: { id: string; display_name: string } }
-export type TauriPendingProjectChanges = { changes_version: number; package_changes: ([string, TauriPackageChange])[]; remove_legacy_files: string[]; remove_legacy_folders: string[]; conflicts: ([string, TauriConflictInfo])[] }
+export type TauriPendingProjectChanges = { changes_version: number; package_changes: ([string, TauriPackageChange])[]; remove_legacy_files: string[]; remove_legacy_folders: string[]; conflicts: ([string, TauriConflictInfo])[]; dependency_overrides: ([string, string])[] }
 export type TauriPickProjectBackupPathResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful" }
 export type TauriPickProjectDefaultPathResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful"; new_path: string }
 export type TauriPickUnityHubResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful" }
 export type TauriPickUnityResult = "NoFolderSelected" | "InvalidSelection" | "AlreadyAdded" | "Successful"
+export type TauriPickUnitySearchRootResult = "NoFolderSelected" | "AlreadyAdded" | "Successful"
 export type TauriProject = { list_version: number; index: number; name: string; path: string; project_type: TauriProjectType; unity: string; unity_revision: string | null; last_modified: number; created_at: number; favorite: boolean; is_exists: boolean }
 export type TauriProjectCreationInformation = { templates: TauriProjectTemplate[]; default_path: string }
-export type TauriProjectDetails = { unity: [number, number] | null; unity_str: string | null; unity_revision: string | null; installed_packages: ([string, TauriBasePackageInfo])[]; should_resolve: boolean }
+export type TauriProjectAdvisories = { path: string; affected_packages: number; severity: TauriAdvisorySeverity }
+export type TauriProjectDetails = { unity: [number, number] | null; unity_str: string | null; unity_revision: string | null; installed_packages: ([string, TauriBasePackageInfo])[]; ejected_packages: ([string, TauriVersion])[]; dependency_ranges: ([string, string])[]; should_resolve: boolean; unfinished_changes: TauriUnfinishedChanges | null }
 export type TauriProjectDirCheckResult = "InvalidNameForFolderName" | "MayCompatibilityProblem" | "WideChar" | "AlreadyExists" | "Ok"
 export type TauriProjectTemplate = { type: "Builtin"; id: string; name: string } | { type: "Custom"; name: string }
 export type TauriProjectType = "Unknown" | "LegacySdk2" | "LegacyWorlds" | "LegacyAvatars" | "UpmWorlds" | "UpmAvatars" | "UpmStarter" | "Worlds" | "Avatars" | "VpmStarter"
@@ -308,6 +341,7 @@ export type TauriRemoteRepositoryInfo = { display_name: string; id: string; url:
 export type TauriRemoveReason = "Requested" | "Legacy" | "Unused"
 export type TauriRepositoriesInfo = { user_repositories: TauriUserRepository[]; hidden_user_repositories: string[]; hide_local_user_packages: boolean; show_prerelease_packages: boolean }
 export type TauriRepositoryDescriptor = { url: string; headers: { [key in string]: string } }
+export type TauriUnfinishedChanges = { can_rollback: boolean; remove_packages: string[]; install_packages: ([string, TauriVersion])[] }
 export type TauriUnityVersions = { unity_paths: ([string, string, boolean])[]; recommended_version: string; install_recommended_version_link: string }
 export type TauriUserPackage = { path: string; package: TauriBasePackageInfo }
 export type TauriUserRepository = { id: string; url: string | null; display_name: string }
@@ -13,7 +13,8 @@ use vrc_get_vpm::PackageManifest;
 use vrc_get_vpm::environment::VccDatabaseConnection;
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
 use vrc_get_vpm::unity_project::{
-    AddPackageErr, MigrateUnity2022Error, MigrateVpmError, ReinstalPackagesError, ResolvePackageErr,
+    AddPackageErr, MigrateUnity2022Error, MigrateVpmError, RecoverChangesErr,
+    ReinstalPackagesError, ResolvePackageErr,
 };
 use vrc_get_vpm::version::Version;
 
@@ -38,6 +39,7 @@ macro_rules! localizable_error {
 }
 
 mod async_command;
+mod auto_resolve;
 mod environment;
 mod project;
 mod start;
@@ -73,7 +75,9 @@ pub(crate) fn handlers() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
         environment::config::environment_gui_animation,
         environment::config::environment_set_gui_animation,
         environment::config::environment_set_unity_hub_access_method,
+        environment::config::environment_set_auto_resolve_projects,
         environment::projects::environment_projects,
+        environment::projects::environment_project_advisories,
         environment::projects::environment_add_project_with_picker,
         environment::projects::environment_remove_project,
         environment::projects::environment_remove_project_by_path,
@@ -103,6 +107,8 @@ pub(crate) fn handlers() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
         environment::settings::environment_get_settings,
         environment::settings::environment_pick_unity_hub,
         environment::settings::environment_pick_unity,
+        environment::settings::environment_pick_unity_search_root,
+        environment::settings::environment_remove_unity_search_root,
         environment::settings::environment_pick_project_default_path,
         environment::settings::environment_pick_project_backup_path,
         environment::settings::environment_set_show_prerelease_packages,
@@ -115,11 +121,17 @@ pub(crate) fn handlers() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
         environment::unity_hub::environment_is_loading_from_unity_hub_in_progress,
         environment::unity_hub::environment_wait_for_unity_hub_update,
         project::project_details,
+        project::project_missing_repositories,
         project::project_install_packages,
+        project::project_install_package_with_range,
         project::project_reinstall_packages,
         project::project_resolve,
         project::project_remove_packages,
+        project::project_eject_packages,
+        project::project_adopt_packages,
         project::project_apply_pending_changes,
+        project::project_roll_forward_unfinished_changes,
+        project::project_rollback_unfinished_changes,
         project::project_clear_pending_changes,
         project::project_migrate_project_to_2022,
         project::project_call_unity_for_migration,
@@ -164,7 +176,9 @@ pub(crate) fn export_ts() {
             environment::config::environment_gui_animation,
             environment::config::environment_set_gui_animation,
             environment::config::environment_set_unity_hub_access_method,
+            environment::config::environment_set_auto_resolve_projects,
             environment::projects::environment_projects,
+            environment::projects::environment_project_advisories,
             environment::projects::environment_add_project_with_picker,
             environment::projects::environment_remove_project,
             environment::projects::environment_remove_project_by_path,
@@ -194,6 +208,8 @@ pub(crate) fn export_ts() {
             environment::settings::environment_get_settings,
             environment::settings::environment_pick_unity_hub,
             environment::settings::environment_pick_unity,
+            environment::settings::environment_pick_unity_search_root,
+            environment::settings::environment_remove_unity_search_root,
             environment::settings::environment_pick_project_default_path,
             environment::settings::environment_pick_project_backup_path,
             environment::settings::environment_set_show_prerelease_packages,
@@ -206,11 +222,17 @@ pub(crate) fn export_ts() {
             environment::unity_hub::environment_is_loading_from_unity_hub_in_progress,
             environment::unity_hub::environment_wait_for_unity_hub_update,
             project::project_details,
+            project::project_missing_repositories,
             project::project_install_packages,
+            project::project_install_package_with_range,
             project::project_reinstall_packages,
             project::project_resolve,
             project::project_remove_packages,
+            project::project_eject_packages,
+            project::project_adopt_packages,
             project::project_apply_pending_changes,
+            project::project_roll_forward_unfinished_changes,
+            project::project_rollback_unfinished_changes,
             project::project_clear_pending_changes,
             project::project_migrate_project_to_2022,
             project::project_call_unity_for_migration,
@@ -325,6 +347,9 @@ impl_from_error!(
     async_zip::error::ZipError,
     vrc_get_vpm::environment::AddRepositoryErr,
     vrc_get_vpm::unity_project::RemovePackageErr,
+    vrc_get_vpm::unity_project::EjectPackageErr,
+    vrc_get_vpm::unity_project::AdoptPackageErr,
+    vrc_get_vpm::unity_project::SetDependencyRangeErr,
     fs_extra::error::Error,
 );
 
@@ -365,6 +390,18 @@ impl From<ReinstalPackagesError> for RustError {
     }
 }
 
+impl From<RecoverChangesErr> for RustError {
+    fn from(value: RecoverChangesErr) -> Self {
+        let message = value.to_string();
+        match value {
+            RecoverChangesErr::PackagesNotFound { packages } => {
+                RustError::handleable_missing_dependencies(message, packages)
+            }
+            _ => RustError::unrecoverable(message),
+        }
+    }
+}
+
 impl From<AddPackageErr> for RustError {
     fn from(value: AddPackageErr) -> Self {
         let message = value.to_string();
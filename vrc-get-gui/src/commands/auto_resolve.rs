@@ -0,0 +1,117 @@
+//! Resolves the registered projects in background when their manifests are changed,
+//! for example by pulling a branch with git. This is opt-in with `autoResolveProjects` config.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use log::info;
+use tauri::{AppHandle, Manager};
+use vrc_get_vpm::environment::{PackageInstaller, VccDatabaseConnection};
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+
+use crate::commands::prelude::*;
+
+/// The interval to check the modification time of the manifests
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+pub async fn watch_projects(app: AppHandle) {
+    // the last modification time of the manifests for each project path
+    let mut last_modified = HashMap::<Box<str>, Option<SystemTime>>::new();
+
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        if !app.state::<GuiConfigState>().get().auto_resolve_projects {
+            // the manifests modified while disabled should not be resolved when enabled
+            last_modified.clear();
+            continue;
+        }
+
+        // errors are logged when converted to RustError
+        let _ = check_projects(&app, &mut last_modified).await;
+    }
+}
+
+async fn check_projects(
+    app: &AppHandle,
+    last_modified: &mut HashMap<Box<str>, Option<SystemTime>>,
+) -> Result<(), RustError> {
+    let io = app.state::<DefaultEnvironmentIo>();
+
+    let projects = {
+        let connection = VccDatabaseConnection::connect(io.inner()).await?;
+        let projects = connection.get_projects().await?;
+        connection.dispose().await?;
+        projects
+    };
+
+    for project in &projects {
+        let Some(path) = project.path() else {
+            continue;
+        };
+        let project_io = DefaultProjectIo::new(Path::new(path).into());
+        let modified = UnityProject::manifests_modified_time(&project_io).await;
+
+        // the projects are not resolved when first seen, only when the manifests are changed
+        let Some(&last) = last_modified.get(path) else {
+            last_modified.insert(path.into(), modified);
+            continue;
+        };
+        if modified.is_none() || modified == last {
+            continue;
+        }
+
+        // the time is kept on errors so that the project is retried on the next check
+        if resolve_project(app, path, project_io).await.is_ok() {
+            // resolving updates the manifests, so take the time after resolving
+            let project_io = DefaultProjectIo::new(Path::new(path).into());
+            let modified = UnityProject::manifests_modified_time(&project_io).await;
+            last_modified.insert(path.into(), modified);
+        }
+    }
+
+    Ok(())
+}
+
+async fn resolve_project(
+    app: &AppHandle,
+    path: &str,
+    project_io: DefaultProjectIo,
+) -> Result<(), RustError> {
+    // wait for the operations from the GUI and load the project after them
+    let _lock = app.state::<ProjectOperationsState>().lock(path).await;
+    let mut unity_project = UnityProject::load(project_io).await?;
+    if !unity_project.should_resolve() {
+        return Ok(());
+    }
+
+    info!("manifests of {path} changed, resolving");
+
+    let settings = app.state::<SettingsState>();
+    let packages = app.state::<PackagesState>();
+    let io = app.state::<DefaultEnvironmentIo>();
+    let http = app.state::<reqwest::Client>();
+
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+    let changes = unity_project.resolve_request(packages.collection()).await?;
+
+    for (name, change) in changes.package_changes() {
+        let Some(package) = change.as_install().and_then(|x| x.install_package()) else {
+            continue;
+        };
+        info!("installing {name} version {} to {path}", package.version());
+    }
+
+    let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+    unity_project
+        .apply_pending_changes(&installer, changes)
+        .await?;
+
+    update_project_last_modified(&io, unity_project.project_dir()).await;
+
+    info!("resolved {path}");
+
+    Ok(())
+}
@@ -205,3 +205,15 @@ pub async fn environment_set_unity_hub_access_method(
     config.save().await?;
     Ok(())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn environment_set_auto_resolve_projects(
+    config: State<'_, GuiConfigState>,
+    auto_resolve_projects: bool,
+) -> Result<(), RustError> {
+    let mut config = config.load_mut().await?;
+    config.auto_resolve_projects = auto_resolve_projects;
+    config.save().await?;
+    Ok(())
+}
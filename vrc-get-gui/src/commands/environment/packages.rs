@@ -16,7 +16,7 @@ use vrc_get_vpm::environment::{
 };
 use vrc_get_vpm::io::{DefaultEnvironmentIo, IoTrait};
 use vrc_get_vpm::repositories_file::RepositoriesFile;
-use vrc_get_vpm::repository::RemoteRepository;
+use vrc_get_vpm::repository::{RemoteRepository, is_file_url};
 use vrc_get_vpm::{HttpClient, PackageInfo, VersionSelector};
 
 use crate::commands::prelude::*;
@@ -234,6 +234,7 @@ pub async fn environment_download_repository(
         let user_repo_ids = user_repo_ids(&settings);
 
         download_one_repository(
+            io.inner(),
             http.inner(),
             &url,
             &headers,
@@ -304,6 +305,7 @@ fn user_repo_ids(settings: &Settings) -> HashMap<String, String> {
 }
 
 async fn download_one_repository(
+    io: &DefaultEnvironmentIo,
     client: &impl HttpClient,
     repository_url: &Url,
     headers: &IndexMap<Box<str>, Box<str>>,
@@ -317,8 +319,17 @@ async fn download_one_repository(
         });
     }
 
-    let repo = match RemoteRepository::download(client, repository_url, headers).await {
-        Ok((repo, _)) => repo,
+    let repo = if is_file_url(repository_url) {
+        RemoteRepository::read_file(io, repository_url)
+            .await
+            .map(|(repo, _)| repo)
+    } else {
+        RemoteRepository::download(client, repository_url, headers)
+            .await
+            .map(|(repo, _)| repo)
+    };
+    let repo = match repo {
+        Ok(repo) => repo,
         Err(e) => {
             return Ok(TauriDownloadRepository::DownloadError {
                 message: e.to_string(),
@@ -375,7 +386,15 @@ pub async fn environment_add_repository(
     };
 
     let mut settings = settings.load_mut(io.inner()).await?;
-    add_remote_repo(&mut settings, url, None, headers, io.inner(), http.inner()).await?;
+    add_remote_repo(
+        &mut settings,
+        url,
+        None,
+        headers,
+        io.inner(),
+        Some(http.inner()),
+    )
+    .await?;
     settings.save().await?;
 
     // force update repository
@@ -489,6 +508,7 @@ pub async fn environment_import_download_repositories(
                 let counter_ref = &counter;
                 let user_repo_urls_ref = &user_repo_urls;
                 let user_repo_ids_ref = &user_repo_ids;
+                let io_ref = io.inner();
 
                 let http = window.state::<reqwest::Client>();
                 let mut results = try_join_all(repositories.into_iter().map(|adding_repo| {
@@ -496,6 +516,7 @@ pub async fn environment_import_download_repositories(
                     let http = http.clone();
                     async move {
                         let downloaded = download_one_repository(
+                            io_ref,
                             http.inner(),
                             &adding_repo.url,
                             &adding_repo.headers,
@@ -552,7 +573,7 @@ pub async fn environment_import_add_repositories(
             None,
             adding_repo.headers,
             io.inner(),
-            http.inner(),
+            Some(http.inner()),
         )
         .await?;
     }
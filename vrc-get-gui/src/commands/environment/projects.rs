@@ -2,8 +2,8 @@ use crate::commands::prelude::*;
 
 use crate::commands::async_command::{AsyncCallResult, AsyncCommandContext, With, async_command};
 use crate::utils::{FileSystemTree, collect_notable_project_files_tree, default_project_path};
-use futures::TryStreamExt;
 use futures::future::try_join_all;
+use futures::{StreamExt, TryStreamExt};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
@@ -16,6 +16,8 @@ use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use vrc_get_vpm::ProjectType;
 use vrc_get_vpm::environment::{PackageInstaller, Settings, UserProject, VccDatabaseConnection};
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, DirEntry, EnvironmentIo, IoTrait};
+use vrc_get_vpm::repository::AdvisorySeverity;
+use vrc_get_vpm::unity_project::set_product_identity_in_settings;
 
 #[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct TauriProject {
@@ -144,6 +146,82 @@ pub async fn environment_projects(
     Ok(vec)
 }
 
+#[derive(Serialize, specta::Type)]
+pub struct TauriProjectAdvisories {
+    path: String,
+    // the number of packages affected by advisories
+    affected_packages: u32,
+    severity: TauriAdvisorySeverity,
+}
+
+#[derive(Serialize, specta::Type)]
+enum TauriAdvisorySeverity {
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl From<AdvisorySeverity> for TauriAdvisorySeverity {
+    fn from(value: AdvisorySeverity) -> Self {
+        match value {
+            AdvisorySeverity::Low => Self::Low,
+            AdvisorySeverity::Moderate => Self::Moderate,
+            AdvisorySeverity::High => Self::High,
+            AdvisorySeverity::Critical => Self::Critical,
+        }
+    }
+}
+
+/// Returns the projects with packages affected by advisories in the repositories.
+///
+/// Projects without affected packages or failed to load are not included.
+#[tauri::command]
+#[specta::specta]
+pub async fn environment_project_advisories(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    projects_state: State<'_, ProjectsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+) -> Result<Vec<TauriProjectAdvisories>, RustError> {
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+    let collection = packages.collection();
+
+    let paths = (projects_state.get().await.data().iter())
+        .filter_map(|x| x.path())
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+
+    let advisories = futures::stream::iter(paths)
+        .map(|path| async move {
+            let unity_project = load_project(path.clone()).await.ok()?;
+
+            let severities = unity_project
+                .all_packages()
+                .filter_map(|package| {
+                    collection
+                        .find_advisories(package.name(), package.version())
+                        .map(|x| x.severity())
+                        .max()
+                })
+                .collect::<Vec<_>>();
+
+            Some(TauriProjectAdvisories {
+                path,
+                affected_packages: severities.len() as u32,
+                severity: severities.into_iter().max()?.into(),
+            })
+        })
+        .buffer_unordered(8)
+        .filter_map(|x| async { x })
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(advisories)
+}
+
 #[derive(Serialize, specta::Type)]
 pub enum TauriAddProjectWithPickerResult {
     NoFolderSelected,
@@ -727,36 +805,7 @@ pub async fn environment_create_project(
         let mut settings = String::new();
         settings_file.read_to_string(&mut settings).await?;
 
-        fn set_value(buffer: &mut String, finder: &str, value: &str) {
-            if let Some(pos) = buffer.find(finder) {
-                let before_ws = buffer[..pos]
-                    .chars()
-                    .last()
-                    .map(|x| x.is_ascii_whitespace())
-                    .unwrap_or(true);
-                if before_ws {
-                    if let Some(eol) = buffer[pos..].find('\n') {
-                        let eol = eol + pos;
-                        buffer.replace_range((pos + finder.len())..eol, value);
-                    }
-                }
-            }
-        }
-
-        fn yaml_quote(value: &str) -> String {
-            let s = value
-                .replace('"', "\\\"")
-                .replace('\n', "\\n")
-                .replace('\r', "\\r");
-            format!("\"{}\"", s)
-        }
-
-        set_value(
-            &mut settings,
-            "productGUID: ",
-            &uuid::Uuid::new_v4().simple().to_string(),
-        );
-        set_value(&mut settings, "productName: ", &yaml_quote(&project_name));
+        set_product_identity_in_settings(&mut settings, &project_name);
 
         settings_file.seek(std::io::SeekFrom::Start(0)).await?;
         settings_file.set_len(0).await?;
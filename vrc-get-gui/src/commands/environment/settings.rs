@@ -62,6 +62,8 @@ pub struct TauriEnvironmentSettings {
     default_unity_arguments: Option<Vec<String>>,
     gui_animation: bool,
     unity_hub_access_method: UnityHubAccessMethod,
+    unity_search_roots: Vec<String>,
+    auto_resolve_projects: bool,
 }
 
 #[tauri::command]
@@ -82,6 +84,8 @@ pub async fn environment_get_settings(
     let show_prerelease_packages;
     let gui_animation;
     let unity_hub_access_method;
+    let unity_search_roots;
+    let auto_resolve_projects;
 
     {
         let config = config.get();
@@ -91,6 +95,7 @@ pub async fn environment_get_settings(
         default_unity_arguments = config.default_unity_arguments.clone();
         gui_animation = config.gui_animation;
         unity_hub_access_method = config.unity_hub_access_method;
+        auto_resolve_projects = config.auto_resolve_projects;
     }
 
     {
@@ -118,6 +123,11 @@ pub async fn environment_get_settings(
         default_project_path = crate::utils::default_project_path(&mut settings).to_string();
         project_backup_path = crate::utils::project_backup_path(&mut settings).to_string();
         show_prerelease_packages = settings.show_prerelease_packages();
+        unity_search_roots = settings
+            .unity_search_roots()
+            .into_iter()
+            .map(|x| x.to_string_lossy().into_owned())
+            .collect();
 
         settings.save().await?;
     }
@@ -134,6 +144,8 @@ pub async fn environment_get_settings(
         default_unity_arguments,
         gui_animation,
         unity_hub_access_method,
+        unity_search_roots,
+        auto_resolve_projects,
     })
 }
 
@@ -300,6 +312,53 @@ pub async fn environment_pick_unity(
     Ok(TauriPickUnityResult::Successful)
 }
 
+#[derive(Serialize, specta::Type)]
+pub enum TauriPickUnitySearchRootResult {
+    NoFolderSelected,
+    AlreadyAdded,
+    Successful,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn environment_pick_unity_search_root(
+    settings: State<'_, SettingsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    window: Window,
+) -> Result<TauriPickUnitySearchRootResult, RustError> {
+    let Some(dir) = window
+        .dialog()
+        .file()
+        .set_parent(&window)
+        .blocking_pick_folder()
+        .map(|x| x.into_path_buf())
+        .transpose()?
+    else {
+        return Ok(TauriPickUnitySearchRootResult::NoFolderSelected);
+    };
+
+    let mut settings = settings.load_mut(io.inner()).await?;
+    if !settings.add_unity_search_root(&dir) {
+        return Ok(TauriPickUnitySearchRootResult::AlreadyAdded);
+    }
+    settings.save().await?;
+
+    Ok(TauriPickUnitySearchRootResult::Successful)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn environment_remove_unity_search_root(
+    settings: State<'_, SettingsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    path: String,
+) -> Result<(), RustError> {
+    let mut settings = settings.load_mut(io.inner()).await?;
+    settings.remove_unity_search_root(path.as_ref());
+    settings.save().await?;
+    Ok(())
+}
+
 #[derive(Serialize, specta::Type)]
 #[serde(tag = "type")]
 pub enum TauriPickProjectDefaultPathResult {
@@ -6,7 +6,7 @@ use tauri::State;
 use tokio::spawn;
 use tokio::sync::Mutex;
 use tokio::sync::oneshot;
-use vrc_get_vpm::environment::{VccDatabaseConnection, find_unity_hub};
+use vrc_get_vpm::environment::{VccDatabaseConnection, find_unity_hub, find_unity_in_search_roots};
 use vrc_get_vpm::io::DefaultEnvironmentIo;
 use vrc_get_vpm::unity_hub;
 
@@ -125,37 +125,44 @@ async fn update_unity_paths_from_unity_hub_impl(
     io: &DefaultEnvironmentIo,
 ) -> io::Result<bool> {
     let paths_from_hub = match config.get().unity_hub_access_method {
-        UnityHubAccessMethod::ReadConfig => unity_hub::load_unity_by_loading_unity_hub_files()
-            .await?
-            .into_iter()
-            .map(|x| (x.version, x.path))
-            .collect::<Vec<_>>(),
+        UnityHubAccessMethod::ReadConfig => Some(
+            unity_hub::load_unity_by_loading_unity_hub_files()
+                .await?
+                .into_iter()
+                .map(|x| (x.version, x.path))
+                .collect::<Vec<_>>(),
+        ),
         UnityHubAccessMethod::CallHub => {
             let unity_hub_path = {
                 let mut settings = settings.load_mut(io).await?;
-                let Some(unity_hub_path) = find_unity_hub(&mut settings, io).await? else {
-                    settings.save().await?;
-                    return Ok(false);
-                };
+                let unity_hub_path = find_unity_hub(&mut settings, io).await?;
                 settings.save().await?;
                 unity_hub_path
             };
 
-            unity_hub::load_unity_by_calling_unity_hub(unity_hub_path.as_ref()).await?
+            match unity_hub_path {
+                Some(unity_hub_path) => {
+                    Some(unity_hub::load_unity_by_calling_unity_hub(unity_hub_path.as_ref()).await?)
+                }
+                None => None,
+            }
         }
     };
 
+    let search_roots = settings.load(io).await?.unity_search_roots();
+    let paths_from_search_roots = find_unity_in_search_roots(io, &search_roots).await;
+
     {
         let mut connection = VccDatabaseConnection::connect(io).await?;
 
         connection
-            .update_unity_from_unity_hub_and_fs(&paths_from_hub, io)
+            .update_unity_installations(paths_from_hub.as_deref(), &paths_from_search_roots, io)
             .await?;
 
         connection.save(io).await?;
     }
 
-    Ok(true)
+    Ok(paths_from_hub.is_some())
 }
 
 #[tauri::command]
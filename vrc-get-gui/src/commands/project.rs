@@ -12,10 +12,14 @@ use vrc_get_vpm::io::DefaultEnvironmentIo;
 use vrc_get_vpm::unity_project::pending_project_changes::{
     ConflictInfo, PackageChange, RemoveReason,
 };
-use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
+use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges, UnfinishedChanges};
+use vrc_get_vpm::version::{DependencyRange, PrereleaseAcceptance, VersionRange};
+use vrc_get_vpm::{PackageCollection as _, VersionSelector};
 
 use crate::commands::DEFAULT_UNITY_ARGUMENTS;
+use crate::commands::TauriVersion;
 use crate::commands::async_command::*;
+use crate::commands::environment::packages::TauriRepositoryDescriptor;
 use crate::commands::prelude::*;
 use crate::utils::{PathExt, collect_notable_project_files_tree, project_backup_path};
 
@@ -25,7 +29,31 @@ pub struct TauriProjectDetails {
     unity_str: Option<String>,
     unity_revision: Option<String>,
     installed_packages: Vec<(String, TauriBasePackageInfo)>,
+    ejected_packages: Vec<(String, TauriVersion)>,
+    // dependencies specified with version range instead of single version
+    dependency_ranges: Vec<(String, String)>,
     should_resolve: bool,
+    unfinished_changes: Option<TauriUnfinishedChanges>,
+}
+
+#[derive(Serialize, specta::Type)]
+pub struct TauriUnfinishedChanges {
+    can_rollback: bool,
+    remove_packages: Vec<String>,
+    install_packages: Vec<(String, TauriVersion)>,
+}
+
+impl From<&UnfinishedChanges> for TauriUnfinishedChanges {
+    fn from(value: &UnfinishedChanges) -> Self {
+        Self {
+            can_rollback: value.can_rollback(),
+            remove_packages: value.remove_packages().map(|x| x.to_string()).collect(),
+            install_packages: value
+                .install_packages()
+                .map(|(name, version)| (name.to_string(), version.into()))
+                .collect(),
+        }
+    }
 }
 
 #[tauri::command]
@@ -43,10 +71,46 @@ pub async fn project_details(project_path: String) -> Result<TauriProjectDetails
             .installed_packages()
             .map(|(k, p)| (k.to_string(), TauriBasePackageInfo::new(p)))
             .collect(),
+        ejected_packages: unity_project
+            .ejected_packages()
+            .map(|(name, ejected)| (name.to_string(), ejected.version().into()))
+            .collect(),
+        dependency_ranges: unity_project
+            .dependencies()
+            .filter_map(|name| Some((name, unity_project.get_dependency(name)?)))
+            .filter(|(_, range)| range.as_single_version().is_none())
+            .map(|(name, range)| (name.to_string(), range.to_string()))
+            .collect(),
         should_resolve: unity_project.should_resolve(),
+        unfinished_changes: unity_project.unfinished_changes().map(Into::into),
     })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn project_missing_repositories(
+    settings: State<'_, SettingsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    project_path: String,
+) -> Result<Vec<TauriRepositoryDescriptor>, RustError> {
+    let unity_project = load_project(project_path).await?;
+    let Some(repositories) = unity_project.project_repositories().await? else {
+        return Ok(vec![]);
+    };
+
+    let settings = settings.load(io.inner()).await?;
+
+    Ok(repositories
+        .repositories()
+        .iter()
+        .filter(|x| !settings.has_remote_repo(x.url()))
+        .map(|x| TauriRepositoryDescriptor {
+            url: x.url().clone(),
+            headers: x.headers().clone(),
+        })
+        .collect())
+}
+
 #[derive(Serialize, specta::Type)]
 pub struct TauriPendingProjectChanges {
     changes_version: u32,
@@ -56,6 +120,8 @@ pub struct TauriPendingProjectChanges {
     remove_legacy_folders: Vec<String>,
 
     conflicts: Vec<(String, TauriConflictInfo)>,
+
+    dependency_overrides: Vec<(String, String)>,
 }
 
 impl TauriPendingProjectChanges {
@@ -82,6 +148,11 @@ impl TauriPendingProjectChanges {
                 .iter()
                 .map(|(name, info)| (name.to_string(), info.into()))
                 .collect(),
+            dependency_overrides: changes
+                .dependency_overrides()
+                .iter()
+                .map(|(key, range)| (key.to_string(), range.to_string()))
+                .collect(),
         }
     }
 }
@@ -90,6 +161,11 @@ impl TauriPendingProjectChanges {
 enum TauriPackageChange {
     InstallNew(Box<TauriBasePackageInfo>),
     Remove(TauriRemoveReason),
+    Eject(TauriVersion),
+    Adopt {
+        version: TauriVersion,
+        modified_files: Option<Vec<String>>,
+    },
 }
 
 impl TryFrom<&PackageChange<'_>> for TauriPackageChange {
@@ -102,6 +178,15 @@ impl TryFrom<&PackageChange<'_>> for TauriPackageChange {
                     .into(),
             ),
             PackageChange::Remove(remove) => TauriPackageChange::Remove(remove.reason().into()),
+            PackageChange::Eject(eject) => {
+                TauriPackageChange::Eject(eject.ejected_package().version().into())
+            }
+            PackageChange::Adopt(adopt) => TauriPackageChange::Adopt {
+                version: adopt.version().into(),
+                modified_files: adopt
+                    .modified_files()
+                    .map(|files| files.iter().map(|x| x.to_string()).collect()),
+            },
         })
     }
 }
@@ -208,6 +293,61 @@ pub async fn project_install_packages(
     })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn project_install_package_with_range(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    changes: State<'_, ChangesState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    project_path: String,
+    package_id: String,
+    range: String,
+) -> Result<TauriPendingProjectChanges, RustError> {
+    let range = range
+        .parse::<VersionRange>()
+        .map_err(|e| RustError::unrecoverable(format!("invalid version range: {e}")))?;
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+
+    changes!(packages, changes, |collection| {
+        let unity_project = load_project(project_path).await?;
+
+        let dependency_range = DependencyRange::range(range.clone());
+
+        let locked_in_range = unity_project
+            .get_locked(&package_id)
+            .is_some_and(|locked| dependency_range.matches(locked.version()));
+
+        if unity_project.get_dependency(&package_id).is_some() && locked_in_range {
+            // the installed version is already in the range so just change the range
+            unity_project.set_dependency_range_request(&package_id, dependency_range)?
+        } else {
+            let acceptance = if settings.show_prerelease_packages() {
+                PrereleaseAcceptance::Allow
+            } else {
+                PrereleaseAcceptance::Minimum
+            };
+            let selector =
+                VersionSelector::range_for(unity_project.unity_version(), &range, acceptance);
+            let Some(package) = collection.find_package_by_name(&package_id, selector) else {
+                return Err(RustError::unrecoverable(format!(
+                    "no version of {package_id} matches {range}"
+                )));
+            };
+
+            unity_project
+                .add_package_request_with_ranges(
+                    collection,
+                    &[(package, dependency_range)],
+                    settings.show_prerelease_packages(),
+                )
+                .await?
+        }
+    })
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn project_reinstall_packages(
@@ -268,10 +408,60 @@ pub async fn project_remove_packages(
     Ok(changes_state.set(changes, TauriPendingProjectChanges::new))
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn project_eject_packages(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    changes: State<'_, ChangesState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    project_path: String,
+    names: Vec<String>,
+) -> Result<TauriPendingProjectChanges, RustError> {
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+
+    changes!(packages, changes, |collection| {
+        let unity_project = load_project(project_path).await?;
+
+        let names = names.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+
+        unity_project.eject_request(&names, collection).await?
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn project_adopt_packages(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    changes: State<'_, ChangesState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    project_path: String,
+    names: Vec<String>,
+) -> Result<TauriPendingProjectChanges, RustError> {
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+    let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+
+    changes!(packages, changes, |collection| {
+        let unity_project = load_project(project_path).await?;
+
+        let names = names.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+
+        unity_project
+            .adopt_request(&names, collection, &installer)
+            .await?
+    })
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn project_apply_pending_changes(
     changes: State<'_, ChangesState>,
+    operations: State<'_, ProjectOperationsState>,
     io: State<'_, DefaultEnvironmentIo>,
     http: State<'_, reqwest::Client>,
     project_path: String,
@@ -285,6 +475,7 @@ pub async fn project_apply_pending_changes(
 
     let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
 
+    let _lock = operations.lock(&project_path).await;
     let mut unity_project = load_project(project_path).await?;
 
     unity_project
@@ -295,6 +486,47 @@ pub async fn project_apply_pending_changes(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn project_roll_forward_unfinished_changes(
+    settings: State<'_, SettingsState>,
+    packages: State<'_, PackagesState>,
+    operations: State<'_, ProjectOperationsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    http: State<'_, reqwest::Client>,
+    project_path: String,
+) -> Result<(), RustError> {
+    let settings = settings.load(io.inner()).await?;
+    let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+    let _lock = operations.lock(&project_path).await;
+    let mut unity_project = load_project(project_path).await?;
+
+    let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
+
+    unity_project
+        .roll_forward_unfinished_changes(packages.collection(), &installer)
+        .await?;
+
+    update_project_last_modified(&io, unity_project.project_dir()).await;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn project_rollback_unfinished_changes(
+    operations: State<'_, ProjectOperationsState>,
+    io: State<'_, DefaultEnvironmentIo>,
+    project_path: String,
+) -> Result<(), RustError> {
+    let _lock = operations.lock(&project_path).await;
+    let mut unity_project = load_project(project_path).await?;
+
+    unity_project.rollback_unfinished_changes().await?;
+
+    update_project_last_modified(&io, unity_project.project_dir()).await;
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn project_clear_pending_changes(
@@ -309,6 +541,7 @@ pub async fn project_clear_pending_changes(
 pub async fn project_migrate_project_to_2022(
     settings: State<'_, SettingsState>,
     packages: State<'_, PackagesState>,
+    operations: State<'_, ProjectOperationsState>,
     io: State<'_, DefaultEnvironmentIo>,
     http: State<'_, reqwest::Client>,
     project_path: String,
@@ -316,6 +549,7 @@ pub async fn project_migrate_project_to_2022(
     {
         let settings = settings.load(io.inner()).await?;
         let packages = packages.load(&settings, io.inner(), http.inner()).await?;
+        let _lock = operations.lock(&project_path).await;
         let mut unity_project = load_project(project_path).await?;
 
         let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
@@ -415,6 +649,7 @@ pub async fn project_call_unity_for_migration(
 pub async fn project_migrate_project_to_vpm(
     settings: State<'_, SettingsState>,
     packages: State<'_, PackagesState>,
+    operations: State<'_, ProjectOperationsState>,
     io: State<'_, DefaultEnvironmentIo>,
     http: State<'_, reqwest::Client>,
     project_path: String,
@@ -422,6 +657,7 @@ pub async fn project_migrate_project_to_vpm(
     let settings = settings.load(io.inner()).await?;
     let packages = packages.load(&settings, io.inner(), http.inner()).await?;
 
+    let _lock = operations.lock(&project_path).await;
     let mut unity_project = load_project(project_path).await?;
     let installer = PackageInstaller::new(io.inner(), Some(http.inner()));
 
@@ -58,6 +58,8 @@ pub fn startup(app: &mut App) {
         let config = GuiConfigState::new_load(io.inner()).await?;
         app.manage(config);
 
+        spawn(super::auto_resolve::watch_projects(app.clone()));
+
         let handle = app.clone();
         spawn(async move {
             let state = handle.state();
@@ -36,6 +36,8 @@ pub struct GuiConfig {
     pub gui_animation: bool,
     #[serde(default)]
     pub unity_hub_access_method: UnityHubAccessMethod,
+    #[serde(default)]
+    pub auto_resolve_projects: bool,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Default, specta::Type)]
@@ -65,6 +67,7 @@ impl Default for GuiConfig {
             logs_level: log_level_default(),
             gui_animation: true,
             unity_hub_access_method: UnityHubAccessMethod::ReadConfig,
+            auto_resolve_projects: false,
         }
     }
 }
@@ -58,6 +58,7 @@ fn main() {
         .manage(state::ProjectsState::new())
         .manage(state::PackagesState::new())
         .manage(state::ChangesState::new())
+        .manage(state::ProjectOperationsState::new())
         .register_uri_scheme_protocol("vrc-get", commands::handle_vrc_get_scheme)
         .invoke_handler(commands::handlers())
         .setup(move |app| {
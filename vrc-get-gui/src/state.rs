@@ -1,6 +1,7 @@
 mod changes;
 mod config;
 mod packages;
+mod project_operations;
 mod projects;
 mod settings;
 mod updater;
@@ -8,6 +9,7 @@ mod updater;
 pub use changes::*;
 pub use config::*;
 pub use packages::*;
+pub use project_operations::*;
 pub use projects::*;
 pub use settings::*;
 pub use updater::*;
@@ -114,7 +114,11 @@ impl PackagesState {
             }
         }
 
-        let collection = PackageCollection::load(settings, io, Some(http)).await?;
+        let collection = if force {
+            PackageCollection::load_force(settings, io, Some(http)).await?
+        } else {
+            PackageCollection::load(settings, io, Some(http)).await?
+        };
 
         let yoke = Yoke::<YokeData<'static>, _>::attach_to_cart(Arc::new(collection), |x| {
             YokeData::new(x.get_all_packages().collect())
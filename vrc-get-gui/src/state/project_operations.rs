@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// The locks to serialize the operations modifying the packages of each project,
+/// like applying changes from the GUI and resolving in background.
+pub struct ProjectOperationsState {
+    locks: std::sync::Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+}
+
+impl ProjectOperationsState {
+    pub fn new() -> Self {
+        Self {
+            locks: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits for other operations on the project to finish, and locks the project until the guard is dropped.
+    pub async fn lock(&self, project_path: impl AsRef<Path>) -> OwnedMutexGuard<()> {
+        let lock = (self.locks.lock().unwrap())
+            .entry(project_path.as_ref().into())
+            .or_default()
+            .clone();
+        lock.lock_owned().await
+    }
+}
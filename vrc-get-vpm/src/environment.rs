@@ -1,11 +1,13 @@
 mod repo_holder;
 mod repo_source;
+mod repository_policy;
 mod uesr_package_collection;
 mod vpm_settings;
 mod vrc_get_settings;
 
 #[cfg(feature = "vrc-get-litedb")]
 mod litedb;
+mod package_archive;
 mod package_collection;
 mod package_installer;
 #[cfg(feature = "experimental-project-management")]
@@ -13,10 +15,12 @@ mod project_management;
 mod settings;
 #[cfg(feature = "experimental-unity-management")]
 mod unity_management;
+#[cfg(all(feature = "unity-hub", feature = "unity"))]
+mod unity_search_roots;
 
 use crate::io;
-use crate::repository::RemoteRepository;
 use crate::repository::local::LocalCachedRepository;
+use crate::repository::{RemoteRepository, is_file_url};
 use crate::traits::HttpClient;
 use crate::utils::to_vec_pretty_os_eol;
 use futures::prelude::*;
@@ -34,11 +38,16 @@ pub(crate) use repo_holder::RepoHolder;
 pub(crate) use repo_source::RepoSource;
 #[cfg(feature = "experimental-unity-management")]
 pub use unity_management::*;
+#[cfg(all(feature = "unity-hub", feature = "unity"))]
+pub use unity_search_roots::*;
 
 #[cfg(feature = "vrc-get-litedb")]
 pub use litedb::VccDatabaseConnection;
+pub(crate) use package_archive::read_package_json_in_archive;
+pub use package_archive::{download_package_archive, read_package_archive, read_package_folder};
 pub use package_collection::PackageCollection;
 pub use package_installer::PackageInstaller;
+pub use repository_policy::RepositoryPolicy;
 pub use settings::Settings;
 pub use uesr_package_collection::UserPackageCollection;
 
@@ -46,30 +55,36 @@ const OFFICIAL_URL_STR: &str = "https://packages.vrchat.com/official?download";
 const LOCAL_OFFICIAL_PATH: &str = "Repos/vrc-official.json";
 const CURATED_URL_STR: &str = "https://packages.vrchat.com/curated?download";
 const LOCAL_CURATED_PATH: &str = "Repos/vrc-curated.json";
-const REPO_CACHE_FOLDER: &str = "Repos";
+pub(crate) const REPO_CACHE_FOLDER: &str = "Repos";
 
+/// Adds the repository at the url to the settings.
+///
+/// `file:` urls are read from the file system, so `http` can be `None` for them.
 pub async fn add_remote_repo(
     settings: &mut Settings,
     url: Url,
     name: Option<&str>,
     headers: IndexMap<Box<str>, Box<str>>,
     io: &impl EnvironmentIo,
-    http: &impl HttpClient,
+    http: Option<&impl HttpClient>,
 ) -> Result<(), AddRepositoryErr> {
-    let (remote_repo, etag) = RemoteRepository::download(http, &url, &headers).await?;
+    let local_cache = if is_file_url(&url) {
+        let (remote_repo, modified) = RemoteRepository::read_file(io, &url).await?;
+        let mut local_cache = LocalCachedRepository::new(remote_repo, headers.clone());
+        local_cache.set_source_modified(modified);
+        local_cache
+    } else {
+        let http = http.ok_or(AddRepositoryErr::OfflineMode)?;
+        let (remote_repo, etag) = RemoteRepository::download(http, &url, &headers).await?;
+        let mut local_cache = LocalCachedRepository::new(remote_repo, headers.clone());
+        local_cache.set_etag(etag);
+        local_cache
+    };
 
-    if !settings.can_add_remote_repo(&url, &remote_repo) {
+    if !settings.can_add_remote_repo(&url, local_cache.repo()) {
         return Err(AddRepositoryErr::AlreadyAdded);
     }
 
-    let mut local_cache = LocalCachedRepository::new(remote_repo, headers.clone());
-    if let Some(etag) = etag {
-        local_cache
-            .vrc_get
-            .get_or_insert_with(Default::default)
-            .etag = etag;
-    }
-
     io.create_dir_all(REPO_CACHE_FOLDER.as_ref()).await?;
     let file_name = write_new_repo(&local_cache, io).await?;
     let repo_path = io.resolve(format!("{}/{}", REPO_CACHE_FOLDER, file_name).as_ref());
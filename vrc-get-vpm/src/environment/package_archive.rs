@@ -0,0 +1,76 @@
+use crate::environment::REPO_CACHE_FOLDER;
+use crate::io::{EnvironmentIo, IoTrait};
+use crate::package_manifest::LooseManifest;
+use crate::utils::{MapResultExt, read_json_file, try_load_json};
+use crate::{HttpClient, PackageManifest, io};
+use async_zip::base::read::seek::ZipFileReader;
+use futures::prelude::*;
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use url::Url;
+
+/// Reads `package.json` at the root of the zip file of the package.
+pub async fn read_package_archive(io: &impl IoTrait, path: &Path) -> io::Result<PackageManifest> {
+    let LooseManifest(manifest) = read_package_json_in_archive(io, path).await?;
+    Ok(manifest)
+}
+
+pub(crate) async fn read_package_json_in_archive<T: DeserializeOwned>(
+    io: &impl IoTrait,
+    path: &Path,
+) -> io::Result<T> {
+    let zip_file = io::BufReader::new(io.open(path).await?);
+    let mut zip_reader = ZipFileReader::new(zip_file).await.err_mapped()?;
+
+    let Some(index) = zip_reader
+        .file()
+        .entries()
+        .iter()
+        .position(|x| x.filename().as_str().ok() == Some("package.json"))
+    else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("package.json not found in {}", path.display()),
+        ));
+    };
+
+    let reader = zip_reader.reader_without_entry(index).await.err_mapped()?;
+    read_json_file(reader, &path.join("package.json")).await
+}
+
+/// Reads `package.json` in the folder of the package.
+pub async fn read_package_folder(io: &impl IoTrait, path: &Path) -> io::Result<PackageManifest> {
+    let json_path = path.join("package.json");
+    match try_load_json::<LooseManifest>(io, &json_path).await? {
+        Some(LooseManifest(manifest)) => Ok(manifest),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("package.json not found in {}", path.display()),
+        )),
+    }
+}
+
+/// Downloads the zip file of the package to the cache folder and returns the absolute path to it.
+pub async fn download_package_archive(
+    io: &impl EnvironmentIo,
+    http: &impl HttpClient,
+    url: &Url,
+) -> io::Result<PathBuf> {
+    let hash = Sha256::digest(url.as_str().as_bytes());
+    let folder = PathBuf::from(format!("{REPO_CACHE_FOLDER}/vrc-get/downloaded"));
+    let path = folder.join(format!("{}.zip", hex::encode(&hash[..8])));
+
+    io.create_dir_all(&folder).await?;
+
+    log::debug!("downloading package archive {}", url);
+    let headers = IndexMap::new();
+    let mut response = pin!(http.get(url, &headers).await?);
+    let mut file = io.create(&path).await?;
+    io::copy(&mut response, &mut file).await?;
+    file.flush().await?;
+
+    Ok(io.resolve(&path))
+}
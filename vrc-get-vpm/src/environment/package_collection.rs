@@ -2,19 +2,31 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::PackageCollection as _;
-use crate::environment::{RepoHolder, Settings, UserPackageCollection};
+use crate::environment::{
+    REPO_CACHE_FOLDER, RepoHolder, RepoSource, RepositoryPolicy, Settings, UserPackageCollection,
+};
+use crate::git::GitPackage;
 use crate::io::EnvironmentIo;
-use crate::repository::LocalCachedRepository;
+use crate::repositories_file::RepositoryInfo;
+use crate::repository::{Advisory, LocalCachedRepository};
+use crate::version::Version;
 use crate::{HttpClient, PackageInfo, PackageManifest, UserRepoSetting, VersionSelector, io};
 use futures::prelude::*;
 use itertools::Itertools;
 use log::error;
+use sha2::{Digest, Sha256};
 
 /// A immutable structure that holds information about all the packages.
 #[derive(Debug, Clone)]
 pub struct PackageCollection {
     pub(super) repositories: HashMap<Box<Path>, LocalCachedRepository>,
     pub(super) user_packages: Vec<(PathBuf, PackageManifest)>,
+    /// zip files of packages explicitly added to this collection
+    pub(super) archive_packages: Vec<(PathBuf, PackageManifest)>,
+    /// packages in git repositories explicitly added to this collection.
+    /// other packages with the same name are hidden by them
+    pub(super) git_packages: Vec<GitPackage>,
+    pub(super) policy: RepositoryPolicy,
 }
 
 impl PackageCollection {
@@ -22,25 +34,168 @@ impl PackageCollection {
         Self {
             repositories: HashMap::new(),
             user_packages: Vec::new(),
+            archive_packages: Vec::new(),
+            git_packages: Vec::new(),
+            policy: RepositoryPolicy::default(),
         }
     }
 
+    /// Loads the packages from the repositories and user packages.
+    ///
+    /// Remote repositories fetched within the cache TTL in the settings are not fetched again.
     pub async fn load(
         settings: &Settings,
         io: &impl EnvironmentIo,
         http: Option<&impl HttpClient>,
+    ) -> io::Result<Self> {
+        Self::load_impl(settings, io, http, false).await
+    }
+
+    /// Loads the packages like [`Self::load`] but fetches all remote repositories regardless of the cache TTL.
+    pub async fn load_force(
+        settings: &Settings,
+        io: &impl EnvironmentIo,
+        http: Option<&impl HttpClient>,
+    ) -> io::Result<Self> {
+        Self::load_impl(settings, io, http, true).await
+    }
+
+    async fn load_impl(
+        settings: &Settings,
+        io: &impl EnvironmentIo,
+        http: Option<&impl HttpClient>,
+        force: bool,
     ) -> io::Result<Self> {
         let (repositories, user_packages) = futures::try_join!(
-            RepoHolder::load(settings, io, http),
+            RepoHolder::load(settings, io, http, force),
             UserPackageCollection::load(settings, io).map(Ok)
         )?;
 
         Ok(Self {
             repositories: repositories.into_repos(),
             user_packages: user_packages.into_packages(),
+            archive_packages: Vec::new(),
+            git_packages: Vec::new(),
+            policy: settings.repository_policy(),
         })
     }
 
+    /// Loads the repositories and adds them to this collection.
+    ///
+    /// This is for repositories not added to the settings, like repositories listed in the project.
+    /// The caches of the repositories are saved in `Repos/project` folder.
+    ///
+    /// Repositories with the id already used by another repository are not added
+    /// since the packages would shadow each other. They are returned to report to the user.
+    pub async fn add_repositories<'a>(
+        &mut self,
+        repositories: &[&'a RepositoryInfo],
+        io: &impl EnvironmentIo,
+        http: Option<&impl HttpClient>,
+    ) -> io::Result<Vec<&'a RepositoryInfo>> {
+        let cache_folder = format!("{REPO_CACHE_FOLDER}/project");
+        io.create_dir_all(cache_folder.as_ref()).await?;
+
+        let cache_paths = repositories
+            .iter()
+            .map(|repo| {
+                let hash = Sha256::digest(repo.url().as_str().as_bytes());
+                PathBuf::from(format!("{cache_folder}/{}.json", hex::encode(&hash[..8])))
+            })
+            .collect::<Vec<_>>();
+
+        let mut holder = RepoHolder::new();
+        holder
+            .load_repos(
+                http,
+                io,
+                repositories
+                    .iter()
+                    .zip(&cache_paths)
+                    .map(|(repo, path)| RepoSource::new(path, repo.headers(), Some(repo.url()))),
+            )
+            .await?;
+
+        let mut loaded = holder.into_repos();
+        let mut duplicated = Vec::new();
+        for (&repo, path) in repositories.iter().zip(&cache_paths) {
+            let Some(cache) = loaded.remove(path.as_path()) else {
+                continue;
+            };
+            let is_duplicated = cache
+                .id()
+                .is_some_and(|id| self.repositories.values().any(|x| x.id() == Some(id)));
+            if is_duplicated {
+                duplicated.push(repo);
+                continue;
+            }
+
+            // the repositories in the project are identified by the url in the project
+            self.policy.set_repository_id(path, repo.url().as_str());
+            self.repositories.insert(path.as_path().into(), cache);
+        }
+
+        Ok(duplicated)
+    }
+
+    /// Adds the zip file of the package to this collection.
+    ///
+    /// The path should be absolute.
+    pub fn add_archive_package(&mut self, path: PathBuf, manifest: PackageManifest) {
+        self.archive_packages.push((path, manifest));
+    }
+
+    /// Loads the packages in the git repository and adds them to this collection.
+    ///
+    /// The repository is cloned or fetched if `fetch` is true, otherwise the cloned repository is used.
+    /// If `name` is specified, packages with other names in the repository are ignored.
+    /// The package at `locked_commit` is added in addition to the packages of the source.
+    /// Returns the name of the package, or `None` if no packages are found.
+    #[cfg(feature = "git")]
+    pub async fn add_git_packages(
+        &mut self,
+        io: &impl EnvironmentIo,
+        source: &crate::git::GitSource,
+        name: Option<&str>,
+        locked_commit: Option<&str>,
+        fetch: bool,
+    ) -> io::Result<Option<Box<str>>> {
+        let repository = crate::git::GitRepository::open(io, source.url(), fetch).await?;
+        let packages = repository
+            .load_packages(source, name, locked_commit)
+            .await?;
+
+        let Some(first) = packages.first() else {
+            return Ok(None);
+        };
+        let name: Box<str> = first.manifest().name().into();
+        if let Some(other) = packages.iter().find(|x| x.manifest().name() != &*name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the name of the package in {source} changed from {name} to {}",
+                    other.manifest().name()
+                ),
+            ));
+        }
+
+        // the packages from the source replaces ones previously added
+        self.git_packages.retain(|x| x.manifest().name() != &*name);
+        self.git_packages.extend(packages);
+
+        Ok(Some(name))
+    }
+
+    pub fn git_packages(&self) -> &[GitPackage] {
+        &self.git_packages
+    }
+
+    fn is_git_package(&self, package: &str) -> bool {
+        self.git_packages
+            .iter()
+            .any(|x| x.manifest().name() == package)
+    }
+
     pub async fn remove_repositories(
         &mut self,
         remove_repos: &[UserRepoSetting],
@@ -66,6 +221,48 @@ impl PackageCollection {
         &self.user_packages
     }
 
+    pub fn repository_policy(&self) -> &RepositoryPolicy {
+        &self.policy
+    }
+
+    /// Returns true if the package can be taken from the repository cached at the path with the repository policy.
+    pub fn is_allowed_repository(&self, package: &str, cache_path: &Path) -> bool {
+        if self.policy.is_empty() {
+            return true;
+        }
+
+        if let Some(pinned) = self.policy.pinned_repository(package) {
+            return self.policy.repository_id(cache_path) == Some(pinned);
+        }
+
+        // no repository with higher priority provides the package
+        let priority = self.policy.priority_of(cache_path);
+        self.repositories
+            .iter()
+            .filter(|(_, x)| x.repo.get_package(package).is_some())
+            .all(|(path, _)| self.policy.priority_of(path) >= priority)
+    }
+
+    /// Returns true if the package can be taken from user packages with the repository policy.
+    fn is_allowed_user_package(&self, package: &str) -> bool {
+        self.policy.pinned_repository(package).is_none()
+    }
+
+    /// Returns the advisories affecting the version of the package.
+    ///
+    /// Advisories from repositories not allowed for the package with the repository policy are ignored.
+    pub fn find_advisories<'a>(
+        &'a self,
+        package: &str,
+        version: &Version,
+    ) -> impl Iterator<Item = &'a Advisory> {
+        self.repositories
+            .iter()
+            .filter(move |(path, _)| self.is_allowed_repository(package, path))
+            .flat_map(|(_, repo)| repo.advisories())
+            .filter(move |advisory| advisory.affects(package, version))
+    }
+
     pub fn find_whole_all_packages(
         &self,
         version_selector: VersionSelector,
@@ -88,45 +285,77 @@ impl crate::PackageCollection for PackageCollection {
         version_selector: VersionSelector,
     ) -> impl Iterator<Item = PackageInfo> {
         self.repositories
-            .values()
-            .filter(|x| x.repo.id() == Some("com.vrchat.repos.curated"))
-            .flat_map(move |repo| {
+            .iter()
+            .filter(|(_, x)| x.repo.id() == Some("com.vrchat.repos.curated"))
+            .flat_map(move |(path, repo)| {
                 repo.repo()
                     .get_packages()
                     .filter_map(move |x| x.get_latest(version_selector))
+                    .filter(move |json| self.is_allowed_repository(json.name(), path))
                     .map(|json| PackageInfo::remote(json, repo))
             })
     }
 
     fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo> {
-        let remote = self.repositories.values().flat_map(|repo| {
+        let remote = self.repositories.iter().flat_map(|(path, repo)| {
             repo.repo
                 .get_packages()
                 .flat_map(|x| x.all_versions())
+                .filter(|pkg| self.is_allowed_repository(pkg.name(), path))
                 .map(|pkg| PackageInfo::remote(pkg, repo))
         });
         let local = self
             .user_packages
             .iter()
+            .filter(|(_, json)| self.is_allowed_user_package(json.name()))
             .map(|(path, json)| PackageInfo::local(json, path));
+        let archive = self
+            .archive_packages
+            .iter()
+            .map(|(path, json)| PackageInfo::archive(json, path));
+        let git = self.git_packages.iter().map(PackageInfo::git);
 
-        remote.chain(local)
+        remote
+            .chain(local)
+            .chain(archive)
+            .filter(|x| !self.is_git_package(x.name()))
+            .chain(git)
     }
 
     fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo> {
-        let remote = self.repositories.values().flat_map(|repo| {
-            repo.repo
-                .get_package(package)
-                .into_iter()
-                .flat_map(|x| x.all_versions().map(|pkg| PackageInfo::remote(pkg, repo)))
-        });
+        let remote = self
+            .repositories
+            .iter()
+            .filter(move |(path, _)| self.is_allowed_repository(package, path))
+            .flat_map(|(_, repo)| {
+                repo.repo
+                    .get_package(package)
+                    .into_iter()
+                    .flat_map(|x| x.all_versions().map(|pkg| PackageInfo::remote(pkg, repo)))
+            });
         let local = self
             .user_packages
             .iter()
             .filter(move |(_, json)| json.name() == package)
+            .filter(move |_| self.is_allowed_user_package(package))
             .map(|(path, json)| PackageInfo::local(json, path));
+        let archive = self
+            .archive_packages
+            .iter()
+            .filter(move |(_, json)| json.name() == package)
+            .map(|(path, json)| PackageInfo::archive(json, path));
+        let git = self
+            .git_packages
+            .iter()
+            .filter(move |x| x.manifest().name() == package)
+            .map(PackageInfo::git);
 
-        remote.chain(local)
+        let is_git_package = self.is_git_package(package);
+        remote
+            .chain(local)
+            .chain(archive)
+            .filter(move |_| !is_git_package)
+            .chain(git)
     }
 
     fn find_package_by_name(
@@ -134,20 +363,44 @@ impl crate::PackageCollection for PackageCollection {
         package: &str,
         package_selector: VersionSelector,
     ) -> Option<PackageInfo> {
-        let remote = self.repositories.values().flat_map(|repo| {
-            repo.repo
-                .get_package(package)
-                .into_iter()
-                .flat_map(|pkg| pkg.get_latest(package_selector))
-                .map(|pkg| PackageInfo::remote(pkg, repo))
-        });
+        let remote = self
+            .repositories
+            .iter()
+            .filter(|(path, _)| self.is_allowed_repository(package, path))
+            .flat_map(|(_, repo)| {
+                repo.repo
+                    .get_package(package)
+                    .into_iter()
+                    .flat_map(|pkg| pkg.get_latest(package_selector))
+                    .map(|pkg| PackageInfo::remote(pkg, repo))
+            });
 
         let local = self
             .user_packages
             .iter()
             .filter(move |(_, json)| json.name() == package && package_selector.satisfies(json))
+            .filter(|_| self.is_allowed_user_package(package))
             .map(|(path, json)| PackageInfo::local(json, path));
 
-        remote.chain(local).max_by_key(|x| x.version())
+        let archive = self
+            .archive_packages
+            .iter()
+            .filter(move |(_, json)| json.name() == package && package_selector.satisfies(json))
+            .map(|(path, json)| PackageInfo::archive(json, path));
+
+        let git = self
+            .git_packages
+            .iter()
+            .filter(move |x| x.manifest().name() == package)
+            .filter(move |x| package_selector.satisfies(x.manifest()))
+            .map(PackageInfo::git);
+
+        let is_git_package = self.is_git_package(package);
+        remote
+            .chain(local)
+            .chain(archive)
+            .filter(|_| !is_git_package)
+            .chain(git)
+            .max_by_key(|x| x.version())
     }
 }
@@ -1,13 +1,15 @@
 use crate::environment::REPO_CACHE_FOLDER;
 use crate::io::{EnvironmentIo, ProjectIo};
 use crate::repository::LocalCachedRepository;
+use crate::repository::file::{file_url_to_path, is_file_url};
 use crate::traits::AbortCheck;
-use crate::utils::Sha256AsyncWrite;
+use crate::utils::{Sha256AsyncWrite, hash_folder_files, hash_zip_files};
 use crate::{HttpClient, PackageInfo, PackageManifest, io};
 use futures::prelude::*;
 use hex::FromHex;
 use indexmap::IndexMap;
 use log::{debug, error};
+use std::collections::BTreeMap;
 use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use std::pin::pin;
@@ -42,41 +44,80 @@ impl<T: HttpClient, IO: EnvironmentIo> crate::PackageInstaller for PackageInstal
                 // downloading may take a long time, so check abort again
                 abort.check()?;
 
-                let zip_file = io::BufReader::new(zip_file);
-
-                debug!(
-                    "Extracting zip file for {}@{}",
-                    package.name(),
-                    package.version()
-                );
-                // remove dest folder before extract if exists
-                if let Err(e) = crate::utils::extract_zip(zip_file, io, &dest_folder).await {
-                    // if an error occurs, try to remove the dest folder
-                    log::debug!(
-                        "Error occurred while extracting zip file for {}@{}: {}",
-                        package.name(),
-                        package.version(),
-                        e
-                    );
-                    let _ = io.remove_dir_all(&dest_folder).await;
-                    return Err(e);
-                }
-                debug!(
-                    "Extracted zip file for {}@{}",
-                    package.name(),
-                    package.version()
-                );
-
-                Ok(())
+                extract_package(io, package, zip_file, &dest_folder).await
             }
             PackageInfoInner::Local(_, path) => {
                 crate::utils::copy_recursive(self.io, path.into(), io, dest_folder).await?;
                 Ok(())
             }
+            PackageInfoInner::Archive(package, path) => {
+                let zip_file = self.io.open(path).await?;
+                extract_package(io, package, zip_file, &dest_folder).await
+            }
+            PackageInfoInner::Git(package) => {
+                let zip_file = crate::git::export_package(self.io, package).await?;
+                extract_package(io, package.manifest(), zip_file, &dest_folder).await
+            }
+        }
+    }
+
+    async fn package_file_hashes(
+        &self,
+        package: PackageInfo<'_>,
+    ) -> io::Result<BTreeMap<Box<str>, Box<str>>> {
+        use crate::PackageInfoInner;
+        match package.inner {
+            PackageInfoInner::Remote(package, user_repo) => {
+                let zip_file = get_package(self.io, self.http, user_repo, package).await?;
+                hash_zip_files(io::BufReader::new(zip_file)).await
+            }
+            PackageInfoInner::Local(_, path) => hash_folder_files(self.io, path).await,
+            PackageInfoInner::Archive(_, path) => {
+                let zip_file = self.io.open(path).await?;
+                hash_zip_files(io::BufReader::new(zip_file)).await
+            }
+            PackageInfoInner::Git(package) => {
+                let zip_file = crate::git::export_package(self.io, package).await?;
+                hash_zip_files(io::BufReader::new(zip_file)).await
+            }
         }
     }
 }
 
+async fn extract_package(
+    io: &impl ProjectIo,
+    package: &PackageManifest,
+    zip_file: impl AsyncRead + AsyncSeek + Unpin,
+    dest_folder: &Path,
+) -> io::Result<()> {
+    let zip_file = io::BufReader::new(zip_file);
+
+    debug!(
+        "Extracting zip file for {}@{}",
+        package.name(),
+        package.version()
+    );
+    // remove dest folder before extract if exists
+    if let Err(e) = crate::utils::extract_zip(zip_file, io, dest_folder).await {
+        // if an error occurs, try to remove the dest folder
+        log::debug!(
+            "Error occurred while extracting zip file for {}@{}: {}",
+            package.name(),
+            package.version(),
+            e
+        );
+        let _ = io.remove_dir_all(dest_folder).await;
+        return Err(e);
+    }
+    debug!(
+        "Extracted zip file for {}@{}",
+        package.name(),
+        package.version()
+    );
+
+    Ok(())
+}
+
 async fn get_package<T: HttpClient, IO: EnvironmentIo>(
     io: &IO,
     http: Option<&T>,
@@ -113,6 +154,26 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
             ),
         );
 
+        let url = package.url().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "URL field of the package.json in the repository empty",
+            )
+        })?;
+
+        // remote repositories must not make us read arbitrary files on the local machine
+        if is_file_url(url) && !repository.url().is_some_and(is_file_url) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is a local file but the repository of {}@{} is not on the file system",
+                    url,
+                    package.name(),
+                    package.version()
+                ),
+            ));
+        }
+
         let (zip_file, zip_hash) = download_package_zip(
             http,
             io,
@@ -120,12 +181,7 @@ async fn get_package<T: HttpClient, IO: EnvironmentIo>(
             &zip_path,
             &sha_path,
             &zip_file_name,
-            package.url().ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "URL field of the package.json in the repository empty",
-                )
-            })?,
+            url,
         )
         .await?;
 
@@ -199,7 +255,7 @@ async fn try_load_package_cache<IO: EnvironmentIo>(
 ///
 /// # Arguments
 ///
-/// * `http`: http client. returns error if none
+/// * `http`: http client. returns error if none unless the url is `file:`
 /// * `zip_path`: the path to zip file
 /// * `sha_path`: the path to sha256 file
 /// * `zip_file_name`: the name of zip file. will be used in the sha file
@@ -215,32 +271,70 @@ async fn download_package_zip<IO: EnvironmentIo>(
     zip_file_name: &str,
     url: &Url,
 ) -> io::Result<(IO::FileStream, [u8; 256 / 8])> {
-    let Some(http) = http else {
-        return Err(io::Error::new(io::ErrorKind::NotFound, "Offline mode"));
+    // packages on the file system are copied to the cache even in offline mode
+    let http = if is_file_url(url) {
+        None
+    } else {
+        Some(http.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Offline mode"))?)
     };
 
-    // file not found: err
-    let cache_file = io.create(zip_path).await?;
+    // download to the temporary files and rename them into place
+    // so concurrent downloads of the same package never see a partially written cache
+    let temp_suffix = format!(".{}.tmp", uuid::Uuid::new_v4().simple());
+    let temp_zip_path = with_suffix(zip_path, &temp_suffix);
+    let temp_sha_path = with_suffix(sha_path, &temp_suffix);
+
+    let result = async {
+        // file not found: err
+        let cache_file = io.create(&temp_zip_path).await?;
+        let mut writer = Sha256AsyncWrite::new(cache_file);
+
+        if let Some(http) = http {
+            debug!("Download started for {}", url);
+            let mut response = pin!(http.get(url, headers).await?);
+            io::copy(&mut response, &mut writer).await?;
+            debug!("finished downloading {}", url);
+        } else {
+            debug!("Copy started for {}", url);
+            let mut source = io.open(&file_url_to_path(url)?).await?;
+            io::copy(&mut source, &mut writer).await?;
+            debug!("finished copying {}", url);
+        }
+
+        let (mut cache_file, hash) = writer.finalize();
+        let hash: [u8; 256 / 8] = hash.into();
+
+        cache_file.flush().await?;
+        drop(cache_file);
 
-    debug!("Download started for {}", url);
-    let mut response = pin!(http.get(url, headers).await?);
+        // write sha file
+        io.write(
+            &temp_sha_path,
+            format!("{} {}\n", hex::encode(&hash[..]), zip_file_name).as_bytes(),
+        )
+        .await?;
 
-    let mut writer = Sha256AsyncWrite::new(cache_file);
-    io::copy(&mut response, &mut writer).await?;
-    debug!("finished downloading {}", url);
+        io.rename(&temp_zip_path, zip_path).await?;
+        io.rename(&temp_sha_path, sha_path).await?;
 
-    let (mut cache_file, hash) = writer.finalize();
-    let hash: [u8; 256 / 8] = hash.into();
+        Ok(hash)
+    }
+    .await;
 
-    cache_file.flush().await?;
-    cache_file.seek(SeekFrom::Start(0)).await?;
+    let hash = match result {
+        Ok(hash) => hash,
+        Err(e) => {
+            io.remove_file(&temp_zip_path).await.ok();
+            io.remove_file(&temp_sha_path).await.ok();
+            return Err(e);
+        }
+    };
 
-    // write sha file
-    io.write(
-        sha_path,
-        format!("{} {}\n", hex::encode(&hash[..]), zip_file_name).as_bytes(),
-    )
-    .await?;
+    Ok((io.open(zip_path).await?, hash))
+}
 
-    Ok((cache_file, hash))
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
 }
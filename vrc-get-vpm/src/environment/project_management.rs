@@ -67,7 +67,7 @@ impl VccDatabaseConnection {
                     path: &Path,
                 ) -> io::Result<(ProjectType, Option<UnityVersion>, Option<String>)>
                 {
-                    let project = UnityProject::load(io.new_project_io(path)).await?;
+                    let project = UnityProject::load(io.new_project_io(path)?).await?;
                     let detected_type = project.detect_project_type().await?;
                     Ok((
                         detected_type,
@@ -178,7 +178,7 @@ impl VccDatabaseConnection {
 
             let mut changed = false;
 
-            let loaded_project = UnityProject::load(io.new_project_io(path)).await?;
+            let loaded_project = UnityProject::load(io.new_project_io(path)?).await?;
             if let Some(unity_version) = loaded_project.unity_version() {
                 let unity_version = unity_version.to_string();
                 if let Some(revision) = loaded_project.unity_revision() {
@@ -3,8 +3,8 @@ use crate::environment::{
     CURATED_URL_STR, LOCAL_CURATED_PATH, LOCAL_OFFICIAL_PATH, OFFICIAL_URL_STR, Settings,
 };
 use crate::io::EnvironmentIo;
-use crate::repository::RemoteRepository;
 use crate::repository::local::LocalCachedRepository;
+use crate::repository::{RemoteRepository, is_file_url};
 use crate::traits::HttpClient;
 use crate::utils::{read_json_file, to_vec_pretty_os_eol, try_load_json};
 use crate::{UserRepoSetting, io};
@@ -14,27 +14,38 @@ use lazy_static::lazy_static;
 use log::{error, warn};
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::{Duration, SystemTime};
 use url::Url;
 
 #[derive(Debug)]
 pub(crate) struct RepoHolder {
     cached_repos_new: HashMap<Box<Path>, LocalCachedRepository>,
+    /// remote repositories fetched within this duration are not fetched again
+    cache_ttl: Duration,
+    /// time limit for fetching each remote repository
+    fetch_timeout: Option<Duration>,
 }
 
 impl RepoHolder {
     pub(crate) fn new() -> Self {
         RepoHolder {
             cached_repos_new: HashMap::new(),
+            cache_ttl: Duration::ZERO,
+            fetch_timeout: None,
         }
     }
 }
 
 // new system
 impl RepoHolder {
+    /// Loads the repositories in the settings.
+    ///
+    /// If `force` is true, all remote repositories are fetched even if the cache is fresh.
     pub(crate) async fn load(
         settings: &Settings,
         io: &impl EnvironmentIo,
         http: Option<&impl HttpClient>,
+        force: bool,
     ) -> io::Result<Self> {
         let predefined_repos = Self::get_predefined_repos(settings).into_iter();
         let user_repos = settings
@@ -43,6 +54,10 @@ impl RepoHolder {
             .map(UserRepoSetting::to_source);
         io.create_dir_all("Repos".as_ref()).await?;
         let mut repo_cache = Self::new();
+        if !force {
+            repo_cache.cache_ttl = settings.repository_cache_ttl();
+        }
+        repo_cache.fetch_timeout = settings.repository_fetch_timeout();
         repo_cache
             .load_repos(http, io, predefined_repos.chain(user_repos))
             .await?;
@@ -89,8 +104,9 @@ impl RepoHolder {
         sources: impl Iterator<Item = RepoSource<'a>>,
     ) -> io::Result<()> {
         let start = std::time::Instant::now();
+        let (cache_ttl, fetch_timeout) = (self.cache_ttl, self.fetch_timeout);
         let repos = join_all(sources.map(|src| async move {
-            match Self::load_repo_from_source(http, io, &src).await {
+            match Self::load_repo_from_source(http, io, &src, cache_ttl, fetch_timeout).await {
                 Ok(Some(v)) => Some((v, src.cache_path().into())),
                 Ok(None) => None,
                 Err(e) => {
@@ -114,11 +130,21 @@ impl RepoHolder {
         client: Option<&impl HttpClient>,
         io: &IO,
         source: &RepoSource<'_>,
+        cache_ttl: Duration,
+        fetch_timeout: Option<Duration>,
     ) -> io::Result<Option<LocalCachedRepository>> {
         if let Some(url) = &source.url() {
-            RepoHolder::load_remote_repo(client, io, source.headers(), source.cache_path(), url)
-                .await
-                .map(Some)
+            RepoHolder::load_remote_repo(
+                client,
+                io,
+                source.headers(),
+                source.cache_path(),
+                url,
+                cache_ttl,
+                fetch_timeout,
+            )
+            .await
+            .map(Some)
         } else {
             RepoHolder::load_local_repo(io, source.cache_path())
                 .await
@@ -132,9 +158,17 @@ impl RepoHolder {
         headers: &IndexMap<Box<str>, Box<str>>,
         path: &Path,
         remote_url: &Url,
+        cache_ttl: Duration,
+        fetch_timeout: Option<Duration>,
     ) -> io::Result<LocalCachedRepository> {
+        if is_file_url(remote_url) {
+            return Self::load_file_repo(io, headers, path, remote_url).await;
+        }
+
         if let Some(mut loaded) = try_load_json::<LocalCachedRepository>(io, path).await? {
-            if let Some(client) = client {
+            if loaded.is_fresh(cache_ttl) {
+                log::debug!("local cache of '{}' is fresh enough", remote_url);
+            } else if let Some(client) = client {
                 // if it's possible to download remote repo, try to update with that
                 log::debug!("downloading remote repo '{}' with local cache", remote_url);
                 match RemoteRepository::download_with_etag(
@@ -142,23 +176,27 @@ impl RepoHolder {
                     remote_url,
                     loaded.headers(),
                     loaded.vrc_get.as_ref().map(|x| x.etag.as_ref()),
+                    fetch_timeout,
                 )
                 .await
                 {
-                    Ok(None) => log::debug!("cache matched downloading '{}'", remote_url),
+                    Ok(None) => {
+                        log::debug!("cache matched downloading '{}'", remote_url);
+                        loaded.set_last_fetched(SystemTime::now());
+                        Self::write_cache(io, path, &loaded).await;
+                    }
                     Ok(Some((remote_repo, etag))) => {
                         log::debug!("downloaded finished '{}'", remote_url);
                         loaded.set_repo(remote_repo);
                         loaded.set_etag(etag);
-
-                        io.write(path, &to_vec_pretty_os_eol(&loaded)?)
-                            .await
-                            .unwrap_or_else(|e| {
-                                error!("writing local repo cache '{}': {}", path.display(), e)
-                            });
+                        loaded.set_last_fetched(SystemTime::now());
+                        Self::write_cache(io, path, &loaded).await;
                     }
                     Err(e) => {
-                        error!("fetching remote repo '{}': {}", remote_url, e);
+                        warn!(
+                            "fetching remote repo '{}' failed, using local cache: {}",
+                            remote_url, e
+                        );
                     }
                 }
             }
@@ -173,24 +211,104 @@ impl RepoHolder {
                 ));
             };
             log::debug!("downloading remote repo '{}'", remote_url);
-            let (remote_repo, etag) =
-                RemoteRepository::download(client, remote_url, headers).await?;
+            let Some((remote_repo, etag)) = RemoteRepository::download_with_etag(
+                client,
+                remote_url,
+                headers,
+                None,
+                fetch_timeout,
+            )
+            .await?
+            else {
+                unreachable!("downloading without etag should must return Some")
+            };
             log::debug!("downloaded finished '{}'", remote_url);
 
             let mut local_cache = LocalCachedRepository::new(remote_repo, headers.clone());
 
             local_cache.set_etag(etag);
+            local_cache.set_last_fetched(SystemTime::now());
 
-            io.write(path, &to_vec_pretty_os_eol(&local_cache)?)
-                .await
-                .unwrap_or_else(|e| {
-                    error!("writing local repo cache '{}': {}", path.display(), e);
-                });
+            Self::write_cache(io, path, &local_cache).await;
 
             Ok(local_cache)
         }
     }
 
+    /// Loads the repository on the file system, which is re-read only if the modification time is changed.
+    ///
+    /// Since reading files is cheap, the cache ttl is not used and this works in offline mode.
+    async fn load_file_repo(
+        io: &impl EnvironmentIo,
+        headers: &IndexMap<Box<str>, Box<str>>,
+        path: &Path,
+        source_url: &Url,
+    ) -> io::Result<LocalCachedRepository> {
+        let mut loaded = try_load_json::<LocalCachedRepository>(io, path).await?;
+
+        let modified = match RemoteRepository::file_modified(io, source_url).await {
+            Ok(modified) => modified,
+            Err(e) => {
+                if let Some(loaded) = loaded {
+                    warn!(
+                        "reading repo '{}' failed, using local cache: {}",
+                        source_url, e
+                    );
+                    return Ok(loaded);
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(loaded) =
+            loaded.take_if(|x| modified.is_some() && x.source_modified() == modified)
+        {
+            log::debug!("local cache of '{}' is up to date", source_url);
+            return Ok(loaded);
+        }
+
+        log::debug!("reading repo '{}'", source_url);
+        let (remote_repo, modified) = match RemoteRepository::read_file(io, source_url).await {
+            Ok(read) => read,
+            Err(e) => {
+                if let Some(loaded) = loaded {
+                    warn!(
+                        "reading repo '{}' failed, using local cache: {}",
+                        source_url, e
+                    );
+                    return Ok(loaded);
+                }
+                return Err(e);
+            }
+        };
+
+        let mut local_cache = match loaded {
+            Some(mut loaded) => {
+                loaded.set_repo(remote_repo);
+                loaded
+            }
+            None => LocalCachedRepository::new(remote_repo, headers.clone()),
+        };
+        local_cache.set_source_modified(modified);
+        local_cache.set_last_fetched(SystemTime::now());
+        Self::write_cache(io, path, &local_cache).await;
+
+        Ok(local_cache)
+    }
+
+    /// Writes the local cache of the repository.
+    ///
+    /// Failing to write is only logged since the loaded repository can still be used.
+    async fn write_cache(io: &impl EnvironmentIo, path: &Path, repo: &LocalCachedRepository) {
+        let result = match to_vec_pretty_os_eol(repo) {
+            Ok(json) => io.write(path, &json).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            error!("writing local repo cache '{}': {}", path.display(), e);
+        }
+    }
+
     async fn load_local_repo(
         io: &impl EnvironmentIo,
         path: &Path,
@@ -0,0 +1,117 @@
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The rule to choose repositories for packages.
+///
+/// When a package is provided by multiple repositories, only the repositories with the highest priority are used.
+/// Repositories not listed in the priority have the lowest priority.
+/// If a package is pinned to a repository, the package is only taken from the repository.
+///
+/// Repositories are identified by the id in the settings, or the url if the repository has no id.
+/// The id declared by the downloaded repository is not trusted,
+/// so a repository cannot take the priority or the pins of another repository by claiming its id.
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryPolicy {
+    /// repository ids, highest priority first
+    priority: Vec<Box<str>>,
+    /// package name pattern to repository id
+    pins: IndexMap<Box<str>, Box<str>>,
+    /// path of the repository cache to the id of the configured repository
+    ids: HashMap<Box<Path>, Box<str>>,
+}
+
+impl RepositoryPolicy {
+    pub fn new(priority: Vec<Box<str>>, pins: IndexMap<Box<str>, Box<str>>) -> Self {
+        Self {
+            priority,
+            pins,
+            ids: HashMap::new(),
+        }
+    }
+
+    /// Sets the id of the repository cached at the path.
+    ///
+    /// Repositories without id are not matched by the priority nor the pins.
+    pub(crate) fn set_repository_id(&mut self, cache_path: &Path, id: &str) {
+        self.ids.insert(cache_path.into(), id.into());
+    }
+
+    /// Returns the id of the repository cached at the path.
+    pub(crate) fn repository_id(&self, cache_path: &Path) -> Option<&str> {
+        self.ids.get(cache_path).map(Box::as_ref)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priority.is_empty() && self.pins.is_empty()
+    }
+
+    pub fn priority(&self) -> &[Box<str>] {
+        &self.priority
+    }
+
+    pub fn pins(&self) -> &IndexMap<Box<str>, Box<str>> {
+        &self.pins
+    }
+
+    /// Returns the id of the repository the package is pinned to.
+    ///
+    /// If multiple patterns match, the longest pattern is used.
+    pub fn pinned_repository(&self, package: &str) -> Option<&str> {
+        self.pins
+            .iter()
+            .filter(|(pattern, _)| pattern_matches(pattern, package))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, repository)| repository.as_ref())
+    }
+
+    /// Returns the priority of the repository. smaller is higher priority.
+    pub(crate) fn priority_of(&self, cache_path: &Path) -> usize {
+        self.repository_id(cache_path)
+            .and_then(|id| self.priority.iter().position(|x| x.as_ref() == id))
+            .unwrap_or(self.priority.len())
+    }
+}
+
+/// Package name pattern. `*` at the end matches any suffix, otherwise the exact name.
+fn pattern_matches(pattern: &str, package: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => package.starts_with(prefix),
+        None => pattern == package,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_repository() {
+        let policy = RepositoryPolicy::new(
+            vec![],
+            IndexMap::from([
+                ("com.vrchat.*".into(), "com.vrchat.repos.official".into()),
+                (
+                    "com.vrchat.core.*".into(),
+                    "com.vrchat.repos.curated".into(),
+                ),
+                ("com.anatawa12.gists".into(), "com.anatawa12.vpm".into()),
+            ]),
+        );
+
+        assert_eq!(
+            policy.pinned_repository("com.vrchat.avatars"),
+            Some("com.vrchat.repos.official")
+        );
+        assert_eq!(
+            policy.pinned_repository("com.vrchat.core.vpm-resolver"),
+            Some("com.vrchat.repos.curated")
+        );
+        assert_eq!(
+            policy.pinned_repository("com.anatawa12.gists"),
+            Some("com.anatawa12.vpm")
+        );
+        assert_eq!(policy.pinned_repository("com.anatawa12.gists2"), None);
+        assert_eq!(policy.pinned_repository("com.vrchat"), None);
+    }
+}
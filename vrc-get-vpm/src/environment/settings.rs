@@ -1,13 +1,17 @@
 use std::collections::HashSet;
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use indexmap::IndexMap;
 use url::Url;
 
 use crate::environment::vpm_settings::VpmSettings;
 use crate::environment::vrc_get_settings::VrcGetSettings;
-use crate::environment::{AddUserPackageResult, PackageCollection};
+use crate::environment::{
+    AddUserPackageResult, LOCAL_CURATED_PATH, LOCAL_OFFICIAL_PATH, PackageCollection,
+    RepositoryPolicy,
+};
 use crate::io::EnvironmentIo;
 use crate::package_manifest::LooseManifest;
 use crate::repository::RemoteRepository;
@@ -34,6 +38,7 @@ impl Settings {
 
     pub async fn save(&self, io: &impl EnvironmentIo) -> io::Result<()> {
         self.vpm.save(io).await?;
+        self.vrc_get.save(io).await?;
 
         Ok(())
     }
@@ -57,6 +62,11 @@ impl Settings {
         self.vpm.set_default_project_path(value);
     }
 
+    /// Resets the default project path so that the default location of vrc-get or VCC is used.
+    pub fn clear_default_project_path(&mut self) {
+        self.vpm.clear_default_project_path();
+    }
+
     pub fn project_backup_path(&self) -> Option<&str> {
         self.vpm.project_backup_path()
     }
@@ -65,6 +75,11 @@ impl Settings {
         self.vpm.set_project_backup_path(value);
     }
 
+    /// Resets the backup path so that the default location of vrc-get or VCC is used.
+    pub fn clear_project_backup_path(&mut self) {
+        self.vpm.clear_project_backup_path();
+    }
+
     pub fn unity_hub_path(&self) -> &str {
         self.vpm.unity_hub()
     }
@@ -128,6 +143,144 @@ impl Settings {
     pub fn ignore_official_repository(&self) -> bool {
         self.vrc_get.ignore_official_repository()
     }
+
+    pub fn set_ignore_curated_repository(&mut self, value: bool) {
+        self.vrc_get.set_ignore_curated_repository(value);
+    }
+
+    pub fn set_ignore_official_repository(&mut self, value: bool) {
+        self.vrc_get.set_ignore_official_repository(value);
+    }
+
+    /// The folders to find Unity editors in.
+    ///
+    /// If not configured, the default install location of Unity Hub is returned.
+    pub fn unity_search_roots(&self) -> Vec<PathBuf> {
+        match self.vrc_get.unity_search_roots() {
+            Some(roots) => roots.to_vec(),
+            None => default_unity_search_roots(),
+        }
+    }
+
+    pub fn add_unity_search_root(&mut self, path: &Path) -> bool {
+        let mut roots = self.unity_search_roots();
+        if roots.iter().any(|x| x == path) {
+            return false;
+        }
+        roots.push(path.to_owned());
+        self.vrc_get.set_unity_search_roots(roots);
+        true
+    }
+
+    pub fn remove_unity_search_root(&mut self, path: &Path) -> bool {
+        let mut roots = self.unity_search_roots();
+        let len = roots.len();
+        roots.retain(|x| x != path);
+        if roots.len() == len {
+            return false;
+        }
+        self.vrc_get.set_unity_search_roots(roots);
+        true
+    }
+}
+
+/// Repository priority and package pins (vrc-get extensions)
+impl Settings {
+    /// The ids of repositories in order of priority, highest first.
+    pub fn repository_priority(&self) -> &[Box<str>] {
+        self.vrc_get.repository_priority()
+    }
+
+    pub fn set_repository_priority(&mut self, priority: Vec<Box<str>>) {
+        self.vrc_get.set_repository_priority(priority);
+    }
+
+    /// The package name patterns and the ids of repositories the packages are pinned to.
+    pub fn package_pins(&self) -> &IndexMap<Box<str>, Box<str>> {
+        self.vrc_get.package_pins()
+    }
+
+    /// Pins packages matching the pattern to the repository.
+    ///
+    /// `*` at the end of the pattern matches any suffix like `com.vrchat.*`.
+    pub fn set_package_pin(&mut self, pattern: &str, repository: &str) {
+        self.vrc_get.set_package_pin(pattern, repository);
+    }
+
+    pub fn remove_package_pin(&mut self, pattern: &str) -> bool {
+        self.vrc_get.remove_package_pin(pattern)
+    }
+
+    /// The repository policy with the ids of the configured repositories.
+    ///
+    /// The ids are taken from the settings, which are checked not to conflict when the repositories are added,
+    /// instead of the ids declared by the downloaded repositories.
+    pub fn repository_policy(&self) -> RepositoryPolicy {
+        let mut policy = RepositoryPolicy::new(
+            self.repository_priority().to_vec(),
+            self.package_pins().clone(),
+        );
+        policy.set_repository_id(LOCAL_OFFICIAL_PATH.as_ref(), "com.vrchat.repos.official");
+        policy.set_repository_id(LOCAL_CURATED_PATH.as_ref(), "com.vrchat.repos.curated");
+        for repo in self.get_user_repos() {
+            if let Some(id) = repo.id().or(repo.url().map(Url::as_str)) {
+                policy.set_repository_id(repo.local_path(), id);
+            }
+        }
+        policy
+    }
+}
+
+/// Repository cache freshness (vrc-get extensions)
+impl Settings {
+    /// How long the cache of remote repositories is used without fetching.
+    ///
+    /// Zero by default, which means the repositories are fetched every time.
+    pub fn repository_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.vrc_get.repository_cache_ttl().unwrap_or(0))
+    }
+
+    pub fn set_repository_cache_ttl(&mut self, ttl: Duration) {
+        self.vrc_get.set_repository_cache_ttl(Some(ttl.as_secs()));
+    }
+
+    /// Removes the cache ttl from the settings so that the default is used.
+    pub fn clear_repository_cache_ttl(&mut self) {
+        self.vrc_get.set_repository_cache_ttl(None);
+    }
+
+    /// The time limit to fetch each remote repository.
+    ///
+    /// If fetching a repository takes longer than this, the cached one is used.
+    pub fn repository_fetch_timeout(&self) -> Option<Duration> {
+        self.vrc_get
+            .repository_fetch_timeout()
+            .map(Duration::from_secs)
+    }
+
+    pub fn set_repository_fetch_timeout(&mut self, timeout: Option<Duration>) {
+        self.vrc_get
+            .set_repository_fetch_timeout(timeout.map(|x| x.as_secs()));
+    }
+}
+
+/// The default location Unity Hub installs editors to
+fn default_unity_search_roots() -> Vec<PathBuf> {
+    #[cfg(windows)]
+    {
+        vec![PathBuf::from(r"C:\Program Files\Unity\Hub\Editor")]
+    }
+    #[cfg(target_os = "macos")]
+    {
+        vec![PathBuf::from("/Applications/Unity/Hub/Editor")]
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        match std::env::var_os("HOME") {
+            Some(home) => vec![PathBuf::from(home).join("Unity/Hub/Editor")],
+            None => vec![],
+        }
+    }
 }
 
 /// User Package Managements
@@ -174,6 +327,24 @@ impl Settings {
         self.vpm.user_repos()
     }
 
+    /// Returns true if the repository with the url is already added, including predefined repositories.
+    pub fn has_remote_repo(&self, url: &Url) -> bool {
+        if self.get_user_repos().iter().any(|x| x.url() == Some(url)) {
+            return true;
+        }
+        if !self.ignore_curated_repository()
+            && url.as_str() == "https://packages.vrchat.com/curated?download"
+        {
+            return true;
+        }
+        if !self.ignore_official_repository()
+            && url.as_str() == "https://packages.vrchat.com/official?download"
+        {
+            return true;
+        }
+        false
+    }
+
     pub fn can_add_remote_repo(&self, url: &Url, remote_repo: &RemoteRepository) -> bool {
         let user_repos = self.get_user_repos();
         if user_repos.iter().any(|x| x.url() == Some(url)) {
@@ -4,7 +4,7 @@ use crate::io::EnvironmentIo;
 use crate::unity_hub::get_executable_path;
 use crate::utils::{check_absolute_path, normalize_path};
 use crate::version::UnityVersion;
-use futures::TryStreamExt;
+use futures::prelude::*;
 use log::info;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -107,7 +107,28 @@ impl VccDatabaseConnection {
         path_and_version_from_hub: &[(UnityVersion, PathBuf)],
         io: &impl EnvironmentIo,
     ) -> io::Result<()> {
+        self.update_unity_installations(Some(path_and_version_from_hub), &[], io)
+            .await
+    }
+
+    /// Updates the Unity list with Unity Hub, search roots, and file system.
+    ///
+    /// Unity editors that no longer exist are removed, and Unity editors from Unity Hub
+    /// or found by [`find_unity_in_search_roots`](super::find_unity_in_search_roots) are added.
+    /// If `path_and_version_from_hub` is `None`, whether loaded from Unity Hub is kept as is.
+    pub async fn update_unity_installations(
+        &mut self,
+        path_and_version_from_hub: Option<&[(UnityVersion, PathBuf)]>,
+        path_and_version_from_search_roots: &[(UnityVersion, PathBuf)],
+        io: &impl EnvironmentIo,
+    ) -> io::Result<()> {
+        let update_loaded_from_hub = path_and_version_from_hub.is_some();
         let path_and_version_from_hub = path_and_version_from_hub
+            .unwrap_or_default()
+            .iter()
+            .map(|(version, path)| (version, get_executable_path(path)))
+            .collect::<Vec<_>>();
+        let path_and_version_from_search_roots = path_and_version_from_search_roots
             .iter()
             .map(|(version, path)| (version, get_executable_path(path)))
             .collect::<Vec<_>>();
@@ -162,7 +183,9 @@ impl VccDatabaseConnection {
                         changed = true;
                     }
 
-                    if Some(exists_in_hub) != in_db[LOADED_FROM_HUB].as_bool() {
+                    if update_loaded_from_hub
+                        && Some(exists_in_hub) != in_db[LOADED_FROM_HUB].as_bool()
+                    {
                         in_db.insert(LOADED_FROM_HUB, exists_in_hub);
                         changed = true;
                     }
@@ -190,6 +213,26 @@ impl VccDatabaseConnection {
                         }
                         info!("Adding Unity from Unity Hub: {}", path);
                         Self::add_unity_installation_internal(db, path, version, true).await?;
+                        registered.insert(path.to_string());
+                    }
+                }
+
+                for &(&version, ref path) in &path_and_version_from_search_roots {
+                    let Some(path) = path.as_os_str().to_str() else {
+                        info!(
+                            "Ignoring Unity from search roots since non-utf8 path: {}",
+                            path.display()
+                        );
+                        continue;
+                    };
+                    if !registered.contains(path) {
+                        if version < UnityVersion::new_f1(2019, 4, 0) {
+                            info!("Ignoring Unity from search roots since old: {}", path);
+                            continue;
+                        }
+                        info!("Adding Unity from search roots: {}", path);
+                        Self::add_unity_installation_internal(db, path, version, false).await?;
+                        registered.insert(path.to_string());
                     }
                 }
 
@@ -0,0 +1,113 @@
+use crate::io;
+use crate::io::{DirEntry, EnvironmentIo};
+use crate::unity::call_unity_for_version;
+use crate::unity_hub::{get_executable_path, load_unity_version};
+use crate::version::UnityVersion;
+use futures::prelude::*;
+use lazy_static::lazy_static;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+lazy_static! {
+    /// The versions of the editors found in the search roots, keyed by the path to the executable.
+    /// The modified time of the executable is kept to detect the editor replaced by another version.
+    static ref VERSION_CACHE: Mutex<HashMap<PathBuf, (SystemTime, UnityVersion)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Finds Unity editors installed in the search roots.
+///
+/// A search root is expected to be a folder like the install location of Unity Hub,
+/// which has a folder for each editor version, or a folder of an editor itself.
+///
+/// The version is read from the metadata of the editor like Unity Hub lookup does,
+/// and the editor is launched to get the version only if the metadata has no version.
+/// The versions are cached with the modified time of the editor,
+/// so each editor is checked only once until it's updated.
+pub async fn find_unity_in_search_roots(
+    io: &impl EnvironmentIo,
+    roots: &[PathBuf],
+) -> Vec<(UnityVersion, PathBuf)> {
+    let mut found = Vec::new();
+
+    for root in roots {
+        let mut candidates = vec![root.clone()];
+
+        match io.read_dir(root).await {
+            Ok(read_dir) => {
+                let mut read_dir = pin!(read_dir);
+                while let Some(entry) = read_dir.next().await {
+                    let Ok(entry) = entry else { continue };
+                    if entry.file_type().await.is_ok_and(|x| x.is_dir()) {
+                        candidates.push(root.join(entry.file_name()));
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                info!("Unity search root not found: {}", root.display());
+                continue;
+            }
+            Err(e) => {
+                warn!("Error reading Unity search root {}: {e}", root.display());
+                continue;
+            }
+        }
+
+        for candidate in candidates {
+            let Some(editor) = find_editor_in_folder(io, &candidate).await else {
+                continue;
+            };
+            match load_editor_version(io, &editor).await {
+                Ok(version) => found.push((version, editor)),
+                Err(e) => warn!("Error getting version of {}: {e}", editor.display()),
+            }
+        }
+    }
+
+    found
+}
+
+/// Returns the path to the editor in the folder.
+///
+/// The returned path is app bundle on macOS, and executable file on other platforms.
+async fn find_editor_in_folder(io: &impl EnvironmentIo, folder: &Path) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let editor = folder.join("Unity.app");
+    #[cfg(windows)]
+    let editor = folder.join("Editor/Unity.exe");
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let editor = folder.join("Editor/Unity");
+
+    if io.is_file(&get_executable_path(&editor)).await {
+        Some(editor)
+    } else {
+        None
+    }
+}
+
+async fn load_editor_version(io: &impl EnvironmentIo, editor: &Path) -> io::Result<UnityVersion> {
+    let executable = io.resolve(&get_executable_path(editor));
+    let modified = io.metadata(&executable).await?.modified();
+
+    if let Some(modified) = modified {
+        let cache = VERSION_CACHE.lock().unwrap();
+        if let Some(&(_, version)) = cache.get(&executable).filter(|(m, _)| *m == modified) {
+            return Ok(version);
+        }
+    }
+
+    let version = match load_unity_version(editor).await {
+        Ok(version) => version,
+        Err(_) => call_unity_for_version(&executable).await?,
+    };
+
+    if let Some(modified) = modified {
+        (VERSION_CACHE.lock().unwrap()).insert(executable, (modified, version));
+    }
+
+    Ok(version)
+}
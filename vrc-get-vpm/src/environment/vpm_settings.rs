@@ -148,6 +148,10 @@ impl VpmSettings {
         self.parsed.default_project_path = Some(value.into());
     }
 
+    pub(crate) fn clear_default_project_path(&mut self) {
+        self.parsed.default_project_path = None;
+    }
+
     pub(crate) fn project_backup_path(&self) -> Option<&str> {
         self.parsed.project_backup_path.as_deref()
     }
@@ -156,6 +160,10 @@ impl VpmSettings {
         self.parsed.project_backup_path = Some(value.into());
     }
 
+    pub(crate) fn clear_project_backup_path(&mut self) {
+        self.parsed.project_backup_path = None;
+    }
+
     pub(crate) fn unity_hub(&self) -> &str {
         &self.parsed.path_to_unity_hub
     }
@@ -1,9 +1,12 @@
 use crate::io;
 use crate::io::EnvironmentIo;
-use crate::utils::read_json_file;
+use crate::utils::{read_json_file, save_json};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
 
-/// since this file is vrc-get specific, additional keys can be removed
+/// unknown keys are kept so that settings of newer vrc-get are not lost
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct AsJson {
@@ -11,11 +14,32 @@ struct AsJson {
     ignore_official_repository: bool,
     #[serde(default)]
     ignore_curated_repository: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unity_search_roots: Option<Vec<PathBuf>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    repository_priority: Vec<Box<str>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    package_pins: IndexMap<Box<str>, Box<str>>,
+    /// in seconds
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository_cache_ttl: Option<u64>,
+    /// in seconds
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository_fetch_timeout: Option<u64>,
+
+    #[serde(flatten)]
+    rest: Map<String, Value>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct VrcGetSettings {
     parsed: AsJson,
+    changed: bool,
 }
 
 const JSON_PATH: &str = "vrc-get/settings.json";
@@ -33,7 +57,18 @@ impl VrcGetSettings {
             Err(e) => return Err(e),
         };
 
-        Ok(Self { parsed })
+        Ok(Self {
+            parsed,
+            changed: false,
+        })
+    }
+
+    pub async fn save(&self, io: &impl EnvironmentIo) -> io::Result<()> {
+        // do not create the file if nothing is changed
+        if !self.changed {
+            return Ok(());
+        }
+        save_json(io, JSON_PATH.as_ref(), &self.parsed).await
     }
 
     pub fn ignore_official_repository(&self) -> bool {
@@ -43,4 +78,67 @@ impl VrcGetSettings {
     pub fn ignore_curated_repository(&self) -> bool {
         self.parsed.ignore_curated_repository
     }
+
+    pub fn set_ignore_official_repository(&mut self, value: bool) {
+        self.parsed.ignore_official_repository = value;
+        self.changed = true;
+    }
+
+    pub fn set_ignore_curated_repository(&mut self, value: bool) {
+        self.parsed.ignore_curated_repository = value;
+        self.changed = true;
+    }
+
+    pub fn unity_search_roots(&self) -> Option<&[PathBuf]> {
+        self.parsed.unity_search_roots.as_deref()
+    }
+
+    pub fn set_unity_search_roots(&mut self, roots: Vec<PathBuf>) {
+        self.parsed.unity_search_roots = Some(roots);
+        self.changed = true;
+    }
+
+    pub fn repository_priority(&self) -> &[Box<str>] {
+        &self.parsed.repository_priority
+    }
+
+    pub fn set_repository_priority(&mut self, priority: Vec<Box<str>>) {
+        self.parsed.repository_priority = priority;
+        self.changed = true;
+    }
+
+    pub fn package_pins(&self) -> &IndexMap<Box<str>, Box<str>> {
+        &self.parsed.package_pins
+    }
+
+    pub fn set_package_pin(&mut self, pattern: &str, repository: &str) {
+        self.parsed
+            .package_pins
+            .insert(pattern.into(), repository.into());
+        self.changed = true;
+    }
+
+    pub fn remove_package_pin(&mut self, pattern: &str) -> bool {
+        let removed = self.parsed.package_pins.shift_remove(pattern).is_some();
+        self.changed |= removed;
+        removed
+    }
+
+    pub fn repository_cache_ttl(&self) -> Option<u64> {
+        self.parsed.repository_cache_ttl
+    }
+
+    pub fn set_repository_cache_ttl(&mut self, ttl: Option<u64>) {
+        self.parsed.repository_cache_ttl = ttl;
+        self.changed = true;
+    }
+
+    pub fn repository_fetch_timeout(&self) -> Option<u64> {
+        self.parsed.repository_fetch_timeout
+    }
+
+    pub fn set_repository_fetch_timeout(&mut self, timeout: Option<u64>) {
+        self.parsed.repository_fetch_timeout = timeout;
+        self.changed = true;
+    }
 }
@@ -0,0 +1,420 @@
+//! Packages in git repositories that are not published to any VPM repository.
+//!
+//! The repository is specified with `git+<url>#<ref>` for installing, or with `git` field of the dependency
+//! in `vpm-manifest.json` like `"git": "https://example.com/package.git"`.
+//! Without ref, the versions of the package are the tags named like `1.0.0` or `v1.0.0`.
+//! With ref, which is a tag or commit, the package at the ref is used.
+//!
+//! The repositories are cloned as bare repositories in `Repos/git` of the environment folder,
+//! and the packages are installed by exporting the tree of the commit.
+//! Operations on the repositories require `git` command and the `git` feature.
+
+use crate::PackageManifest;
+use std::fmt;
+use std::path::Path;
+
+/// The git repository with an optional tag or commit to get the package from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GitSource {
+    url: Box<str>,
+    reference: Option<Box<str>>,
+}
+
+impl GitSource {
+    /// The prefix for package ids to install from git repository, like `git+https://example.com/package.git`
+    pub const PREFIX: &'static str = "git+";
+
+    /// Parses the source like `https://example.com/package.git#v1.0.0`.
+    ///
+    /// The `git+` prefix is removed if exists.
+    pub fn parse(source: &str) -> Self {
+        let source = source.strip_prefix(Self::PREFIX).unwrap_or(source);
+        match source.rsplit_once('#') {
+            Some((url, reference)) if !reference.is_empty() => Self {
+                url: url.into(),
+                reference: Some(reference.into()),
+            },
+            Some((url, _)) => Self {
+                url: url.into(),
+                reference: None,
+            },
+            None => Self {
+                url: source.into(),
+                reference: None,
+            },
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The tag or commit specified for the source
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+}
+
+impl fmt::Display for GitSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reference {
+            Some(reference) => write!(f, "{}#{}", self.url, reference),
+            None => write!(f, "{}", self.url),
+        }
+    }
+}
+
+/// The package at a commit of the git repository.
+#[derive(Debug, Clone)]
+pub struct GitPackage {
+    manifest: PackageManifest,
+    source: GitSource,
+    commit: Box<str>,
+    /// The absolute path to the bare repository in the cache
+    repository: Box<Path>,
+}
+
+impl GitPackage {
+    pub fn manifest(&self) -> &PackageManifest {
+        &self.manifest
+    }
+
+    /// The source the package is loaded from, which will be saved to `vpm-manifest.json`.
+    pub fn source(&self) -> &GitSource {
+        &self.source
+    }
+
+    /// The full hash of the commit
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    pub fn repository(&self) -> &Path {
+        &self.repository
+    }
+}
+
+#[cfg(feature = "git")]
+pub use repository::*;
+
+#[cfg(feature = "git")]
+mod repository {
+    use super::{GitPackage, GitSource};
+    use crate::environment::REPO_CACHE_FOLDER;
+    use crate::io::EnvironmentIo;
+    use crate::package_manifest::LooseManifest;
+    use crate::utils::read_json_file;
+    use crate::version::Version;
+    use crate::{PackageManifest, io};
+    use log::{debug, warn};
+    use sha2::{Digest, Sha256};
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use tokio::process::Command;
+
+    const GIT_CACHE_FOLDER: &str = "git";
+
+    /// The bare repository cloned in the cache folder.
+    #[derive(Debug)]
+    pub struct GitRepository {
+        url: Box<str>,
+        /// The absolute path to the bare repository
+        path: Box<Path>,
+    }
+
+    fn cache_name(url: &str) -> String {
+        let hash = Sha256::digest(url.as_bytes());
+        hex::encode(&hash[..8])
+    }
+
+    async fn git(args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> io::Result<Vec<u8>> {
+        let mut command = Command::new("git");
+        command
+            .args(args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            // transports like ext:: can run arbitrary commands so only well-known ones are allowed
+            .env("GIT_ALLOW_PROTOCOL", "https:ssh:file");
+        debug!("running {command:?}");
+
+        let output = match command.output().await {
+            Ok(output) => output,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "git command not found. git is required for packages in git repositories",
+                ));
+            }
+            Err(e) => return Err(e),
+        };
+
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "git exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+
+    impl GitRepository {
+        /// Clones the repository into the cache folder if not cloned yet, or fetches the repository if `fetch` is true.
+        ///
+        /// If the repository is not cloned yet and `fetch` is false, this fails with [`io::ErrorKind::NotFound`].
+        pub async fn open(io: &impl EnvironmentIo, url: &str, fetch: bool) -> io::Result<Self> {
+            // the url would be parsed as an option of git
+            if url.starts_with('-') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid git repository url: {url}"),
+                ));
+            }
+
+            let folder = format!("{REPO_CACHE_FOLDER}/{GIT_CACHE_FOLDER}");
+            let relative = format!("{folder}/{}.git", cache_name(url));
+            let path = io.resolve(relative.as_ref());
+
+            if io.is_dir(relative.as_ref()).await {
+                if fetch {
+                    debug!("fetching git repository {url}");
+                    git([
+                        OsStr::new("-C"),
+                        path.as_os_str(),
+                        OsStr::new("fetch"),
+                        OsStr::new("--quiet"),
+                        OsStr::new("--prune"),
+                        OsStr::new("--force"),
+                        OsStr::new("origin"),
+                        OsStr::new("+refs/heads/*:refs/heads/*"),
+                        OsStr::new("+refs/tags/*:refs/tags/*"),
+                    ])
+                    .await?;
+                }
+            } else {
+                if !fetch {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("git repository {url} is not cloned yet and fetching is disabled"),
+                    ));
+                }
+                debug!("cloning git repository {url}");
+                io.create_dir_all(folder.as_ref()).await?;
+                git([
+                    OsStr::new("clone"),
+                    OsStr::new("--quiet"),
+                    OsStr::new("--bare"),
+                    OsStr::new("--"),
+                    OsStr::new(url),
+                    path.as_os_str(),
+                ])
+                .await?;
+            }
+
+            Ok(Self {
+                url: url.into(),
+                path: path.into_boxed_path(),
+            })
+        }
+
+        pub fn url(&self) -> &str {
+            &self.url
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        async fn git(&self, args: &[&str]) -> io::Result<Vec<u8>> {
+            let prefix = [OsStr::new("-C"), self.path.as_os_str()];
+            git(prefix.into_iter().chain(args.iter().map(OsStr::new))).await
+        }
+
+        /// Returns the versions from the tags with the commit of each tag.
+        ///
+        /// Tags not named like `1.0.0` or `v1.0.0` are ignored.
+        pub async fn version_tags(&self) -> io::Result<Vec<(Version, Box<str>)>> {
+            let output = self
+                .git(&[
+                    "for-each-ref",
+                    "--format=%(refname:strip=2)%09%(objectname)%09%(*objectname)",
+                    "refs/tags",
+                ])
+                .await?;
+            let output = String::from_utf8_lossy(&output);
+
+            let mut tags = Vec::new();
+            for line in output.lines() {
+                let mut fields = line.split('\t');
+                let (Some(tag), Some(object), peeled) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    continue;
+                };
+                let version = tag.strip_prefix('v').unwrap_or(tag);
+                let Ok(version) = Version::from_str(version) else {
+                    continue;
+                };
+                // annotated tags point to the tag object so use the commit peeled
+                let commit = peeled.filter(|x| !x.is_empty()).unwrap_or(object);
+                tags.push((version, commit.into()));
+            }
+
+            Ok(tags)
+        }
+
+        /// Returns the full hash of the commit for the tag, branch, or (abbreviated) commit hash.
+        pub async fn resolve_commit(&self, reference: &str) -> io::Result<Box<str>> {
+            if reference.starts_with('-') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid git reference: {reference}"),
+                ));
+            }
+            let output = self
+                .git(&[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    "--end-of-options",
+                    &format!("{reference}^{{commit}}"),
+                ])
+                .await
+                .map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("{reference} not found in {}", self.url),
+                    )
+                })?;
+            Ok(String::from_utf8_lossy(&output).trim().into())
+        }
+
+        /// Reads `package.json` at the root of the commit.
+        pub async fn read_manifest(&self, commit: &str) -> io::Result<PackageManifest> {
+            let output = self
+                .git(&["cat-file", "blob", &format!("{commit}:package.json")])
+                .await?;
+            let path = PathBuf::from(format!("{}#{commit}/package.json", self.url));
+            let LooseManifest(manifest) = read_json_file(output.as_slice(), &path).await?;
+            Ok(manifest)
+        }
+
+        /// Loads the packages of the source in this repository.
+        ///
+        /// With a ref, the package at the ref is loaded. Without a ref, the packages at all version tags are loaded.
+        /// If `locked_commit` is specified, the package at the commit is loaded and
+        /// takes precedence over the tag with the same version.
+        /// If `name` is specified, packages with other names are ignored.
+        pub async fn load_packages(
+            &self,
+            source: &GitSource,
+            name: Option<&str>,
+            locked_commit: Option<&str>,
+        ) -> io::Result<Vec<GitPackage>> {
+            let mut packages = Vec::<GitPackage>::new();
+
+            let mut commits = Vec::new();
+            if let Some(commit) = locked_commit {
+                commits.push((None, self.resolve_commit(commit).await?));
+            }
+            match source.reference() {
+                Some(reference) => commits.push((None, self.resolve_commit(reference).await?)),
+                None => {
+                    for (version, commit) in self.version_tags().await? {
+                        commits.push((Some(version), commit));
+                    }
+                }
+            }
+
+            for (tag_version, commit) in commits {
+                if packages.iter().any(|x| *x.commit == *commit) {
+                    continue;
+                }
+                let manifest = match self.read_manifest(&commit).await {
+                    Ok(manifest) => manifest,
+                    Err(e) => {
+                        warn!("reading package.json at {commit} of {}: {e}", self.url);
+                        continue;
+                    }
+                };
+                if name.is_some_and(|name| name != manifest.name()) {
+                    warn!(
+                        "package at {commit} of {} is {}, not {}",
+                        self.url,
+                        manifest.name(),
+                        name.unwrap()
+                    );
+                    continue;
+                }
+                if let Some(tag_version) = tag_version.filter(|x| x != manifest.version()) {
+                    warn!(
+                        "version in package.json at tag {tag_version} of {} is {}",
+                        self.url,
+                        manifest.version()
+                    );
+                    continue;
+                }
+                if packages
+                    .iter()
+                    .any(|x| x.manifest.version() == manifest.version())
+                {
+                    continue;
+                }
+
+                packages.push(GitPackage {
+                    manifest,
+                    source: source.clone(),
+                    commit,
+                    repository: self.path.clone(),
+                });
+            }
+
+            Ok(packages)
+        }
+    }
+
+    /// Exports the tree of the commit as a zip file in the cache folder and opens it.
+    pub(crate) async fn export_package<IO: EnvironmentIo>(
+        io: &IO,
+        package: &GitPackage,
+    ) -> io::Result<IO::FileStream> {
+        let folder = format!("{REPO_CACHE_FOLDER}/{GIT_CACHE_FOLDER}");
+        let relative = format!(
+            "{folder}/vrc-get-{}-{}.zip",
+            cache_name(package.source().url()),
+            package.commit()
+        );
+
+        // the tree of the commit never changes so the exported zip can be reused
+        if !io.is_file(relative.as_ref()).await {
+            io.create_dir_all(folder.as_ref()).await?;
+            let path = io.resolve(relative.as_ref());
+            let temp = path.with_extension("zip.tmp");
+            git([
+                OsStr::new("-C"),
+                package.repository().as_os_str(),
+                OsStr::new("archive"),
+                OsStr::new("--format=zip"),
+                OsStr::new("-o"),
+                temp.as_os_str(),
+                OsStr::new(package.commit()),
+            ])
+            .await?;
+            tokio::fs::rename(&temp, &path).await?;
+        }
+
+        io.open(relative.as_ref()).await
+    }
+}
+
+#[cfg(not(feature = "git"))]
+pub(crate) async fn export_package<IO: crate::io::EnvironmentIo>(
+    _: &IO,
+    _: &GitPackage,
+) -> crate::io::Result<IO::FileStream> {
+    Err(crate::io::Error::new(
+        crate::io::ErrorKind::Unsupported,
+        "git packages are not supported in this build",
+    ))
+}
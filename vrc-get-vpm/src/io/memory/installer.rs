@@ -0,0 +1,92 @@
+use crate::io::ProjectIo;
+use crate::utils::{MapResultExt, hash_zip_files};
+use crate::version::Version;
+use crate::{AbortCheck, PackageInfo, io};
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// The [`PackageInstaller`](crate::PackageInstaller) extracting the zip files registered in advance.
+///
+/// This installs packages the same way as [`environment::PackageInstaller`] does with downloaded
+/// zip files, without any network access or the environment folder.
+/// Installing a package not registered will fail with [`NotFound`](io::ErrorKind::NotFound).
+///
+/// [`environment::PackageInstaller`]: crate::environment::PackageInstaller
+#[derive(Default)]
+pub struct MemoryPackageInstaller {
+    packages: HashMap<(Box<str>, Version), Vec<u8>>,
+}
+
+impl MemoryPackageInstaller {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the zip file for the version of the package
+    pub fn add_package(&mut self, name: &str, version: Version, zip: Vec<u8>) {
+        self.packages.insert((name.into(), version), zip);
+    }
+
+    fn get_zip(&self, package: &PackageInfo<'_>) -> io::Result<&[u8]> {
+        self.packages
+            .get(&(package.name().into(), package.version().clone()))
+            .map(Vec::as_slice)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "package {}@{} is not registered",
+                        package.name(),
+                        package.version()
+                    ),
+                )
+            })
+    }
+}
+
+impl crate::PackageInstaller for MemoryPackageInstaller {
+    async fn install_package(
+        &self,
+        io: &impl ProjectIo,
+        package: PackageInfo<'_>,
+        abort: &AbortCheck,
+    ) -> io::Result<()> {
+        abort.check()?;
+        let zip = self.get_zip(&package)?;
+
+        let dest_folder = PathBuf::from(format!("Packages/{}", package.name()));
+        let zip_file = io::BufReader::new(futures::io::Cursor::new(zip));
+        if let Err(e) = crate::utils::extract_zip(zip_file, io, &dest_folder).await {
+            // like the real installer, do not leave the partially extracted package
+            let _ = io.remove_dir_all(&dest_folder).await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    async fn package_file_hashes(
+        &self,
+        package: PackageInfo<'_>,
+    ) -> io::Result<BTreeMap<Box<str>, Box<str>>> {
+        let zip = self.get_zip(&package)?;
+        hash_zip_files(io::BufReader::new(futures::io::Cursor::new(zip))).await
+    }
+}
+
+/// Creates the zip file with the files without compression.
+///
+/// The path ending with `/` will be added as a directory.
+pub async fn create_zip(files: &[(&str, impl AsRef<[u8]>)]) -> io::Result<Vec<u8>> {
+    let mut writer = ZipFileWriter::new(Vec::new());
+    for (name, content) in files {
+        let entry = ZipEntryBuilder::new((*name).into(), Compression::Stored);
+        writer
+            .write_entry_whole(entry, content.as_ref())
+            .await
+            .err_mapped()?;
+    }
+    writer.close().await.err_mapped()
+}
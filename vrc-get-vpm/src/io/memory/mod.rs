@@ -1,3 +1,4 @@
+use crate::io::{EnvironmentIo, FileType, Metadata, ProjectIo};
 use futures::Stream;
 use indexmap::IndexMap;
 use indexmap::map::Entry as IndexEntry;
@@ -8,10 +9,15 @@ use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::SystemTime;
 use std::{error, io};
-use vrc_get_vpm::io::{EnvironmentIo, ExitStatus, FileType, IoTrait, Metadata, ProjectIo};
 
-pub(crate) use file_stream::*;
+mod installer;
+mod snapshot;
+
+pub use file_stream::FileStream;
+pub use installer::{MemoryPackageInstaller, create_zip};
+pub use snapshot::{Snapshot, SnapshotDiff, SnapshotEntry};
 
 const NOTA_DIRECTORY: ErrorKind = ErrorKind::Other; // NotADirectory is unstable
 const IS_DIRECTORY: ErrorKind = ErrorKind::Other; // IsADirectory is unstable
@@ -23,13 +29,20 @@ where
     Err(io::Error::new(kind, error))
 }
 
-/// The virtual file system is a TraitIo implementation for testing.
+/// The in-memory file system implementing [`EnvironmentIo`] and [`ProjectIo`] for testing.
 ///
-/// This struct implements All EnvironmentIo and ProjectIo methods.
+/// Both relative and absolute paths are resolved from the root of the file system.
+/// Use [`snapshot`](Self::snapshot) to compare the contents before and after some operation.
 pub struct VirtualFileSystem {
     root: DirectoryEntry,
 }
 
+impl Default for VirtualFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VirtualFileSystem {
     pub fn new() -> Self {
         Self {
@@ -37,13 +50,13 @@ impl VirtualFileSystem {
         }
     }
 
+    /// Creates the file with the content, creating the parent directories if not exists
     pub async fn add_file(&self, path: &Path, content: &[u8]) -> io::Result<()> {
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return err(IS_DIRECTORY, "is directory");
         };
         self.root
-            .create_dir_all(&dir_path)
-            .await?
+            .create_dir_all(&dir_path)?
             .create_file(last, true)
             .await?
             .set_content(content)
@@ -51,6 +64,7 @@ impl VirtualFileSystem {
         Ok(())
     }
 
+    /// Makes the file at the path undeletable to simulate files locked by other processes
     pub async fn deny_deletion(&self, path: &Path) -> io::Result<()> {
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return err(IS_DIRECTORY, "is directory");
@@ -64,6 +78,11 @@ impl VirtualFileSystem {
             .deny_deletion();
         Ok(())
     }
+
+    /// Takes the snapshot of all files and directories in the file system
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(&self.root)
+    }
 }
 
 impl VirtualFileSystem {
@@ -72,13 +91,12 @@ impl VirtualFileSystem {
 
         for x in path.components() {
             match x {
-                Component::Prefix(_) | Component::RootDir => {
-                    panic!("absolute path")
-                }
+                // absolute paths are resolved from the root of the file system
+                Component::Prefix(_) | Component::RootDir => result.clear(),
                 Component::CurDir => continue,
                 Component::ParentDir => {
                     if result.pop().is_none() {
-                        panic!("accessing parent folder")
+                        return err(ErrorKind::InvalidInput, "accessing parent folder");
                     }
                 }
                 Component::Normal(component) => result.push(component),
@@ -99,9 +117,9 @@ impl VirtualFileSystem {
     }
 }
 
-impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
+impl crate::io::IoTrait for VirtualFileSystem {
     async fn create_dir_all(&self, path: &Path) -> io::Result<()> {
-        self.root.create_dir_all(&self.resolve(path)?).await?;
+        self.root.create_dir_all(&self.resolve(path)?)?;
         Ok(())
     }
 
@@ -146,7 +164,7 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
         self.root
             .get_folder(&dir_path)
             .await?
-            .remove_dir_all(last)
+            .remove_dir(last)
             .await?;
         Ok(())
     }
@@ -188,6 +206,16 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
         let mut to_dir = to_dir.backed.lock().unwrap();
 
         let to_entry = match to_dir.entry(to_last.to_os_string()) {
+            // like std::fs::rename, existing file will be replaced
+            Entry::Occupied(mut e)
+                if matches!(
+                    (e.get(), &original),
+                    (FileSystemEntry::File(_), FileSystemEntry::File(_))
+                ) =>
+            {
+                *e.get_mut() = original;
+                return Ok(());
+            }
             Entry::Occupied(_) => return err(ErrorKind::AlreadyExists, "file exists"),
             Entry::Vacant(e) => e,
         };
@@ -275,14 +303,15 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
 
 impl EnvironmentIo for VirtualFileSystem {
     fn resolve(&self, path: &Path) -> PathBuf {
-        self.resolve(path)
-            .expect("unexpected full path")
-            .iter()
-            .collect()
+        // paths outside the file system are kept as is, and rejected when they are accessed
+        match self.resolve(path) {
+            Ok(resolved) => resolved.iter().collect(),
+            Err(_) => path.to_owned(),
+        }
     }
 
     #[cfg(feature = "vrc-get-litedb")]
-    async fn connect_lite_db(&self) -> io::Result<vrc_get_vpm::environment::VccDatabaseConnection> {
+    async fn connect_lite_db(&self) -> io::Result<crate::environment::VccDatabaseConnection> {
         err(ErrorKind::Unsupported, "lite db")
     }
 
@@ -290,8 +319,9 @@ impl EnvironmentIo for VirtualFileSystem {
     type ProjectIo = VirtualFileSystem;
 
     #[cfg(feature = "experimental-project-management")]
-    fn new_project_io(&self, _: &Path) -> Self::ProjectIo {
-        panic!("not implemented") // TODO: implement
+    fn new_project_io(&self, path: &Path) -> io::Result<Self::ProjectIo> {
+        let root = self.root.create_dir_all(&self.resolve(path)?)?;
+        Ok(VirtualFileSystem { root })
     }
 }
 
@@ -306,7 +336,12 @@ enum FileSystemEntry {
 impl FileSystemEntry {
     fn metadata(&self) -> Metadata {
         match self {
-            FileSystemEntry::File(_) => Metadata::file(),
+            FileSystemEntry::File(file) => {
+                let content = file.content.lock().unwrap();
+                Metadata::file()
+                    .with_modified(content.modified)
+                    .with_size(content.content.len() as u64)
+            }
             FileSystemEntry::Directory(_) => Metadata::dir(),
         }
     }
@@ -364,7 +399,7 @@ impl DirectoryContent {
         self.content.get(name)?.as_ref()
     }
 
-    fn entry(&mut self, name: OsString) -> Entry {
+    fn entry(&mut self, name: OsString) -> Entry<'_> {
         Entry::new(self.content.entry(name))
     }
 
@@ -414,6 +449,10 @@ impl<'a> OccupiedEntry<'a> {
         self.0.into_mut().take().unwrap()
     }
 
+    fn get(&self) -> &FileSystemEntry {
+        self.0.get().as_ref().unwrap()
+    }
+
     fn get_mut(&mut self) -> &mut FileSystemEntry {
         self.0.get_mut().as_mut().unwrap()
     }
@@ -459,7 +498,7 @@ impl DirectoryEntry {
         Ok(current)
     }
 
-    async fn create_dir_all(&self, path: &[&OsStr]) -> io::Result<DirectoryEntry> {
+    fn create_dir_all(&self, path: &[&OsStr]) -> io::Result<DirectoryEntry> {
         let mut current = self.clone();
 
         for component in path {
@@ -561,6 +600,7 @@ struct FileEntry {
 struct FileContent {
     content: Vec<u8>,
     locked: bool,
+    modified: SystemTime,
 }
 
 impl FileContent {
@@ -568,6 +608,7 @@ impl FileContent {
         Self {
             content: Vec::new(),
             locked: false,
+            modified: SystemTime::now(),
         }
     }
 }
@@ -579,15 +620,17 @@ impl FileEntry {
         }
     }
 
-    pub(crate) async fn set_content(&self, content: &[u8]) {
-        self.content.lock().unwrap().content = content.to_vec();
+    async fn set_content(&self, content: &[u8]) {
+        let mut locked = self.content.lock().unwrap();
+        locked.content = content.to_vec();
+        locked.modified = SystemTime::now();
     }
 
-    pub(crate) fn deny_deletion(&self) {
+    fn deny_deletion(&self) {
         self.content.lock().unwrap().locked = true;
     }
 
-    pub(crate) fn can_remove(&self) -> bool {
+    fn can_remove(&self) -> bool {
         !self.content.lock().unwrap().locked
     }
 }
@@ -641,7 +684,7 @@ impl DirEntry {
     }
 }
 
-impl vrc_get_vpm::io::DirEntry for DirEntry {
+impl crate::io::DirEntry for DirEntry {
     fn file_name(&self) -> OsString {
         self.name.clone()
     }
@@ -656,14 +699,14 @@ impl vrc_get_vpm::io::DirEntry for DirEntry {
 }
 
 mod file_stream {
-    use crate::common::virtual_file_system::FileContent;
+    use super::FileContent;
     use futures::{AsyncRead, AsyncSeek, AsyncWrite};
-    use std::future::Future;
     use std::io;
     use std::io::{ErrorKind, SeekFrom};
     use std::pin::Pin;
     use std::sync::{Arc, Mutex};
     use std::task::{Context, Poll};
+    use std::time::SystemTime;
 
     pub struct FileStream {
         content: Arc<Mutex<FileContent>>,
@@ -741,7 +784,7 @@ mod file_stream {
 
     impl AsyncWrite for FileStream {
         fn poll_write(
-            self: Pin<&mut Self>,
+            mut self: Pin<&mut Self>,
             _: &mut Context<'_>,
             buf: &[u8],
         ) -> Poll<io::Result<usize>> {
@@ -752,6 +795,8 @@ mod file_stream {
                 guard.content.resize(new_len, 0);
             }
             guard.content[self.position..][..buf.len()].copy_from_slice(buf);
+            guard.modified = SystemTime::now();
+            self.position = new_len;
 
             Poll::Ready(Ok(buf.len()))
         }
@@ -765,5 +810,5 @@ mod file_stream {
         }
     }
 
-    impl vrc_get_vpm::io::FileStream for FileStream {}
+    impl crate::io::FileStream for FileStream {}
 }
@@ -0,0 +1,127 @@
+use super::{DirectoryEntry, FileSystemEntry};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// The contents of the [`VirtualFileSystem`](super::VirtualFileSystem) at some point.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot {
+    entries: BTreeMap<PathBuf, SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SnapshotEntry {
+    Directory,
+    File(Vec<u8>),
+}
+
+impl Snapshot {
+    pub(super) fn new(root: &DirectoryEntry) -> Self {
+        let mut entries = BTreeMap::new();
+        collect_entries(root, PathBuf::new(), &mut entries);
+        Self { entries }
+    }
+
+    /// Returns all files and directories in the path order
+    pub fn entries(&self) -> impl Iterator<Item = (&Path, &SnapshotEntry)> {
+        self.entries.iter().map(|(k, v)| (k.as_path(), v))
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&SnapshotEntry> {
+        self.entries.get(path)
+    }
+
+    /// Returns the content of the file at the path, or `None` if it's not a file
+    pub fn file(&self, path: &Path) -> Option<&[u8]> {
+        match self.entries.get(path)? {
+            SnapshotEntry::File(content) => Some(content),
+            SnapshotEntry::Directory => None,
+        }
+    }
+
+    /// Computes the changes from this snapshot to the `after` snapshot.
+    pub fn diff(&self, after: &Snapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+
+        for (path, entry) in &self.entries {
+            match after.entries.get(path) {
+                None => diff.removed.push(path.clone()),
+                Some(after) if after != entry => diff.modified.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+
+        for path in after.entries.keys() {
+            if !self.entries.contains_key(path) {
+                diff.added.push(path.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+fn collect_entries(
+    dir: &DirectoryEntry,
+    path: PathBuf,
+    entries: &mut BTreeMap<PathBuf, SnapshotEntry>,
+) {
+    let locked = dir.backed.lock().unwrap();
+    for (name, entry) in &locked.content {
+        let Some(entry) = entry else { continue };
+        let path = path.join(name);
+        match entry {
+            FileSystemEntry::File(file) => {
+                let content = file.content.lock().unwrap().content.clone();
+                entries.insert(path, SnapshotEntry::File(content));
+            }
+            FileSystemEntry::Directory(child) => {
+                entries.insert(path.clone(), SnapshotEntry::Directory);
+                collect_entries(child, path, entries);
+            }
+        }
+    }
+}
+
+/// The changes between two [`Snapshot`]s.
+///
+/// Paths are sorted, and a path changed from file to directory or vice versa is reported as modified.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SnapshotDiff {
+    added: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    modified: Vec<PathBuf>,
+}
+
+impl SnapshotDiff {
+    pub fn added(&self) -> &[PathBuf] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[PathBuf] {
+        &self.removed
+    }
+
+    pub fn modified(&self) -> &[PathBuf] {
+        &self.modified
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl Display for SnapshotDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for path in &self.added {
+            writeln!(f, "+ {}", path.display())?;
+        }
+        for path in &self.removed {
+            writeln!(f, "- {}", path.display())?;
+        }
+        for path in &self.modified {
+            writeln!(f, "M {}", path.display())?;
+        }
+        Ok(())
+    }
+}
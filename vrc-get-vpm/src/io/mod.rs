@@ -1,6 +1,7 @@
 use std::ffi::OsString;
 use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub(crate) use futures::Stream;
 pub(crate) use futures::io::{
@@ -8,6 +9,8 @@ pub(crate) use futures::io::{
 };
 pub(crate) use std::io::SeekFrom;
 
+#[cfg(feature = "memory")]
+pub mod memory;
 #[cfg(feature = "tokio")]
 mod tokio;
 #[cfg(feature = "tokio")]
@@ -19,6 +22,7 @@ pub use tokio::DefaultProjectIo;
 ///
 /// All relative paths should be resolved as a relative path from the environment folder.
 /// Which is `%APPDATA%\\VRChatCreatorCompanion` or `${XDG_DATA_HOME}/VRChatCreatorCompanion` by default.
+/// See [`DefaultEnvironmentIo::new_default`] for how the environment folder is determined.
 pub trait EnvironmentIo: Sync + IoTrait {
     /// We may need to resolve a relative path to an absolute path for some reason.
     /// For example, to get the absolute path of the Repos folder for creating local cache and cleanup repos folder.
@@ -31,7 +35,7 @@ pub trait EnvironmentIo: Sync + IoTrait {
     type ProjectIo: ProjectIo;
 
     #[cfg(feature = "experimental-project-management")]
-    fn new_project_io(&self, path: &Path) -> Self::ProjectIo;
+    fn new_project_io(&self, path: &Path) -> Result<Self::ProjectIo>;
 }
 
 /// Wrapper for the file system operation for the [UnityProject]
@@ -129,21 +133,38 @@ impl From<std::fs::FileType> for FileType {
 #[derive(Debug, Clone)]
 pub struct Metadata {
     file_type: FileType,
+    modified: Option<SystemTime>,
+    size: u64,
 }
 
 impl Metadata {
     pub fn file() -> Self {
         Self {
             file_type: FileType::file(),
+            modified: None,
+            size: 0,
         }
     }
 
     pub fn dir() -> Self {
         Self {
             file_type: FileType::dir(),
+            modified: None,
+            size: 0,
         }
     }
 
+    pub fn with_modified(self, modified: SystemTime) -> Self {
+        Self {
+            modified: Some(modified),
+            ..self
+        }
+    }
+
+    pub fn with_size(self, size: u64) -> Self {
+        Self { size, ..self }
+    }
+
     pub fn file_type(&self) -> FileType {
         self.file_type
     }
@@ -155,12 +176,24 @@ impl Metadata {
     pub fn is_dir(&self) -> bool {
         self.file_type.is_dir
     }
+
+    /// The last modification time if the file system supports it
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// The size of the file in bytes. This is not meaningful for directories
+    pub fn size(&self) -> u64 {
+        self.size
+    }
 }
 
 impl From<std::fs::Metadata> for Metadata {
     fn from(value: std::fs::Metadata) -> Self {
         Self {
             file_type: value.file_type().into(),
+            modified: value.modified().ok(),
+            size: value.len(),
         }
     }
 }
@@ -23,17 +23,64 @@ impl DefaultEnvironmentIo {
         Self { root }
     }
 
+    /// Creates the environment io with the default environment folder.
+    ///
+    /// The environment folder is determined in the following order:
+    /// - The folder specified with [`ENV_DIR_VAR`](Self::ENV_DIR_VAR) environment variable
+    /// - The [`PORTABLE_FOLDER_NAME`](Self::PORTABLE_FOLDER_NAME) folder beside the executable if exists (portable mode)
+    /// - `%LOCALAPPDATA%\VRChatCreatorCompanion` or `${XDG_DATA_HOME}/VRChatCreatorCompanion`
     pub fn new_default() -> Self {
-        let mut folder = Self::get_local_config_folder();
-        folder.push("VRChatCreatorCompanion");
-        let folder = folder;
+        let folder = Self::default_folder();
+
+        debug!(
+            "initializing EnvironmentIo with config folder {}",
+            folder.display()
+        );
+
+        DefaultEnvironmentIo::new(folder.into_boxed_path())
+    }
+
+    /// The environment variable to specify the environment folder.
+    pub const ENV_DIR_VAR: &'static str = "VRC_GET_ENV_DIR";
+
+    /// The name of the folder beside the executable used as the environment folder in portable mode.
+    pub const PORTABLE_FOLDER_NAME: &'static str = "vrc-get-env";
+
+    /// Creates the environment io with the specified environment folder.
+    ///
+    /// Relative paths are resolved from the current directory.
+    pub fn new_with_folder(folder: &Path) -> Self {
+        let folder = std::path::absolute(folder).unwrap_or_else(|_| folder.to_path_buf());
 
         debug!(
             "initializing EnvironmentIo with config folder {}",
             folder.display()
         );
 
-        DefaultEnvironmentIo::new(folder.clone().into_boxed_path())
+        DefaultEnvironmentIo::new(folder.into_boxed_path())
+    }
+
+    fn default_folder() -> PathBuf {
+        if let Some(env_dir) = std::env::var_os(Self::ENV_DIR_VAR).filter(|x| !x.is_empty()) {
+            debug!("{} found {:?}", Self::ENV_DIR_VAR, env_dir);
+            let env_dir = PathBuf::from(env_dir);
+            return std::path::absolute(&env_dir).unwrap_or(env_dir);
+        }
+
+        if let Some(portable) = Self::portable_folder() {
+            debug!("portable environment found at {}", portable.display());
+            return portable;
+        }
+
+        let mut folder = Self::get_local_config_folder();
+        folder.push("VRChatCreatorCompanion");
+        folder
+    }
+
+    fn portable_folder() -> Option<PathBuf> {
+        let exe = std::env::current_exe().ok()?;
+        let folder = exe.parent()?.join(Self::PORTABLE_FOLDER_NAME);
+        folder.is_dir().then_some(folder)
     }
 
     #[cfg(windows)]
@@ -112,8 +159,8 @@ impl EnvironmentIo for DefaultEnvironmentIo {
     type ProjectIo = DefaultProjectIo;
 
     #[cfg(feature = "experimental-project-management")]
-    fn new_project_io(&self, path: &Path) -> Self::ProjectIo {
-        DefaultProjectIo::new(path.into())
+    fn new_project_io(&self, path: &Path) -> io::Result<Self::ProjectIo> {
+        Ok(DefaultProjectIo::new(path.into()))
     }
 }
 
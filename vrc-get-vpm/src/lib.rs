@@ -13,6 +13,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use version::{ReleaseType, UnityVersion, Version, VersionRange};
 
 pub mod environment;
+pub mod git;
 pub mod io;
 mod package_manifest;
 pub mod repository;
@@ -30,6 +31,7 @@ pub mod unity;
 #[cfg(feature = "unity-hub")]
 pub mod unity_hub;
 
+use crate::git::GitPackage;
 use crate::repository::local::LocalCachedRepository;
 
 pub use package_manifest::PackageManifest;
@@ -57,6 +59,8 @@ impl std::fmt::Debug for PackageInfo<'_> {
         enum SourceEnum<'a> {
             Local(&'a Path),
             Remote(&'a str),
+            Archive(&'a Path),
+            Git(&'a str),
         }
 
         let source = match self.inner {
@@ -66,6 +70,8 @@ impl std::fmt::Debug for PackageInfo<'_> {
                     .unwrap_or("(unknown id)"),
             ),
             PackageInfoInner::Local(_, path) => SourceEnum::Local(path),
+            PackageInfoInner::Archive(_, path) => SourceEnum::Archive(path),
+            PackageInfoInner::Git(package) => SourceEnum::Git(package.source().url()),
         };
 
         f.debug_struct("PackageInfo")
@@ -79,6 +85,10 @@ impl std::fmt::Debug for PackageInfo<'_> {
 enum PackageInfoInner<'a> {
     Remote(&'a PackageManifest, &'a LocalCachedRepository),
     Local(&'a PackageManifest, &'a Path),
+    /// zip file of the package
+    Archive(&'a PackageManifest, &'a Path),
+    /// package at a commit of the git repository
+    Git(&'a GitPackage),
 }
 
 impl<'a> PackageInfo<'a> {
@@ -87,6 +97,8 @@ impl<'a> PackageInfo<'a> {
         match self.inner {
             PackageInfoInner::Remote(pkg, _) => pkg,
             PackageInfoInner::Local(pkg, _) => pkg,
+            PackageInfoInner::Archive(pkg, _) => pkg,
+            PackageInfoInner::Git(package) => package.manifest(),
         }
     }
 
@@ -102,10 +114,32 @@ impl<'a> PackageInfo<'a> {
         }
     }
 
+    pub fn archive(json: &'a PackageManifest, path: &'a Path) -> Self {
+        Self {
+            inner: PackageInfoInner::Archive(json, path),
+        }
+    }
+
+    pub fn git(package: &'a GitPackage) -> Self {
+        Self {
+            inner: PackageInfoInner::Git(package),
+        }
+    }
+
     pub fn repo(self) -> Option<&'a LocalCachedRepository> {
         match self.inner {
             PackageInfoInner::Remote(_, repo) => Some(repo),
             PackageInfoInner::Local(_, _) => None,
+            PackageInfoInner::Archive(_, _) => None,
+            PackageInfoInner::Git(_) => None,
+        }
+    }
+
+    /// The package in the git repository if the package is from git
+    pub fn git_package(self) -> Option<&'a GitPackage> {
+        match self.inner {
+            PackageInfoInner::Git(package) => Some(package),
+            _ => None,
         }
     }
 
@@ -119,6 +153,11 @@ impl<'a> PackageInfo<'a> {
         matches!(self.inner, PackageInfoInner::Local(_, _))
     }
 
+    #[allow(unused)]
+    pub fn is_archive(self) -> bool {
+        matches!(self.inner, PackageInfoInner::Archive(_, _))
+    }
+
     pub fn name(self) -> &'a str {
         self.package_json().name()
     }
@@ -42,7 +42,7 @@ impl RepositoriesFile {
                 "vcc" => {
                     repository = parse_add_repo_link(url);
                 }
-                "http" | "https" => {
+                "http" | "https" | "file" => {
                     repository = Some(RepositoryInfo {
                         url,
                         headers: IndexMap::new(),
@@ -79,6 +79,10 @@ impl RepositoriesFileParseResult {
     pub fn unparseable_lines(&self) -> &[String] {
         &self.unparseable_lines
     }
+
+    pub fn into_parsed(self) -> RepositoriesFile {
+        self.parsed
+    }
 }
 
 impl RepositoryInfo {
@@ -0,0 +1,97 @@
+use crate::version::{PrereleaseAcceptance, Version, VersionRange};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt::Display;
+use url::Url;
+
+/// An advisory about the versions of a package published by the repository.
+///
+/// Repositories list advisories in `advisories` next to `packages`.
+/// Advisories for packages not provided by the repository are ignored.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Advisory {
+    #[serde(default)]
+    id: Option<Box<str>>,
+    package: Box<str>,
+    versions: VersionRange,
+    severity: AdvisorySeverity,
+    message: Box<str>,
+    #[serde(default)]
+    url: Option<Url>,
+}
+
+impl Advisory {
+    /// The identifier of the advisory like `GHSA-xxxx-xxxx-xxxx` if exists
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    /// The range of affected versions
+    pub fn versions(&self) -> &VersionRange {
+        &self.versions
+    }
+
+    pub fn severity(&self) -> AdvisorySeverity {
+        self.severity
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The url to the details of the advisory if exists
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    /// Returns true if the version of the package is affected by this advisory.
+    ///
+    /// Prerelease versions are matched as well as release versions.
+    pub fn affects(&self, package: &str, version: &Version) -> bool {
+        self.package.as_ref() == package
+            && self
+                .versions
+                .match_pre(version, PrereleaseAcceptance::Allow)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AdvisorySeverity {
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl Display for AdvisorySeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdvisorySeverity::Low => f.write_str("low"),
+            AdvisorySeverity::Moderate => f.write_str("moderate"),
+            AdvisorySeverity::High => f.write_str("high"),
+            AdvisorySeverity::Critical => f.write_str("critical"),
+        }
+    }
+}
+
+/// Deserializes advisories, skipping malformed ones with warning.
+pub(super) fn deserialize_advisories<'de, D>(deserializer: D) -> Result<Vec<Advisory>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Vec::<serde_value::Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|value| match Advisory::deserialize(value) {
+            Ok(advisory) => Some(advisory),
+            Err(err) => {
+                log::warn!("Error deserializing advisory: {err}");
+                None
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,157 @@
+use crate::environment::read_package_json_in_archive;
+use crate::io;
+use crate::io::{DirEntry, EnvironmentIo};
+use crate::repository::RemoteRepository;
+use crate::utils::read_json_file;
+use futures::prelude::*;
+use log::warn;
+use serde_json::{Map, Value, json};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use url::Url;
+
+type JsonMap = Map<String, Value>;
+
+/// Returns true if the repository or the package is read from the file system instead of the network.
+pub fn is_file_url(url: &Url) -> bool {
+    url.scheme() == "file"
+}
+
+pub(crate) fn file_url_to_path(url: &Url) -> io::Result<PathBuf> {
+    url.to_file_path().map_err(|()| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{url} is not a valid file url"),
+        )
+    })
+}
+
+impl RemoteRepository {
+    /// Reads the repository at the `file:` url.
+    ///
+    /// The url can point to the repository json file, or a directory with the zip files of packages.
+    /// For a directory, the repository is generated from `package.json` in each zip file.
+    ///
+    /// Returns the repository and the last modification time of the source if known.
+    pub async fn read_file(
+        io: &impl EnvironmentIo,
+        url: &Url,
+    ) -> io::Result<(RemoteRepository, Option<SystemTime>)> {
+        let path = file_url_to_path(url)?;
+        let metadata = io.metadata(&path).await?;
+
+        let (mut repo, modified) = if metadata.is_dir() {
+            let archives = list_archives(io, &path).await?;
+            let repo = read_directory(io, &path, &archives).await?;
+            (repo, latest_modified(metadata.modified(), &archives))
+        } else {
+            let json: JsonMap = read_json_file(io.open(&path).await?, &path).await?;
+            (RemoteRepository::parse(json)?, metadata.modified())
+        };
+
+        repo.set_url_if_none(|| url.clone());
+        Ok((repo, modified))
+    }
+
+    /// Returns the last modification time of the repository at the `file:` url without reading it.
+    ///
+    /// Returns `None` if the file system doesn't provide the modification time.
+    pub async fn file_modified(
+        io: &impl EnvironmentIo,
+        url: &Url,
+    ) -> io::Result<Option<SystemTime>> {
+        let path = file_url_to_path(url)?;
+        let metadata = io.metadata(&path).await?;
+        if metadata.is_dir() {
+            let archives = list_archives(io, &path).await?;
+            Ok(latest_modified(metadata.modified(), &archives))
+        } else {
+            Ok(metadata.modified())
+        }
+    }
+}
+
+async fn list_archives(
+    io: &impl EnvironmentIo,
+    path: &Path,
+) -> io::Result<Vec<(PathBuf, Option<SystemTime>)>> {
+    let mut archives = Vec::new();
+    let mut entries = io.read_dir(path).await?;
+    while let Some(entry) = entries.try_next().await? {
+        let file_name = entry.file_name();
+        if !file_name.as_encoded_bytes().ends_with(b".zip") {
+            continue;
+        }
+        let metadata = entry.metadata().await?;
+        if metadata.is_file() {
+            archives.push((path.join(file_name), metadata.modified()));
+        }
+    }
+    archives.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(archives)
+}
+
+/// The modification time of the directory source, or `None` if any of them is unknown
+fn latest_modified(
+    directory: Option<SystemTime>,
+    archives: &[(PathBuf, Option<SystemTime>)],
+) -> Option<SystemTime> {
+    let mut latest = directory?;
+    for (_, modified) in archives {
+        latest = latest.max((*modified)?);
+    }
+    Some(latest)
+}
+
+async fn read_directory(
+    io: &impl EnvironmentIo,
+    path: &Path,
+    archives: &[(PathBuf, Option<SystemTime>)],
+) -> io::Result<RemoteRepository> {
+    let mut packages = JsonMap::new();
+
+    for (archive, _) in archives {
+        let mut manifest = match read_package_json_in_archive::<JsonMap>(io, archive).await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                warn!("skipping {}: {e}", archive.display());
+                continue;
+            }
+        };
+
+        let (Some(Value::String(name)), Some(Value::String(version))) =
+            (manifest.get("name"), manifest.get("version"))
+        else {
+            warn!(
+                "skipping {}: name or version is missing in package.json",
+                archive.display()
+            );
+            continue;
+        };
+        let (name, version) = (name.clone(), version.clone());
+
+        let Ok(archive_url) = Url::from_file_path(archive) else {
+            warn!("skipping {}: cannot be converted to url", archive.display());
+            continue;
+        };
+        manifest.insert("url".into(), Value::String(archive_url.into()));
+
+        let versions = packages
+            .entry(name)
+            .or_insert_with(|| json!({ "versions": {} }));
+        if let Some(Value::Object(versions)) = versions.get_mut("versions") {
+            versions.insert(version, Value::Object(manifest));
+        }
+    }
+
+    let mut json = JsonMap::new();
+    if let Some(name) = path.file_name() {
+        json.insert(
+            "name".into(),
+            Value::String(name.to_string_lossy().into_owned()),
+        );
+    }
+    json.insert("packages".into(), Value::Object(packages));
+
+    RemoteRepository::parse(json)
+}
@@ -1,7 +1,8 @@
 use crate::PackageManifest;
-use crate::repository::{RemotePackages, RemoteRepository};
+use crate::repository::{Advisory, RemotePackages, RemoteRepository};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
 use url::Url;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -49,6 +50,46 @@ impl LocalCachedRepository {
         }
     }
 
+    /// Returns when the repository was last fetched from the remote server.
+    pub fn last_fetched(&self) -> Option<SystemTime> {
+        let secs = self.vrc_get.as_ref()?.last_fetched?;
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    pub(crate) fn set_last_fetched(&mut self, time: SystemTime) {
+        let secs = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or(0);
+        self.vrc_get
+            .get_or_insert_with(Default::default)
+            .last_fetched = Some(secs);
+    }
+
+    /// Returns the modification time of the `file:` repository source when it was last read.
+    pub fn source_modified(&self) -> Option<SystemTime> {
+        let nanos = self.vrc_get.as_ref()?.source_modified?;
+        Some(SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos))
+    }
+
+    pub(crate) fn set_source_modified(&mut self, time: Option<SystemTime>) {
+        let nanos = time
+            .and_then(|x| x.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|x| x.as_nanos() as u64);
+        if nanos.is_some() || self.vrc_get.is_some() {
+            self.vrc_get
+                .get_or_insert_with(Default::default)
+                .source_modified = nanos;
+        }
+    }
+
+    /// Returns true if the repository was fetched within the ttl.
+    pub(crate) fn is_fresh(&self, ttl: Duration) -> bool {
+        self.last_fetched()
+            .and_then(|x| x.elapsed().ok())
+            .is_some_and(|elapsed| elapsed < ttl)
+    }
+
     pub fn url(&self) -> Option<&Url> {
         self.repo().url()
     }
@@ -71,10 +112,54 @@ impl LocalCachedRepository {
     pub fn get_packages(&self) -> impl Iterator<Item = &'_ RemotePackages> {
         self.repo().get_packages()
     }
+
+    pub fn advisories(&self) -> impl Iterator<Item = &'_ Advisory> {
+        self.repo().advisories()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct VrcGetMeta {
     #[serde(default, skip_serializing_if = "str::is_empty")]
     pub etag: Box<str>,
+    /// seconds since the unix epoch
+    #[serde(rename = "lastFetched")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_fetched: Option<u64>,
+    /// nanoseconds since the unix epoch. used instead of etag for `file:` repositories
+    #[serde(rename = "sourceModified")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_modified: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fetched_at(time: Option<SystemTime>) -> LocalCachedRepository {
+        let repo = RemoteRepository::parse(serde_json::Map::new()).unwrap();
+        let mut cache = LocalCachedRepository::new(repo, IndexMap::new());
+        if let Some(time) = time {
+            cache.set_last_fetched(time);
+        }
+        cache
+    }
+
+    #[test]
+    fn is_fresh() {
+        let hour = Duration::from_secs(60 * 60);
+        let now = SystemTime::now();
+
+        assert!(fetched_at(Some(now)).is_fresh(hour));
+        assert!(fetched_at(Some(now - hour + Duration::from_secs(10))).is_fresh(hour));
+        // the repository fetched just the ttl ago is stale
+        assert!(!fetched_at(Some(now - hour)).is_fresh(hour));
+        assert!(!fetched_at(Some(now - hour * 2)).is_fresh(hour));
+
+        // zero ttl disables the cache
+        assert!(!fetched_at(Some(now)).is_fresh(Duration::ZERO));
+        // never fetched or fetched in the future by wrong clock
+        assert!(!fetched_at(None).is_fresh(hour));
+        assert!(!fetched_at(Some(now + hour)).is_fresh(hour));
+    }
 }
@@ -1,6 +1,12 @@
+mod advisory;
+pub(crate) mod file;
 pub(crate) mod local;
 mod remote;
 
+pub use file::is_file_url;
 pub use local::LocalCachedRepository;
 pub use remote::RemotePackages;
 pub use remote::RemoteRepository;
+
+pub use advisory::Advisory;
+pub use advisory::AdvisorySeverity;
@@ -1,4 +1,6 @@
 use crate::PackageManifest;
+use crate::repository::Advisory;
+use crate::repository::advisory::deserialize_advisories;
 use crate::traits::HttpClient;
 use crate::utils::{deserialize_json, deserialize_json_slice};
 use crate::version::Version;
@@ -10,6 +12,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::pin::pin;
+use std::time::Duration;
 use url::Url;
 
 type JsonMap = Map<String, Value>;
@@ -31,6 +34,9 @@ struct ParsedRepository {
     #[serde(default)]
     #[serde(deserialize_with = "deserialize_packages")]
     packages: IndexMap<Box<str>, RemotePackages>,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_advisories")]
+    advisories: Vec<Advisory>,
 }
 
 impl RemoteRepository {
@@ -46,7 +52,7 @@ impl RemoteRepository {
         url: &Url,
         headers: &IndexMap<Box<str>, Box<str>>,
     ) -> io::Result<(RemoteRepository, Option<Box<str>>)> {
-        match Self::download_with_etag(client, url, headers, None).await {
+        match Self::download_with_etag(client, url, headers, None, None).await {
             Ok(None) => unreachable!("downloading without etag should must return Ok(Some)"),
             Ok(Some(repo_and_etag)) => Ok(repo_and_etag),
             Err(err) => Err(err),
@@ -58,8 +64,11 @@ impl RemoteRepository {
         url: &Url,
         headers: &IndexMap<Box<str>, Box<str>>,
         current_etag: Option<&str>,
+        timeout: Option<Duration>,
     ) -> io::Result<Option<(RemoteRepository, Option<Box<str>>)>> {
-        let Some((mut stream, etag)) = client.get_with_etag(url, headers, current_etag).await?
+        let Some((mut stream, etag)) = client
+            .get_with_etag(url, headers, current_etag, timeout)
+            .await?
         else {
             return Ok(None);
         };
@@ -134,6 +143,11 @@ impl RemoteRepository {
     pub fn get_package_version(&self, name: &str, version: &Version) -> Option<&PackageManifest> {
         self.parsed.packages.get(name)?.versions.get(version)
     }
+
+    /// Returns the advisories for the packages provided by this repository.
+    pub fn advisories(&self) -> impl Iterator<Item = &'_ Advisory> {
+        (self.parsed.advisories.iter()).filter(|x| self.parsed.packages.contains_key(x.package()))
+    }
 }
 
 impl Serialize for RemoteRepository {
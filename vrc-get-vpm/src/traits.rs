@@ -6,8 +6,10 @@ use core::iter::Iterator;
 use core::option::Option;
 use futures::prelude::*;
 use indexmap::IndexMap;
+use std::collections::BTreeMap;
 use std::convert::Infallible;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use url::Url;
 
 pub trait PackageCollection {
@@ -44,6 +46,25 @@ pub trait PackageInstaller {
         package: PackageInfo<'_>,
         abort: &AbortCheck,
     ) -> impl Future<Output = io::Result<()>>;
+
+    /// Returns sha256 of the files in the zip file of the package, keyed by the path in the package
+    /// with `/` as the separator.
+    ///
+    /// This is used to find local modifications when adopting packages.
+    /// The default implementation fails with [`Unsupported`](io::ErrorKind::Unsupported).
+    fn package_file_hashes(
+        &self,
+        package: PackageInfo<'_>,
+    ) -> impl Future<Output = io::Result<BTreeMap<Box<str>, Box<str>>>> {
+        future::ready(Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "reading files of {}@{} is not supported",
+                package.name(),
+                package.version()
+            ),
+        )))
+    }
 }
 
 pub struct AbortCheck {
@@ -86,12 +107,15 @@ pub trait HttpClient: Sync {
     /// Returning `Ok(Some((stream, etag)))` means cache not matched and get from remote server.
     /// Returning `Err(_)` means error.
     ///
+    /// If `timeout` is specified, the request should fail if the whole response is not received in time.
+    ///
     /// Note: If remote server returns error status code, this function should return error.
     fn get_with_etag(
         &self,
         url: &Url,
         headers: &IndexMap<Box<str>, Box<str>>,
         current_etag: Option<&str>,
+        timeout: Option<Duration>,
     ) -> impl Future<Output = io::Result<Option<(impl AsyncRead + Send, Option<Box<str>>)>>> + Send;
 }
 
@@ -120,6 +144,7 @@ impl HttpClient for reqwest::Client {
         url: &Url,
         headers: &IndexMap<Box<str>, Box<str>>,
         current_etag: Option<&str>,
+        timeout: Option<Duration>,
     ) -> io::Result<Option<(impl AsyncRead, Option<Box<str>>)>> {
         let mut request = self.get(url.to_owned());
         for (name, value) in headers {
@@ -128,6 +153,9 @@ impl HttpClient for reqwest::Client {
         if let Some(etag) = current_etag {
             request = request.header("If-None-Match", etag.to_owned())
         }
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
         let response = request.send().await.err_mapped()?;
         let response = response.error_for_status().err_mapped()?;
 
@@ -162,6 +190,7 @@ impl HttpClient for Infallible {
         _: &Url,
         _: &IndexMap<Box<str>, Box<str>>,
         _: Option<&str>,
+        _: Option<Duration>,
     ) -> io::Result<Option<(impl AsyncRead, Option<Box<str>>)>> {
         Ok(Some((io::empty(), None)))
     }
@@ -1,16 +1,27 @@
 mod add_package;
+mod cleanup_legacy;
+mod clone_project;
+mod dependency_range;
+mod doctor;
+mod eject_package;
 mod find_legacy_assets;
+mod guid_index;
+mod local_packages;
 mod migrate_unity_2022;
 mod migrate_vpm;
 mod package_resolution;
 pub mod pending_project_changes;
+mod project_repositories;
 mod project_type;
 mod reinstall;
 mod remove_package;
 mod resolve;
+mod unfinished_changes;
 mod upm_manifest;
+mod upm_packages;
 mod vpm_manifest;
 
+use crate::git::GitSource;
 use crate::unity_project::upm_manifest::UpmManifest;
 use crate::unity_project::vpm_manifest::VpmManifest;
 use crate::utils::{PathBufExt, try_load_json};
@@ -30,12 +41,33 @@ use crate::io::{DirEntry, FileSystemProjectIo, ProjectIo};
 use crate::package_manifest::LooseManifest;
 pub use add_package::AddPackageErr;
 pub use add_package::AddPackageOperation;
+pub use cleanup_legacy::LegacyAsset;
+pub use cleanup_legacy::LegacyAssetMatch;
+pub use clone_project::set_product_identity_in_settings;
+pub use dependency_range::SetDependencyRangeErr;
+pub use doctor::ProblemSeverity;
+pub use doctor::ProjectProblem;
+pub use doctor::diagnose_manifest_files;
+pub use eject_package::AdoptPackageErr;
+pub use eject_package::EJECTED_PACKAGES_PATH;
+pub use eject_package::EjectPackageErr;
+pub use eject_package::EjectedPackage;
+pub use guid_index::GuidIndex;
+pub use guid_index::IndexedAsset;
+pub use local_packages::PROJECT_LOCAL_PACKAGES_PATH;
 pub use migrate_unity_2022::MigrateUnity2022Error;
 pub use migrate_vpm::MigrateVpmError;
 pub use pending_project_changes::PendingProjectChanges;
+pub use project_repositories::PROJECT_REPOSITORIES_PATH;
 pub use reinstall::ReinstalPackagesError;
 pub use remove_package::RemovePackageErr;
 pub use resolve::ResolvePackageErr;
+pub use unfinished_changes::RecoverChangesErr;
+pub use unfinished_changes::UnfinishedChanges;
+pub use upm_manifest::ParseUpmDependencyError;
+pub use upm_manifest::ScopedRegistry;
+pub use upm_manifest::UpmDependency;
+pub use upm_packages::UpgradeUpmPackageErr;
 
 #[derive(Debug)]
 pub struct UnityProject<IO: ProjectIo> {
@@ -52,6 +84,10 @@ pub struct UnityProject<IO: ProjectIo> {
     unlocked_packages: Vec<(Box<str>, Option<PackageManifest>)>,
     /// packages installed in the directory and licked in vpm-manifest.json
     installed_packages: HashMap<Box<str>, PackageManifest>,
+    /// changes interrupted in the middle of apply_pending_changes
+    unfinished_changes: Option<UnfinishedChanges>,
+    /// packages ejected from vpm-manifest.json
+    ejected_packages: IndexMap<Box<str>, EjectedPackage>,
 }
 
 // basic lifecycle
@@ -60,6 +96,34 @@ impl<IO: ProjectIo> UnityProject<IO> {
         let manifest = VpmManifest::load(&io).await?;
         let upm_manifest = UpmManifest::load(&io).await?;
 
+        let (installed_packages, unlocked_packages) = Self::load_packages(&io, &manifest).await?;
+        let unfinished_changes = UnfinishedChanges::load(&io).await;
+        let ejected_packages = Self::load_ejected_packages(&io).await;
+
+        let (unity_version, unity_revision) = Self::try_read_unity_version(&io).await;
+
+        Ok(Self {
+            io,
+            manifest,
+            upm_manifest,
+            unity_version,
+            unity_revision,
+            unlocked_packages,
+            installed_packages,
+            unfinished_changes,
+            ejected_packages,
+        })
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    async fn load_packages(
+        io: &IO,
+        manifest: &VpmManifest,
+    ) -> io::Result<(
+        HashMap<Box<str>, PackageManifest>,
+        Vec<(Box<str>, Option<PackageManifest>)>,
+    )> {
         let mut installed_packages = HashMap::new();
         let mut unlocked_packages = vec![];
 
@@ -75,7 +139,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
                     if !dir_entry.file_type().await?.is_dir() {
                         continue;
                     }
-                    let read = Self::try_read_unlocked_package(&io, dir_entry).await;
+                    let read = Self::try_read_unlocked_package(io, dir_entry).await;
                     let mut is_installed = false;
                     if let Some(parsed) = &read.1 {
                         if parsed.name() == read.0.as_ref()
@@ -93,21 +157,15 @@ impl<IO: ProjectIo> UnityProject<IO> {
             }
         }
 
-        let (unity_version, unity_revision) = Self::try_read_unity_version(&io).await;
+        Ok((installed_packages, unlocked_packages))
+    }
 
-        Ok(Self {
-            io,
-            manifest,
-            upm_manifest,
-            unity_version,
-            unity_revision,
-            unlocked_packages,
-            installed_packages,
-        })
+    async fn reload_packages(&mut self) -> io::Result<()> {
+        (self.installed_packages, self.unlocked_packages) =
+            Self::load_packages(&self.io, &self.manifest).await?;
+        Ok(())
     }
-}
 
-impl<IO: ProjectIo> UnityProject<IO> {
     async fn try_read_unlocked_package(
         io: &IO,
         dir_entry: IO::DirEntry,
@@ -220,6 +278,23 @@ impl<IO: ProjectIo> UnityProject<IO> {
         self.manifest.get_locked(name)
     }
 
+    /// Returns the packages from git repositories in vpm-manifest.json
+    /// with the source and the commit locked if any.
+    ///
+    /// The packages should be added to the collection before resolving or upgrading.
+    pub fn git_dependencies(&self) -> impl Iterator<Item = (&str, GitSource, Option<&str>)> {
+        (self.manifest.git_dependencies())
+            .map(|(name, source, commit)| (name, GitSource::parse(source), commit))
+    }
+
+    /// Returns the overrides of dependency ranges in vpm-manifest.json.
+    ///
+    /// The key is the name of the dependency to override the range required by all packages,
+    /// or `dependent/dependency` to override the range required by the one dependent package.
+    pub fn dependency_overrides(&self) -> impl Iterator<Item = (&str, &VersionRange)> {
+        self.manifest.overrides().iter()
+    }
+
     pub fn is_locked(&self, name: &str) -> bool {
         self.manifest.get_locked(name).is_some()
     }
@@ -54,6 +54,10 @@ impl std::error::Error for AddPackageErr {}
 #[derive(Debug)]
 pub enum AddPackageOperation {
     InstallToDependencies,
+    /// Upgrades the locked package.
+    ///
+    /// If the version is out of the range in dependencies, the range is replaced with the version,
+    /// which is reported with [`Install::replaced_dependency`](super::pending_project_changes::Install::replaced_dependency).
     UpgradeLocked,
     Downgrade,
     AutoDetected,
@@ -70,6 +74,41 @@ impl<IO: ProjectIo> UnityProject<IO> {
         packages: &[PackageInfo<'env>],
         operation: AddPackageOperation,
         allow_prerelease: bool,
+    ) -> Result<PendingProjectChanges<'env>, AddPackageErr> {
+        let packages = packages.iter().map(|&x| (x, None)).collect::<Vec<_>>();
+        self.add_package_request_impl(env, &packages, operation, allow_prerelease)
+            .await
+    }
+
+    /// Creates a new `AddPackageRequest` to add the specified packages to dependencies with the ranges.
+    ///
+    /// The package should be the version chosen from the range, which will be locked.
+    /// The range is stored to dependencies as is, so upgrading later can stay within the range.
+    pub async fn add_package_request_with_ranges<'env>(
+        &self,
+        env: &'env impl PackageCollection,
+        packages: &[(PackageInfo<'env>, DependencyRange)],
+        allow_prerelease: bool,
+    ) -> Result<PendingProjectChanges<'env>, AddPackageErr> {
+        let packages = packages
+            .iter()
+            .map(|(x, range)| (*x, Some(range)))
+            .collect::<Vec<_>>();
+        self.add_package_request_impl(
+            env,
+            &packages,
+            AddPackageOperation::InstallToDependencies,
+            allow_prerelease,
+        )
+        .await
+    }
+
+    async fn add_package_request_impl<'env>(
+        &self,
+        env: &'env impl PackageCollection,
+        packages: &[(PackageInfo<'env>, Option<&DependencyRange>)],
+        operation: AddPackageOperation,
+        allow_prerelease: bool,
     ) -> Result<PendingProjectChanges<'env>, AddPackageErr> {
         // if same or newer requested package is in locked dependencies,
         // just add requested version into dependencies
@@ -77,16 +116,46 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         let mut changes = super::pending_project_changes::Builder::new();
 
-        for &request in packages {
+        for &(request, range) in packages {
             debug!("Validating Package: {}", request.name());
 
             {
                 fn install_to_dependencies<'env, IO: ProjectIo>(
                     request: PackageInfo<'env>,
+                    range: Option<&DependencyRange>,
                     this: &UnityProject<IO>,
                     adding_packages: &mut Vec<PackageInfo<'env>>,
                     changes: &mut super::pending_project_changes::Builder,
                 ) -> Result<(), AddPackageErr> {
+                    if let Some(range) = range {
+                        if this.manifest.get_dependency(request.name()) != Some(range) {
+                            debug!(
+                                "Adding package {} to dependencies with range {}",
+                                request.name(),
+                                range
+                            );
+                            changes.add_to_dependencies(request.name().into(), range.clone());
+                        }
+
+                        // the locked version might be newer but out of the range
+                        let locked_in_range = this
+                            .manifest
+                            .get_locked(request.name())
+                            .is_some_and(|locked| range.matches(locked.version()));
+                        if !locked_in_range {
+                            debug!(
+                                "Adding package {} to locked packages at version {}",
+                                request.name(),
+                                request.version()
+                            );
+                            adding_packages.push(request);
+                        } else {
+                            check_and_add_adding_package(request, adding_packages, &this.manifest);
+                        }
+
+                        return Ok(());
+                    }
+
                     let add_to_dependencies = this
                         .manifest
                         .get_dependency(request.name())
@@ -111,8 +180,27 @@ impl<IO: ProjectIo> UnityProject<IO> {
                     request: PackageInfo<'env>,
                     this: &UnityProject<IO>,
                     adding_packages: &mut Vec<PackageInfo<'env>>,
-                    _changes: &mut super::pending_project_changes::Builder,
+                    changes: &mut super::pending_project_changes::Builder,
                 ) -> Result<(), AddPackageErr> {
+                    let out_of_range = this
+                        .manifest
+                        .get_dependency(request.name())
+                        .filter(|range| !range.matches(request.version()));
+
+                    if let Some(range) = out_of_range {
+                        debug!(
+                            "Upgrading package {} to version {} out of the range {} in dependencies",
+                            request.name(),
+                            request.version(),
+                            range
+                        );
+                        changes.replace_dependency(
+                            request.name().into(),
+                            range.clone(),
+                            DependencyRange::version(request.version().clone()),
+                        );
+                    }
+
                     check_and_add_adding_package(request, adding_packages, &this.manifest);
                     Ok(())
                 }
@@ -155,7 +243,13 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
                 match operation {
                     AddPackageOperation::InstallToDependencies => {
-                        install_to_dependencies(request, self, &mut adding_packages, &mut changes)?;
+                        install_to_dependencies(
+                            request,
+                            range,
+                            self,
+                            &mut adding_packages,
+                            &mut changes,
+                        )?;
                     }
                     AddPackageOperation::UpgradeLocked => {
                         if self.manifest.get_locked(request.name()).is_none() {
@@ -194,6 +288,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
                                 install_to_dependencies(
                                     request,
+                                    range,
                                     self,
                                     &mut adding_packages,
                                     &mut changes,
@@ -292,6 +387,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             env,
             adding_packages,
             allow_prerelease,
+            self.manifest.overrides(),
             &mut missing_dependencies,
         );
         if !missing_dependencies.is_empty() {
@@ -0,0 +1,201 @@
+use crate::io::{DirEntry, ProjectIo};
+use crate::unity_project::find_legacy_assets::collect_installed_legacy_assets;
+use crate::unity_project::pending_project_changes::remove_assets;
+use crate::utils::walk_dir_relative;
+use crate::{UnityProject, io};
+use futures::prelude::*;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::pin::pin;
+
+/// How the legacy asset was found in the project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyAssetMatch {
+    /// The asset is at the path defined by the package
+    Path,
+    /// The asset is moved from the defined path and found by the GUID in the `.meta` file
+    Guid,
+}
+
+/// The legacy file or folder of the installed package that still exists in the project.
+#[derive(Debug, Clone)]
+pub struct LegacyAsset<'a> {
+    path: Box<Path>,
+    package_name: &'a str,
+    is_file: bool,
+    matched_by: LegacyAssetMatch,
+    size: u64,
+}
+
+impl LegacyAsset<'_> {
+    /// The path relative to the project root
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The name of the package defining the asset as legacy
+    pub fn package_name(&self) -> &str {
+        self.package_name
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.is_file
+    }
+
+    pub fn matched_by(&self) -> LegacyAssetMatch {
+        self.matched_by
+    }
+
+    /// The total size of the files in bytes, including the `.meta` file
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+fn meta_path(path: &Path) -> PathBuf {
+    let mut meta = OsString::from(path);
+    meta.push(".meta");
+    PathBuf::from(meta)
+}
+
+async fn file_size(io: &impl ProjectIo, path: &Path) -> u64 {
+    io.metadata(path).await.map(|x| x.size()).unwrap_or(0)
+}
+
+async fn folder_size<IO: ProjectIo>(io: &IO, path: &Path) -> u64 {
+    let mut size = 0;
+    let mut stream = pin!(walk_dir_relative(io, [path.to_owned()]));
+    while let Some((_, entry)) = stream.next().await {
+        if let Some(metadata) = entry.metadata().await.ok().filter(|x| x.is_file()) {
+            size += metadata.size();
+        }
+    }
+    size
+}
+
+/// Skips the assets in the legacy folders since they are removed or moved with the folder
+fn outermost<'a, 'b>(assets: &'b [LegacyAsset<'a>]) -> impl Iterator<Item = &'b LegacyAsset<'a>> {
+    assets.iter().filter(|asset| {
+        !assets.iter().any(|folder| {
+            !folder.is_file && folder.path != asset.path && asset.path.starts_with(&folder.path)
+        })
+    })
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Finds the legacy assets defined by the installed packages that still exist in the project.
+    ///
+    /// Legacy assets are usually removed when the package defining them is installed,
+    /// but they remain if they are added back later, like by importing an old unitypackage.
+    /// The assets are sorted by the path.
+    pub async fn find_legacy_assets(&self) -> Vec<LegacyAsset<'_>> {
+        let legacy_assets = collect_installed_legacy_assets(&self.io, self).await;
+
+        let files = (legacy_assets.files.into_iter()).map(|(path, name)| (path, name, true));
+        let folders = (legacy_assets.folders.into_iter()).map(|(path, name)| (path, name, false));
+
+        let mut assets = Vec::new();
+        for (path, package_name, is_file) in files.chain(folders) {
+            let matched_by = if legacy_assets.found_by_guid.contains(&path) {
+                LegacyAssetMatch::Guid
+            } else {
+                LegacyAssetMatch::Path
+            };
+            let size = if is_file {
+                file_size(&self.io, &path).await
+            } else {
+                folder_size(&self.io, &path).await
+            } + file_size(&self.io, &meta_path(&path)).await;
+
+            assets.push(LegacyAsset {
+                path,
+                package_name,
+                is_file,
+                matched_by,
+                size,
+            });
+        }
+
+        assets.sort_by(|a, b| a.path.cmp(&b.path));
+        assets
+    }
+
+    /// Removes the legacy assets with the `.meta` files.
+    ///
+    /// Errors are logged and the remaining assets are still removed.
+    pub async fn remove_legacy_assets(&self, assets: &[LegacyAsset<'_>]) {
+        remove_assets(
+            &self.io,
+            outermost(assets).filter(|x| x.is_file).map(|x| x.path()),
+            outermost(assets).filter(|x| !x.is_file).map(|x| x.path()),
+        )
+        .await;
+    }
+
+    /// Moves the legacy assets with the `.meta` files to the folder instead of removing them.
+    ///
+    /// The assets are moved keeping the path from the project root, like `<folder>/Assets/OldTool`.
+    /// The folder is relative to the project root and must not be in `Assets` or `Packages`
+    /// since Unity would import the moved assets again.
+    /// If any destination already exists, this fails without moving anything.
+    pub async fn move_legacy_assets(
+        &self,
+        assets: &[LegacyAsset<'_>],
+        folder: &Path,
+    ) -> io::Result<()> {
+        // the file system may be case-insensitive so Unity would import `assets/` too
+        let in_unity_folder = folder.components().next().is_some_and(|x| {
+            let first = x.as_os_str().to_string_lossy();
+            first.eq_ignore_ascii_case("Assets") || first.eq_ignore_ascii_case("Packages")
+        });
+        if folder.as_os_str().is_empty()
+            || in_unity_folder
+            || !folder
+                .components()
+                .all(|x| matches!(x, Component::Normal(_)))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} must be a relative path outside of Assets and Packages",
+                    folder.display()
+                ),
+            ));
+        }
+
+        // check all destinations first so that nothing is moved if we would overwrite something
+        for asset in outermost(assets) {
+            let dest = folder.join(&asset.path);
+            for dest in [meta_path(&dest), dest] {
+                match self.io.metadata(&dest).await {
+                    Ok(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AlreadyExists,
+                            format!("{} already exists", dest.display()),
+                        ));
+                    }
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    Err(_) => {}
+                }
+            }
+        }
+
+        for asset in outermost(assets) {
+            let dest = folder.join(&asset.path);
+            if let Some(parent) = dest.parent() {
+                self.io.create_dir_all(parent).await?;
+            }
+            self.io.rename(&asset.path, &dest).await?;
+
+            match (self.io)
+                .rename(&meta_path(&asset.path), &meta_path(&dest))
+                .await
+            {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
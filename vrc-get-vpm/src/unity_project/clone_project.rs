@@ -0,0 +1,185 @@
+use crate::io::{DirEntry, IoTrait, ProjectIo};
+use crate::{UnityProject, io};
+use futures::prelude::*;
+use log::warn;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+const PROJECT_SETTINGS_PATH: &str = "ProjectSettings/ProjectSettings.asset";
+
+/// Returns true if the folder is generated by Unity or version control and should not be copied.
+fn is_skipped_folder(parent: &Path, name: &str) -> bool {
+    let lower_name = name.to_ascii_lowercase();
+    if lower_name == ".git" {
+        return true;
+    }
+    if parent.as_os_str().is_empty() {
+        // some people use multiple library folder to speed up switching platforms
+        return matches!(lower_name.as_str(), "logs" | "obj" | "temp")
+            || lower_name.starts_with("library");
+    }
+    false
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Copies the project to `dest_io` as a new project named `product_name`.
+    ///
+    /// Folders generated by Unity like `Library` and `Temp`, and `.git` folders are not copied.
+    /// The copy has a new `productGUID` so that Unity and VRChat treat it as a different project.
+    /// The folder of `dest_io` should be empty.
+    pub async fn clone_project<DestIO: ProjectIo>(
+        &self,
+        dest_io: DestIO,
+        product_name: &str,
+    ) -> io::Result<UnityProject<DestIO>> {
+        if self.unfinished_changes.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the last operation on the project was interrupted. recover the project first",
+            ));
+        }
+
+        let root = Path::new("");
+        copy_folder(&self.io, root, &dest_io, root, is_skipped_folder).await?;
+        set_product_identity(&dest_io, product_name).await?;
+
+        UnityProject::load(dest_io).await
+    }
+
+    /// Replaces the `Packages` folder with the one of the template project to reset the packages.
+    ///
+    /// The packages in the template are installed when the project is resolved.
+    pub async fn reset_packages_to_template(
+        self,
+        template_io: &impl IoTrait,
+        template: &Path,
+    ) -> io::Result<Self> {
+        let packages = Path::new("Packages");
+        match self.io.remove_dir_all(packages).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        let template_packages = template.join(packages);
+        copy_folder(
+            template_io,
+            &template_packages,
+            &self.io,
+            packages,
+            |_, _| false,
+        )
+        .await?;
+
+        UnityProject::load(self.io).await
+    }
+}
+
+/// Copies the folder recursively. `skip_folder` is called with the path relative to `src` and the name of folders.
+async fn copy_folder(
+    src_io: &impl IoTrait,
+    src: &Path,
+    dst_io: &impl IoTrait,
+    dst: &Path,
+    skip_folder: fn(&Path, &str) -> bool,
+) -> io::Result<()> {
+    let mut queue = VecDeque::new();
+    queue.push_back(PathBuf::new());
+
+    while let Some(relative) = queue.pop_front() {
+        let mut iter = src_io.read_dir(&src.join(&relative)).await?;
+        dst_io.create_dir_all(&dst.join(&relative)).await?;
+        while let Some(entry) = iter.try_next().await? {
+            let file_name = entry.file_name();
+            let path = relative.join(&file_name);
+            let file_type = entry.file_type().await?;
+
+            if file_type.is_file() {
+                copy_file(src_io, &src.join(&path), dst_io, &dst.join(&path)).await?;
+            } else if file_type.is_dir() {
+                let skipped = file_name
+                    .to_str()
+                    .is_some_and(|name| skip_folder(&relative, name));
+                if !skipped {
+                    queue.push_back(path);
+                }
+            } else {
+                warn!("skipping {}: not a file or folder", path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn copy_file(
+    src_io: &impl IoTrait,
+    src: &Path,
+    dst_io: &impl IoTrait,
+    dst: &Path,
+) -> io::Result<()> {
+    let mut src_file = src_io.open(src).await?;
+    let mut dst_file = dst_io.create_new(dst).await?;
+    io::copy(&mut src_file, &mut dst_file).await?;
+    dst_file.flush().await
+}
+
+/// Sets `productName` and a new `productGUID` in ProjectSettings.asset like creating a new project.
+async fn set_product_identity(io: &impl IoTrait, product_name: &str) -> io::Result<()> {
+    let path = PROJECT_SETTINGS_PATH.as_ref();
+    let mut buffer = String::new();
+    match io.open(path).await {
+        Ok(mut file) => file.read_to_string(&mut buffer).await?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            warn!("ProjectSettings.asset not found");
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    set_product_identity_in_settings(&mut buffer, product_name);
+
+    io.write_sync(path, buffer.as_bytes()).await
+}
+
+/// Sets `productName` and a new `productGUID` in the contents of ProjectSettings.asset.
+///
+/// Unity and VRChat treat projects with different `productGUID` as different projects.
+pub fn set_product_identity_in_settings(settings: &mut String, product_name: &str) {
+    fn set_value(buffer: &mut String, finder: &str, value: &str) {
+        let Some(pos) = buffer.find(finder) else {
+            return;
+        };
+        let before_ws = buffer[..pos]
+            .chars()
+            .last()
+            .map(|x| x.is_ascii_whitespace())
+            .unwrap_or(true);
+        if !before_ws {
+            return;
+        }
+        if let Some(eol) = buffer[pos..].find('\n') {
+            let eol = eol + pos;
+            // keep CR of CRLF
+            let eol = if buffer[..eol].ends_with('\r') {
+                eol - 1
+            } else {
+                eol
+            };
+            buffer.replace_range((pos + finder.len())..eol, value);
+        }
+    }
+
+    fn yaml_quote(value: &str) -> String {
+        let s = value
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("\"{}\"", s)
+    }
+
+    set_value(
+        settings,
+        "productGUID: ",
+        &uuid::Uuid::new_v4().simple().to_string(),
+    );
+    set_value(settings, "productName: ", &yaml_quote(product_name));
+}
@@ -0,0 +1,50 @@
+use crate::UnityProject;
+use crate::io::ProjectIo;
+use crate::unity_project::{PendingProjectChanges, pending_project_changes};
+use crate::version::DependencyRange;
+use std::fmt;
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns the range of the package in dependencies of vpm-manifest.json.
+    pub fn get_dependency(&self, name: &str) -> Option<&DependencyRange> {
+        self.manifest.get_dependency(name)
+    }
+
+    /// Creates the changes to replace the range of the package in dependencies.
+    ///
+    /// This does not install or remove any packages even if the locked version is out of the new range.
+    /// Upgrade or resolve the project to install the version in the range.
+    pub fn set_dependency_range_request(
+        &self,
+        name: &str,
+        range: DependencyRange,
+    ) -> Result<PendingProjectChanges<'static>, SetDependencyRangeErr> {
+        if self.manifest.get_dependency(name).is_none() {
+            return Err(SetDependencyRangeErr::NotInDependencies {
+                package_name: name.into(),
+            });
+        }
+
+        let mut changes = pending_project_changes::Builder::new();
+        changes.add_to_dependencies(name.into(), range);
+        Ok(changes.build_no_resolve())
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SetDependencyRangeErr {
+    NotInDependencies { package_name: Box<str> },
+}
+
+impl fmt::Display for SetDependencyRangeErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetDependencyRangeErr::NotInDependencies { package_name } => {
+                write!(f, "Package {package_name} is not in dependencies")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SetDependencyRangeErr {}
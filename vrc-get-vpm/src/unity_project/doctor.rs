@@ -0,0 +1,358 @@
+use crate::io::ProjectIo;
+use crate::unity_project::IndexedAsset;
+use crate::unity_project::find_legacy_assets::collect_installed_legacy_assets;
+use crate::unity_project::upm_manifest::{self, UpmManifest};
+use crate::unity_project::vpm_manifest::{self, VpmManifest};
+use crate::version::Version;
+use crate::{UnityProject, io};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProblemSeverity {
+    /// The project may not work correctly
+    Error,
+    /// The project works but something is unexpected
+    Warning,
+}
+
+/// The problem of the project found by [`UnityProject::diagnose`] or [`diagnose_manifest_files`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ProjectProblem {
+    /// The manifest file cannot be parsed.
+    MalformedManifest { path: Box<Path>, message: Box<str> },
+    /// The last `apply_pending_changes` was interrupted.
+    UnfinishedChanges,
+    /// The package is locked in vpm-manifest.json but not installed.
+    MissingLockedPackage {
+        package_name: Box<str>,
+        version: Version,
+    },
+    /// The package is installed with version different from the locked version.
+    LockedVersionMismatch {
+        package_name: Box<str>,
+        locked: Version,
+        installed: Version,
+    },
+    /// The package is in dependencies of vpm-manifest.json but neither locked nor installed.
+    MissingDependency { package_name: Box<str> },
+    /// The package is in `vpmDependencies` of the unlocked package but neither locked nor installed.
+    MissingUnlockedDependency {
+        package_name: Box<str>,
+        required_by: Box<str>,
+    },
+    /// The folder in Packages is not locked in vpm-manifest.json.
+    UnlockedPackage {
+        folder_name: Box<str>,
+        package_name: Option<Box<str>>,
+    },
+    /// The legacy asset of the installed package still exists.
+    LegacyAsset {
+        path: Box<Path>,
+        package_name: Box<str>,
+    },
+    /// Assets in Assets folder and installed packages have the same GUID.
+    DuplicateGuid {
+        guid: Box<str>,
+        paths: Vec<Box<Path>>,
+    },
+}
+
+impl ProjectProblem {
+    pub fn severity(&self) -> ProblemSeverity {
+        match self {
+            ProjectProblem::MalformedManifest { .. }
+            | ProjectProblem::UnfinishedChanges
+            | ProjectProblem::MissingLockedPackage { .. }
+            | ProjectProblem::LockedVersionMismatch { .. }
+            | ProjectProblem::MissingDependency { .. }
+            | ProjectProblem::MissingUnlockedDependency { .. }
+            | ProjectProblem::DuplicateGuid { .. } => ProblemSeverity::Error,
+            ProjectProblem::UnlockedPackage { .. } | ProjectProblem::LegacyAsset { .. } => {
+                ProblemSeverity::Warning
+            }
+        }
+    }
+
+    /// Returns the suggested way to fix the problem.
+    pub fn suggested_fix(&self) -> &'static str {
+        match self {
+            ProjectProblem::MalformedManifest { .. } => {
+                "Fix the syntax error of the file or restore it from your backup"
+            }
+            ProjectProblem::UnfinishedChanges => "Finish or revert the interrupted changes",
+            ProjectProblem::MissingLockedPackage { .. }
+            | ProjectProblem::LockedVersionMismatch { .. }
+            | ProjectProblem::MissingDependency { .. } => {
+                "Resolve the project to install the locked packages"
+            }
+            ProjectProblem::MissingUnlockedDependency { .. } => {
+                "Resolve the project to install the dependencies of the unlocked package"
+            }
+            ProjectProblem::UnlockedPackage { .. } => {
+                "Remove the folder, or install the package to lock it if the package is a VPM package"
+            }
+            ProjectProblem::LegacyAsset { .. } => {
+                "Remove the legacy asset, or reinstall the package to remove it automatically"
+            }
+            ProjectProblem::DuplicateGuid { .. } => {
+                "Remove the duplicated assets in Assets folder which are provided by the package"
+            }
+        }
+    }
+}
+
+impl fmt::Display for ProjectProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectProblem::MalformedManifest { path, message } => {
+                write!(f, "{} is malformed: {}", path.display(), message)
+            }
+            ProjectProblem::UnfinishedChanges => {
+                write!(f, "The last changes to packages were interrupted")
+            }
+            ProjectProblem::MissingLockedPackage {
+                package_name,
+                version,
+            } => write!(
+                f,
+                "Package {package_name} version {version} is locked but not installed"
+            ),
+            ProjectProblem::LockedVersionMismatch {
+                package_name,
+                locked,
+                installed,
+            } => write!(
+                f,
+                "Package {package_name} is locked to {locked} but version {installed} is installed"
+            ),
+            ProjectProblem::MissingDependency { package_name } => {
+                write!(f, "Dependency {package_name} is not installed")
+            }
+            ProjectProblem::MissingUnlockedDependency {
+                package_name,
+                required_by,
+            } => write!(
+                f,
+                "Dependency {package_name} of unlocked package {required_by} is not installed"
+            ),
+            ProjectProblem::UnlockedPackage {
+                folder_name,
+                package_name: Some(package_name),
+            } => write!(
+                f,
+                "Package {package_name} at Packages/{folder_name} is not locked in vpm-manifest.json"
+            ),
+            ProjectProblem::UnlockedPackage {
+                folder_name,
+                package_name: None,
+            } => write!(
+                f,
+                "Packages/{folder_name} is not a package locked in vpm-manifest.json"
+            ),
+            ProjectProblem::LegacyAsset { path, package_name } => write!(
+                f,
+                "Legacy asset {} of package {package_name} still exists",
+                path.display()
+            ),
+            ProjectProblem::DuplicateGuid { guid, paths } => {
+                write!(f, "GUID {guid} is used by multiple assets: ")?;
+                let mut first = true;
+                for path in paths {
+                    if !first {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", path.display())?;
+                    first = false;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Checks manifest files of the project can be parsed.
+///
+/// Since [`UnityProject::load`] fails with malformed manifest files,
+/// this should be called before loading the project.
+pub async fn diagnose_manifest_files(io: &impl ProjectIo) -> io::Result<Vec<ProjectProblem>> {
+    let mut problems = Vec::new();
+
+    let results = [
+        (
+            vpm_manifest::MANIFEST_PATH,
+            VpmManifest::load(io).await.err(),
+        ),
+        (
+            upm_manifest::MANIFEST_PATH,
+            UpmManifest::load(io).await.err(),
+        ),
+    ];
+
+    for (path, error) in results {
+        match error {
+            None => {}
+            Some(e)
+                if e.kind() == io::ErrorKind::InvalidData
+                    || e.kind() == io::ErrorKind::UnexpectedEof =>
+            {
+                problems.push(ProjectProblem::MalformedManifest {
+                    path: Path::new(path).into(),
+                    message: e.to_string().into(),
+                });
+            }
+            Some(e) => return Err(e),
+        }
+    }
+
+    Ok(problems)
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Inspects the project and returns found problems.
+    ///
+    /// This does not check manifest files since loaded project should have valid manifest files.
+    /// Use [`diagnose_manifest_files`] for checking them.
+    pub async fn diagnose(&self) -> Vec<ProjectProblem> {
+        let mut problems = Vec::new();
+
+        if self.unfinished_changes.is_some() {
+            problems.push(ProjectProblem::UnfinishedChanges);
+        }
+
+        self.diagnose_packages(&mut problems);
+
+        let legacy_assets = collect_installed_legacy_assets(&self.io, self).await;
+        let mut legacy_assets = (legacy_assets.files.into_iter())
+            .chain(legacy_assets.folders)
+            .collect::<Vec<_>>();
+        legacy_assets.sort();
+        for (path, package_name) in legacy_assets {
+            problems.push(ProjectProblem::LegacyAsset {
+                path,
+                package_name: package_name.into(),
+            });
+        }
+
+        self.diagnose_guids(&mut problems).await;
+
+        problems
+    }
+
+    fn diagnose_packages(&self, problems: &mut Vec<ProjectProblem>) {
+        problems.extend(self.missing_package_problems());
+
+        for (folder_name, package) in self.unlocked_packages() {
+            // ejected packages are unlocked intentionally
+            if package
+                .as_ref()
+                .is_some_and(|x| self.ejected_packages.contains_key(x.name()))
+            {
+                continue;
+            }
+            problems.push(ProjectProblem::UnlockedPackage {
+                folder_name: folder_name.clone(),
+                package_name: package.as_ref().map(|x| x.name().into()),
+            });
+        }
+    }
+
+    /// Returns the problems of packages missing or installed with the wrong version, which are fixed by resolving.
+    ///
+    /// [`UnityProject::should_resolve`] returns true if any problem is returned.
+    pub(super) fn missing_package_problems(&self) -> Vec<ProjectProblem> {
+        let mut problems = Vec::new();
+        let mut installed_or_legacy = HashSet::<&str>::new();
+
+        // check locked packages
+        for locked in self.manifest.all_locked() {
+            let Some(installed) = self.installed_packages.get(locked.name()) else {
+                problems.push(ProjectProblem::MissingLockedPackage {
+                    package_name: locked.name().into(),
+                    version: locked.version().clone(),
+                });
+                continue;
+            };
+            if installed.version() != locked.version() {
+                problems.push(ProjectProblem::LockedVersionMismatch {
+                    package_name: locked.name().into(),
+                    locked: locked.version().clone(),
+                    installed: installed.version().clone(),
+                });
+            }
+            installed_or_legacy.insert(locked.name());
+            for legacy in installed.legacy_packages() {
+                installed_or_legacy.insert(legacy.as_ref());
+            }
+        }
+
+        // add legacy packages of unlocked packages to installed_or_legacy
+        for (_, package) in self.unlocked_packages() {
+            if let Some(package) = package {
+                for legacy in package.legacy_packages() {
+                    installed_or_legacy.insert(legacy.as_ref());
+                }
+
+                // using unlocked packages for resolving dependencies means broken vpm-manifest.json
+                // however, we cannot install package with same id as unlocked package
+                // so we add to installed_or_legacy and use them for checking
+                // if dependencies are installed
+                // note: this logic might be different from official VCC but this is our decision,
+                // we won't change this behavior unless we have a good reason
+                installed_or_legacy.insert(package.name());
+            }
+        }
+
+        // check dependencies. locked but not installed packages are reported above
+        for (dependency, _) in self.manifest.dependencies() {
+            if !installed_or_legacy.contains(dependency) && !self.is_locked(dependency) {
+                problems.push(ProjectProblem::MissingDependency {
+                    package_name: dependency.into(),
+                });
+            }
+        }
+
+        // check dependencies of unlocked packages
+        for (_, package) in self.unlocked_packages() {
+            let Some(package) = package else { continue };
+            for (dependency, _) in package.vpm_dependencies() {
+                if !installed_or_legacy.contains(dependency.as_ref()) && !self.is_locked(dependency)
+                {
+                    problems.push(ProjectProblem::MissingUnlockedDependency {
+                        package_name: dependency.clone(),
+                        required_by: package.name().into(),
+                    });
+                }
+            }
+        }
+
+        problems
+    }
+
+    async fn diagnose_guids(&self, problems: &mut Vec<ProjectProblem>) {
+        let is_installed = |asset: &&IndexedAsset| {
+            (asset.package()).is_none_or(|x| self.installed_packages.contains_key(x))
+        };
+
+        let index = self.build_guid_index().await;
+        for (guid, assets) in index.duplicates() {
+            let paths = (assets.iter().filter(is_installed))
+                .map(|x| x.path().into())
+                .collect::<Vec<_>>();
+            // only report assets in Assets folder conflicting with installed packages
+            let in_assets = assets.iter().any(|x| x.package().is_none());
+            let in_packages = assets
+                .iter()
+                .any(|x| x.package().is_some() && is_installed(&x));
+            if !in_assets || !in_packages {
+                continue;
+            }
+            problems.push(ProjectProblem::DuplicateGuid {
+                guid: guid.into(),
+                paths,
+            });
+        }
+    }
+}
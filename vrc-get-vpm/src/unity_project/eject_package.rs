@@ -0,0 +1,334 @@
+use crate::io::ProjectIo;
+use crate::unity_project::pending_project_changes::{Adopt, Eject};
+use crate::unity_project::{PendingProjectChanges, pending_project_changes};
+use crate::utils::{hash_folder_files, load_json_or_default, save_json};
+use crate::version::{DependencyRange, Version};
+use crate::{
+    PackageCollection, PackageInstaller, PackageManifest, UnityProject, VersionSelector, io,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// The file recording where the ejected packages came from.
+pub const EJECTED_PACKAGES_PATH: &str = "Packages/vrc-get-ejected.json";
+
+/// The origin of the package ejected from `locked` of vpm-manifest.json.
+///
+/// The files of ejected packages are kept in the project as unlocked packages
+/// so users can modify them freely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EjectedPackage {
+    version: Version,
+    /// The id or url of the repository the package was installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    repository: Option<Box<str>>,
+    /// The range in dependencies of vpm-manifest.json if the package was a dependency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dependency: Option<DependencyRange>,
+    /// sha256 of the files in the package folder when ejected.
+    #[serde(default)]
+    files: BTreeMap<Box<str>, Box<str>>,
+}
+
+impl EjectedPackage {
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_deref()
+    }
+
+    pub fn dependency(&self) -> Option<&DependencyRange> {
+        self.dependency.as_ref()
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    pub(super) async fn load_ejected_packages(io: &IO) -> IndexMap<Box<str>, EjectedPackage> {
+        match load_json_or_default(io, EJECTED_PACKAGES_PATH.as_ref()).await {
+            Ok(packages) => packages,
+            Err(e) => {
+                log::warn!("loading {EJECTED_PACKAGES_PATH}: {e}");
+                IndexMap::new()
+            }
+        }
+    }
+
+    pub(super) async fn save_ejected_packages(&self) -> io::Result<()> {
+        if self.ejected_packages.is_empty() {
+            match self.io.remove_file(EJECTED_PACKAGES_PATH.as_ref()).await {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            }
+        } else {
+            save_json(
+                &self.io,
+                EJECTED_PACKAGES_PATH.as_ref(),
+                &self.ejected_packages,
+            )
+            .await
+        }
+    }
+
+    /// Returns the packages ejected by [`UnityProject::eject_request`] and not adopted yet.
+    pub fn ejected_packages(&self) -> impl Iterator<Item = (&str, &EjectedPackage)> {
+        self.ejected_packages
+            .iter()
+            .map(|(name, package)| (name.as_ref(), package))
+    }
+
+    pub fn get_ejected(&self, name: &str) -> Option<&EjectedPackage> {
+        self.ejected_packages.get(name)
+    }
+
+    /// Creates the changes to eject the packages.
+    ///
+    /// Ejecting removes the package from vpm-manifest.json but keeps the files in the Packages folder,
+    /// so users can modify the package without it being restored by resolving.
+    /// The origin of the package is recorded to adopt the package later.
+    pub async fn eject_request(
+        &self,
+        names: &[&str],
+        collection: &impl PackageCollection,
+    ) -> Result<PendingProjectChanges<'static>, EjectPackageErr> {
+        let mut not_founds = Vec::new();
+        for name in names.iter().copied() {
+            if self.manifest.get_locked(name).is_none()
+                || !self.installed_packages.contains_key(name)
+            {
+                not_founds.push(name.into());
+            }
+        }
+
+        if !not_founds.is_empty() {
+            return Err(EjectPackageErr::NotInstalled(not_founds));
+        }
+
+        let mut changes = pending_project_changes::Builder::new();
+
+        for name in names.iter().copied() {
+            let version = self.manifest.get_locked(name).unwrap().version().clone();
+
+            let repository = collection
+                .find_package_by_name(name, VersionSelector::specific_version(&version))
+                .and_then(|x| x.repo())
+                .and_then(|repo| {
+                    (repo.id().map(Into::into)).or_else(|| repo.url().map(|x| x.as_str().into()))
+                });
+
+            let files = hash_package_files(&self.io, name).await?;
+
+            changes.eject(
+                name.into(),
+                Eject {
+                    package: EjectedPackage {
+                        version,
+                        repository,
+                        dependency: self.manifest.get_dependency(name).cloned(),
+                        files,
+                    },
+                },
+            );
+        }
+
+        Ok(changes.build_no_resolve())
+    }
+
+    /// Creates the changes to adopt the unlocked packages.
+    ///
+    /// Adopting adds the package back to vpm-manifest.json with the version in the package folder.
+    /// The files are compared with the zip file of the version in the collection, and files modified
+    /// locally are reported with [`Adopt::modified_files`].
+    /// If the zip file cannot be obtained, the files are compared with the hashes recorded when the package
+    /// was ejected by [`UnityProject::eject_request`] instead.
+    pub async fn adopt_request(
+        &self,
+        names: &[&str],
+        collection: &impl PackageCollection,
+        installer: &impl PackageInstaller,
+    ) -> Result<PendingProjectChanges<'static>, AdoptPackageErr> {
+        let mut not_founds = Vec::new();
+        let mut packages = Vec::new();
+        for name in names.iter().copied() {
+            let unlocked = self
+                .unlocked_packages
+                .iter()
+                .filter(|(folder, _)| folder.as_ref() == name)
+                .find_map(|(_, package)| package.as_ref())
+                .filter(|package| package.name() == name);
+            match unlocked {
+                Some(package) => packages.push(package),
+                None => not_founds.push(name.into()),
+            }
+        }
+
+        if !not_founds.is_empty() {
+            return Err(AdoptPackageErr::NotUnlocked(not_founds));
+        }
+
+        let mut changes = pending_project_changes::Builder::new();
+
+        for package in packages {
+            let ejected = self.ejected_packages.get(package.name());
+
+            let original = match self
+                .original_file_hashes(package, collection, installer)
+                .await
+            {
+                Some(original) => Some(original),
+                None => ejected.map(|x| x.files.clone()),
+            };
+            let modified_files = match original {
+                Some(original) => {
+                    let current = hash_package_files(&self.io, package.name()).await?;
+                    Some(modified_files(&original, &current))
+                }
+                None => None,
+            };
+
+            changes.adopt(
+                package.name().into(),
+                Adopt {
+                    version: package.version().clone(),
+                    dependencies: package.vpm_dependencies().clone(),
+                    to_dependencies: ejected.and_then(|x| x.dependency.clone()),
+                    modified_files,
+                },
+            );
+        }
+
+        Ok(changes.build_no_resolve())
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns the hashes of the files in the zip file of the package, or `None` if it cannot be obtained.
+    async fn original_file_hashes(
+        &self,
+        package: &PackageManifest,
+        collection: &impl PackageCollection,
+        installer: &impl PackageInstaller,
+    ) -> Option<BTreeMap<Box<str>, Box<str>>> {
+        let repository = self
+            .ejected_packages
+            .get(package.name())
+            .and_then(|x| x.repository());
+        let versions = collection
+            .find_packages(package.name())
+            .filter(|x| x.version() == package.version())
+            .collect::<Vec<_>>();
+        // prefer the repository the package was ejected from
+        let info = versions
+            .iter()
+            .find(|x| {
+                let repo = x.repo();
+                let id = repo.and_then(|x| x.id());
+                let url = repo.and_then(|x| x.url()).map(|x| x.as_str());
+                repository.is_some() && (id == repository || url == repository)
+            })
+            .or(versions.first())
+            .copied()?;
+
+        match installer.package_file_hashes(info).await {
+            Ok(hashes) => Some(hashes),
+            Err(e) => {
+                log::warn!(
+                    "reading files of {}@{} in the repository: {e}",
+                    package.name(),
+                    package.version()
+                );
+                None
+            }
+        }
+    }
+}
+
+async fn hash_package_files(
+    io: &impl ProjectIo,
+    name: &str,
+) -> io::Result<BTreeMap<Box<str>, Box<str>>> {
+    hash_folder_files(io, &PathBuf::from(format!("Packages/{name}"))).await
+}
+
+fn modified_files(
+    original: &BTreeMap<Box<str>, Box<str>>,
+    current: &BTreeMap<Box<str>, Box<str>>,
+) -> Vec<Box<str>> {
+    let changed_or_removed = original
+        .iter()
+        .filter(|(path, hash)| current.get(*path) != Some(hash))
+        .map(|(path, _)| path.clone());
+    let added = current
+        .keys()
+        .filter(|path| !original.contains_key(*path))
+        .cloned();
+
+    let mut files = changed_or_removed.chain(added).collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EjectPackageErr {
+    Io(io::Error),
+    NotInstalled(Vec<Box<str>>),
+}
+
+impl fmt::Display for EjectPackageErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EjectPackageErr::Io(ioerr) => fmt::Display::fmt(ioerr, f),
+            EjectPackageErr::NotInstalled(names) => {
+                write!(
+                    f,
+                    "the following packages are not installed as locked packages: {}",
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for EjectPackageErr {}
+
+impl From<io::Error> for EjectPackageErr {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AdoptPackageErr {
+    Io(io::Error),
+    NotUnlocked(Vec<Box<str>>),
+}
+
+impl fmt::Display for AdoptPackageErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdoptPackageErr::Io(ioerr) => fmt::Display::fmt(ioerr, f),
+            AdoptPackageErr::NotUnlocked(names) => {
+                write!(
+                    f,
+                    "the following packages are not installed as unlocked packages: {}",
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdoptPackageErr {}
+
+impl From<io::Error> for AdoptPackageErr {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
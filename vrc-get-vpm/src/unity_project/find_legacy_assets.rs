@@ -7,7 +7,7 @@ use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use hex::FromHex;
 use log::debug;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::pin::pin;
@@ -16,6 +16,8 @@ use std::pin::pin;
 pub(crate) struct LegacyAssets<'a> {
     pub(crate) files: Vec<(Box<Path>, &'a str)>,
     pub(crate) folders: Vec<(Box<Path>, &'a str)>,
+    /// The assets found by GUID since they are not at the defined path
+    pub(crate) found_by_guid: HashSet<Box<Path>>,
 }
 
 pub(crate) async fn collect_legacy_assets<'a>(
@@ -70,6 +72,34 @@ pub(crate) async fn collect_legacy_assets<'a>(
     // but the compiler fails so collect it here.
     let assets = folders.chain(files).collect::<Vec<_>>();
 
+    find_legacy_assets(io, assets).await
+}
+
+/// Collects legacy assets defined by installed packages but still exists in the project.
+pub(crate) async fn collect_installed_legacy_assets<'a>(
+    io: &impl ProjectIo,
+    unity_project: &'a UnityProject<impl ProjectIo>,
+) -> LegacyAssets<'a> {
+    let mut assets = Vec::new();
+
+    for (name, package) in unity_project.installed_packages() {
+        for (path, guid) in package.legacy_folders() {
+            let guid = guid.as_deref().and_then(Guid::parse);
+            assets.push(DefinedLegacyInfo::new_dir(name, path, guid));
+        }
+        for (path, guid) in package.legacy_files() {
+            let guid = guid.as_deref().and_then(Guid::parse);
+            assets.push(DefinedLegacyInfo::new_file(name, path, guid));
+        }
+    }
+
+    find_legacy_assets(io, assets).await
+}
+
+async fn find_legacy_assets<'a>(
+    io: &impl ProjectIo,
+    assets: Vec<DefinedLegacyInfo<'a>>,
+) -> LegacyAssets<'a> {
     if assets.is_empty() {
         debug!("There are no legacy assets");
         return LegacyAssets::default();
@@ -79,14 +109,23 @@ pub(crate) async fn collect_legacy_assets<'a>(
     let (mut found_files, mut found_folders, find_guids) =
         find_legacy_assets_by_path(io, assets.into_iter()).await;
 
+    let mut found_by_guid = HashSet::new();
     if !find_guids.is_empty() {
         debug!("Collecting legacy assets with GUID");
-        find_legacy_assets_by_guid(io, find_guids, &mut found_files, &mut found_folders).await;
+        find_legacy_assets_by_guid(
+            io,
+            find_guids,
+            &mut found_files,
+            &mut found_folders,
+            &mut found_by_guid,
+        )
+        .await;
     }
 
     LegacyAssets {
         files: found_files.into_iter().collect(),
         folders: found_folders.into_iter().collect(),
+        found_by_guid,
     }
 }
 
@@ -185,7 +224,7 @@ async fn check_guid(io: &impl ProjectIo, path: &Path, guid: Option<Guid>) -> boo
     true
 }
 
-async fn try_parse_meta(io: &impl ProjectIo, path: &Path) -> Option<Guid> {
+pub(super) async fn try_parse_meta(io: &impl ProjectIo, path: &Path) -> Option<Guid> {
     let mut file = BufReader::new(io.open(path).await.ok()?);
     let mut buffer = String::new();
     while file.read_line(&mut buffer).await.ok()? != 0 {
@@ -205,6 +244,7 @@ async fn find_legacy_assets_by_guid<'a>(
     mut find_guids: HashMap<Guid, (&'a str, bool)>,
     found_files: &mut HashMap<Box<Path>, &'a str>,
     found_folders: &mut HashMap<Box<Path>, &'a str>,
+    found_by_guid: &mut HashSet<Box<Path>>,
 ) {
     async fn get_guid<IO: ProjectIo>(io: &IO, relative: PathBuf) -> Option<(Guid, bool, PathBuf)> {
         if relative.extension() != Some(OsStr::new("meta")) {
@@ -228,10 +268,12 @@ async fn find_legacy_assets_by_guid<'a>(
         if let Some(&(package_name, is_file)) = find_guids.get(&guid) {
             if is_file_actual == is_file {
                 find_guids.remove(&guid);
+                let relative = relative.into_boxed_path();
+                found_by_guid.insert(relative.clone());
                 if is_file {
-                    found_files.insert(relative.into_boxed_path(), package_name);
+                    found_files.insert(relative, package_name);
                 } else {
-                    found_folders.insert(relative.into_boxed_path(), package_name);
+                    found_folders.insert(relative, package_name);
                 }
             }
         }
@@ -270,11 +312,17 @@ enum LegacySearchResult<'a> {
     SearchWithGuid(&'a str, Guid, bool),
 }
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
-struct Guid([u8; 16]);
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub(super) struct Guid([u8; 16]);
 
 impl Guid {
-    fn parse(guid: &str) -> Option<Guid> {
+    pub(super) fn parse(guid: &str) -> Option<Guid> {
         FromHex::from_hex(guid).ok().map(Guid)
     }
 }
+
+impl std::fmt::Display for Guid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::UnityProject;
+use crate::io::{DirEntry, ProjectIo};
+use crate::unity_project::find_legacy_assets::{Guid, try_parse_meta};
+use crate::utils::walk_dir_relative;
+use futures::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use std::pin::pin;
+
+/// The asset with the GUID in the `.meta` file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IndexedAsset {
+    path: Box<Path>,
+    package: Option<Box<str>>,
+}
+
+impl IndexedAsset {
+    /// The path of the asset relative to the project root, without `.meta` extension
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The name of the package containing the asset, or `None` for assets in `Assets`.
+    ///
+    /// This is the name in `package.json` of the folder in `Packages`,
+    /// or the name of the folder if `package.json` is missing.
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+}
+
+/// The index of the GUIDs of the assets in `Assets` and `Packages`, built by [`UnityProject::build_guid_index`].
+#[derive(Debug, Clone, Default)]
+pub struct GuidIndex {
+    assets: HashMap<Guid, Vec<IndexedAsset>>,
+    missing_meta_files: Vec<Box<Path>>,
+}
+
+impl GuidIndex {
+    /// Returns the assets with the GUID. Empty if no asset has the GUID or the GUID is malformed.
+    pub fn get(&self, guid: &str) -> &[IndexedAsset] {
+        Guid::parse(guid)
+            .and_then(|guid| self.assets.get(&guid))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the package containing the asset with the GUID, or `None` if the asset is not in packages.
+    ///
+    /// If multiple packages have the GUID, the first one in order of the path is returned.
+    pub fn find_package(&self, guid: &str) -> Option<&str> {
+        self.get(guid).iter().find_map(IndexedAsset::package)
+    }
+
+    /// Returns all GUIDs with the assets, sorted by the GUID.
+    pub fn guids(&self) -> impl Iterator<Item = (String, &[IndexedAsset])> {
+        let mut guids = self
+            .assets
+            .iter()
+            .map(|(guid, assets)| (guid.to_string(), assets.as_slice()))
+            .collect::<Vec<_>>();
+        guids.sort_by(|(a, _), (b, _)| a.cmp(b));
+        guids.into_iter()
+    }
+
+    /// Returns GUIDs used by multiple assets, sorted by the GUID.
+    ///
+    /// Unity assigns a new GUID to one of the assets, which breaks references to the asset.
+    /// This usually happens when a package installed with vpm is also imported to `Assets` with unitypackage.
+    pub fn duplicates(&self) -> impl Iterator<Item = (String, &[IndexedAsset])> {
+        self.guids().filter(|(_, assets)| assets.len() > 1)
+    }
+
+    /// Returns the assets without `.meta` files, sorted by the path.
+    ///
+    /// Unity generates `.meta` files with new GUIDs for them, so references to the assets may break.
+    pub fn missing_meta_files(&self) -> &[Box<Path>] {
+        &self.missing_meta_files
+    }
+}
+
+/// Returns true if Unity does not import the file or folder with the name.
+fn is_ignored_by_unity(name: &OsStr) -> bool {
+    let Some(name) = name.to_str() else {
+        return false;
+    };
+    name.starts_with('.')
+        || name.ends_with('~')
+        || name.eq_ignore_ascii_case("cvs")
+        || name.ends_with(".tmp")
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Builds the index of the GUIDs in the `.meta` files of the assets in `Assets` and the folders in `Packages`.
+    ///
+    /// Files and folders Unity does not import, like hidden ones or ones ending with `~`, are skipped.
+    pub async fn build_guid_index(&self) -> GuidIndex {
+        let mut roots = vec![(PathBuf::from("Assets"), None)];
+        if let Ok(mut packages) = self.io.read_dir("Packages".as_ref()).await {
+            while let Some(Ok(entry)) = packages.next().await {
+                let is_dir = entry.file_type().await.is_ok_and(|x| x.is_dir());
+                let name = entry.file_name();
+                if !is_dir || is_ignored_by_unity(&name) {
+                    continue;
+                }
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                roots.push((
+                    Path::new("Packages").join(name),
+                    Some(self.package_name_in_folder(name)),
+                ));
+            }
+        }
+
+        let mut index = GuidIndex::default();
+        let mut assets = Vec::new();
+        let mut meta_files = HashSet::new();
+
+        for (root, package) in roots {
+            let mut stream = pin!(walk_dir_relative(&self.io, [root.clone()]));
+            while let Some((relative, _)) = stream.next().await {
+                let ignored = relative
+                    .strip_prefix(&root)
+                    .unwrap()
+                    .components()
+                    .any(|x| matches!(x, Component::Normal(name) if is_ignored_by_unity(name)));
+                if ignored {
+                    continue;
+                }
+                if relative.extension() != Some(OsStr::new("meta")) {
+                    assets.push(relative);
+                    continue;
+                }
+
+                // remove .meta extension
+                let path = relative.with_extension("").into_boxed_path();
+                if let Some(guid) = try_parse_meta(&self.io, &relative).await {
+                    index.assets.entry(guid).or_default().push(IndexedAsset {
+                        path: path.clone(),
+                        package: package.clone(),
+                    });
+                }
+                meta_files.insert(path);
+            }
+        }
+
+        for assets in index.assets.values_mut() {
+            assets.sort();
+        }
+
+        index.missing_meta_files = assets
+            .into_iter()
+            .filter(|x| !meta_files.contains(x.as_path()))
+            .map(PathBuf::into_boxed_path)
+            .collect();
+        index.missing_meta_files.sort();
+
+        index
+    }
+
+    /// Returns the name in `package.json` of the folder in `Packages`, or the folder name if it's missing.
+    fn package_name_in_folder(&self, folder: &str) -> Box<str> {
+        if self.installed_packages.contains_key(folder) {
+            // installed packages are in the folder with the package name
+            return folder.into();
+        }
+        self.unlocked_packages
+            .iter()
+            .find(|(name, _)| name.as_ref() == folder)
+            .and_then(|(_, json)| json.as_ref())
+            .map_or(folder, |json| json.name())
+            .into()
+    }
+}
@@ -0,0 +1,74 @@
+use crate::environment::read_package_archive;
+use crate::io::{DirEntry, FileSystemProjectIo, IoTrait, ProjectIo};
+use crate::{PackageManifest, UnityProject, io};
+use futures::prelude::*;
+use log::warn;
+use std::path::{Path, PathBuf};
+
+/// The folder in the project to keep zip files of packages not available in any repository.
+///
+/// Packages in this folder are available for the project like packages in repositories,
+/// so the project can be resolved on other machines if the folder is shared through version control.
+pub const PROJECT_LOCAL_PACKAGES_PATH: &str = "VpmLocalPackages";
+
+impl<IO: FileSystemProjectIo + ProjectIo> UnityProject<IO> {
+    /// Copies the zip file of the package into the project-local packages folder.
+    ///
+    /// Returns the absolute path to the copied zip file.
+    pub async fn add_local_package_archive(
+        &self,
+        source_io: &impl IoTrait,
+        archive: &Path,
+        manifest: &PackageManifest,
+    ) -> io::Result<PathBuf> {
+        let path = PathBuf::from(format!(
+            "{PROJECT_LOCAL_PACKAGES_PATH}/{}-{}.zip",
+            manifest.name(),
+            manifest.version()
+        ));
+
+        self.io
+            .create_dir_all(PROJECT_LOCAL_PACKAGES_PATH.as_ref())
+            .await?;
+
+        let mut source = source_io.open(archive).await?;
+        let mut dest = self.io.create(&path).await?;
+        io::copy(&mut source, &mut dest).await?;
+        dest.flush().await?;
+
+        std::path::absolute(self.project_dir().join(path))
+    }
+
+    /// Loads the zip files of packages in the project-local packages folder.
+    ///
+    /// The returned paths are absolute.
+    pub async fn local_package_archives(&self) -> io::Result<Vec<(PathBuf, PackageManifest)>> {
+        let mut dir = match self.io.read_dir(PROJECT_LOCAL_PACKAGES_PATH.as_ref()).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let mut packages = vec![];
+        while let Some(entry) = dir.try_next().await? {
+            let file_name = entry.file_name();
+            if !Path::new(&file_name)
+                .extension()
+                .is_some_and(|x| x.eq_ignore_ascii_case("zip"))
+            {
+                continue;
+            }
+
+            let path = Path::new(PROJECT_LOCAL_PACKAGES_PATH).join(&file_name);
+            match read_package_archive(&self.io, &path).await {
+                Ok(manifest) => {
+                    let absolute = std::path::absolute(self.project_dir().join(&path))?;
+                    packages.push((absolute, manifest));
+                }
+                Err(e) => warn!("loading package archive {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(packages)
+    }
+}
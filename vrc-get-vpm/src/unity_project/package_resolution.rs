@@ -1,5 +1,6 @@
 use crate::traits::PackageCollection;
 use crate::unity_project::LockedDependencyInfo;
+use crate::unity_project::vpm_manifest::DependencyOverrides;
 use crate::version::{DependencyRange, PrereleaseAcceptance, UnityVersion, Version, VersionRange};
 use crate::{PackageInfo, PackageManifest, VersionSelector};
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -41,6 +42,7 @@ where
     pub pending_queue: PackageQueue<'env>,
     dependencies: HashMap<&'a str, DependencyInfo<'env, 'a>>,
     unlocked_names: HashSet<&'a str>,
+    overrides: DependencyOverrides<'a>,
 }
 
 struct Legacy<'env>(&'env [Box<str>]);
@@ -119,13 +121,18 @@ where
     }
 }
 
-impl<'env> ResolutionContext<'env, '_> {
-    fn new(allow_prerelease: bool, packages: Vec<PackageInfo<'env>>) -> Self {
+impl<'env, 'a> ResolutionContext<'env, 'a> {
+    fn new(
+        allow_prerelease: bool,
+        packages: Vec<PackageInfo<'env>>,
+        overrides: DependencyOverrides<'a>,
+    ) -> Self {
         let mut this = Self {
             dependencies: HashMap::new(),
             pending_queue: PackageQueue::new(packages),
             allow_prerelease,
             unlocked_names: HashSet::new(),
+            overrides,
         };
 
         for pkg in &this.pending_queue.pending_queue {
@@ -182,6 +189,7 @@ where
         }
 
         for (dependency, range) in manifest.vpm_dependencies() {
+            let range = self.overrides.apply(manifest.name(), dependency, range);
             self.dependencies
                 .entry(dependency)
                 .or_default()
@@ -217,6 +225,7 @@ where
         }
 
         for (dependency, range) in locked.dependencies() {
+            let range = self.overrides.apply(locked.name(), dependency, range);
             self.dependencies
                 .entry(dependency)
                 .or_default()
@@ -257,6 +266,7 @@ where
             self.dependencies.get_mut(*dep).unwrap().remove_range(name);
         }
         for (dependency, range) in vpm_dependencies.iter() {
+            let range = self.overrides.apply(name, dependency, range);
             self.dependencies
                 .entry(dependency)
                 .or_default()
@@ -432,9 +442,10 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
     env: &'env impl PackageCollection,
     packages: Vec<PackageInfo<'env>>,
     allow_prerelease: bool,
+    overrides: DependencyOverrides<'a>,
     missing_dependencies: &mut MissingDependencies,
 ) -> PackageResolutionResult<'env> {
-    let mut context = ResolutionContext::<'env, '_>::new(allow_prerelease, packages);
+    let mut context = ResolutionContext::<'env, '_>::new(allow_prerelease, packages, overrides);
 
     // first, add dependencies
     let root_dependencies = dependencies
@@ -486,6 +497,7 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
         if context.add_package(x, force) {
             // add new dependencies
             for (dependency, range) in vpm_dependencies.iter() {
+                let range = overrides.apply(name, dependency, range);
                 log::debug!("processing package {name}: dependency {dependency} version {range}");
 
                 if context.should_add_package(dependency, range) {
@@ -505,7 +517,7 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
                     struct PackageFinder<'env, 'a, C: PackageCollection> {
                         dependency: &'a str,
                         env: &'env C,
-                        range: &'env VersionRange,
+                        range: &'a VersionRange,
                     }
 
                     impl<'env, C: PackageCollection> PackageFinder<'env, '_, C> {
@@ -1,14 +1,16 @@
 use crate::io::{DirEntry, ProjectIo};
 use crate::traits::AbortCheck;
+use crate::unity_project::eject_package::EjectedPackage;
 use crate::unity_project::find_legacy_assets::collect_legacy_assets;
+use crate::unity_project::unfinished_changes::{JournalStep, UnfinishedChanges};
 use crate::utils::walk_dir_relative;
-use crate::version::DependencyRange;
+use crate::version::{DependencyRange, Version, VersionRange};
 use crate::{PackageInfo, UnityProject, unity_compatible};
 use crate::{PackageInstaller, io};
 use either::Either;
 use futures::future::{join, join_all};
 use futures::prelude::*;
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use log::debug;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -33,26 +35,44 @@ pub struct PendingProjectChanges<'env> {
     pub(crate) remove_legacy_folders: Vec<(Box<Path>, &'env str)>,
 
     pub(crate) conflicts: HashMap<Box<str>, ConflictInfo>,
+
+    pub(crate) dependency_overrides: Vec<(Box<str>, VersionRange)>,
 }
 
 #[derive(Debug)]
 pub enum PackageChange<'env> {
     Install(Install<'env>),
     Remove(Remove<'env>),
+    Eject(Eject),
+    Adopt(Adopt),
 }
 
 impl<'env> PackageChange<'env> {
     pub fn as_install(&self) -> Option<&Install<'env>> {
         match self {
             PackageChange::Install(x) => Some(x),
-            PackageChange::Remove(_) => None,
+            _ => None,
         }
     }
 
     pub fn as_remove(&self) -> Option<&Remove<'env>> {
         match self {
-            PackageChange::Install(_) => None,
             PackageChange::Remove(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_eject(&self) -> Option<&Eject> {
+        match self {
+            PackageChange::Eject(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_adopt(&self) -> Option<&Adopt> {
+        match self {
+            PackageChange::Adopt(x) => Some(x),
+            _ => None,
         }
     }
 }
@@ -62,6 +82,7 @@ pub struct Install<'env> {
     package: Option<PackageInfo<'env>>,
     add_to_locked: bool,
     to_dependencies: Option<DependencyRange>,
+    replaced_dependency: Option<DependencyRange>,
 }
 
 impl<'env> Install<'env> {
@@ -76,6 +97,13 @@ impl<'env> Install<'env> {
     pub fn to_dependencies(&self) -> Option<&DependencyRange> {
         self.to_dependencies.as_ref()
     }
+
+    /// The range in dependencies replaced with [`to_dependencies`](Self::to_dependencies).
+    ///
+    /// This is set when upgrading the package to the version out of the range in dependencies.
+    pub fn replaced_dependency(&self) -> Option<&DependencyRange> {
+        self.replaced_dependency.as_ref()
+    }
 }
 
 #[derive(Debug)]
@@ -90,6 +118,45 @@ impl Remove<'_> {
     }
 }
 
+/// Removes the package from vpm-manifest.json but keeps the files as an unlocked package.
+#[derive(Debug)]
+pub struct Eject {
+    pub(crate) package: EjectedPackage,
+}
+
+impl Eject {
+    pub fn ejected_package(&self) -> &EjectedPackage {
+        &self.package
+    }
+}
+
+/// Adds the unlocked package to vpm-manifest.json without reinstalling the files.
+#[derive(Debug)]
+pub struct Adopt {
+    pub(crate) version: Version,
+    pub(crate) dependencies: IndexMap<Box<str>, VersionRange>,
+    pub(crate) to_dependencies: Option<DependencyRange>,
+    pub(crate) modified_files: Option<Vec<Box<str>>>,
+}
+
+impl Adopt {
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn to_dependencies(&self) -> Option<&DependencyRange> {
+        self.to_dependencies.as_ref()
+    }
+
+    /// The files modified locally, compared with the zip file of the package.
+    ///
+    /// If the zip file cannot be obtained, this is the files modified after the package was ejected.
+    /// This is `None` if neither the zip file nor the record of ejecting is available.
+    pub fn modified_files(&self) -> Option<&[Box<str>]> {
+        self.modified_files.as_deref()
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RemoveReason {
     Requested,
@@ -142,7 +209,7 @@ impl<'env> Builder<'env> {
                         panic!("INTERNAL ERROR: already add_to_dependencies");
                     }
                 }
-                PackageChange::Remove(_) => {
+                PackageChange::Remove(_) | PackageChange::Eject(_) | PackageChange::Adopt(_) => {
                     panic!("INTERNAL ERROR: add_to_dependencies for removed");
                 }
             },
@@ -151,6 +218,7 @@ impl<'env> Builder<'env> {
                     package: None,
                     add_to_locked: false,
                     to_dependencies: Some(version),
+                    replaced_dependency: None,
                 }));
             }
         }
@@ -158,6 +226,19 @@ impl<'env> Builder<'env> {
         self
     }
 
+    pub fn replace_dependency(
+        &mut self,
+        name: Box<str>,
+        replaced: DependencyRange,
+        version: DependencyRange,
+    ) -> &mut Self {
+        self.add_to_dependencies(name.clone(), version);
+        if let Some(PackageChange::Install(e)) = self.package_changes.get_mut(&name) {
+            e.replaced_dependency = Some(replaced);
+        }
+        self
+    }
+
     pub fn install_to_locked(&mut self, info: PackageInfo<'env>) -> &mut Self {
         match self.package_changes.entry(info.name().into()) {
             Entry::Occupied(mut e) => match e.get_mut() {
@@ -169,7 +250,7 @@ impl<'env> Builder<'env> {
                         panic!("INTERNAL ERROR: already install");
                     }
                 }
-                PackageChange::Remove(_) => {
+                PackageChange::Remove(_) | PackageChange::Eject(_) | PackageChange::Adopt(_) => {
                     panic!("INTERNAL ERROR: install for removed");
                 }
             },
@@ -178,6 +259,7 @@ impl<'env> Builder<'env> {
                     package: Some(info),
                     add_to_locked: true,
                     to_dependencies: None,
+                    replaced_dependency: None,
                 }));
             }
         }
@@ -195,7 +277,7 @@ impl<'env> Builder<'env> {
                         panic!("INTERNAL ERROR: already install");
                     }
                 }
-                PackageChange::Remove(_) => {
+                PackageChange::Remove(_) | PackageChange::Eject(_) | PackageChange::Adopt(_) => {
                     panic!("INTERNAL ERROR: install for removed");
                 }
             },
@@ -204,6 +286,7 @@ impl<'env> Builder<'env> {
                     package: Some(info),
                     add_to_locked: false,
                     to_dependencies: None,
+                    replaced_dependency: None,
                 }));
             }
         }
@@ -253,7 +336,7 @@ impl<'env> Builder<'env> {
     pub fn remove(&mut self, name: Box<str>, reason: RemoveReason) -> &mut Self {
         match self.package_changes.entry(name) {
             Entry::Occupied(mut e) => match e.get_mut() {
-                PackageChange::Install(_) => {
+                PackageChange::Install(_) | PackageChange::Eject(_) | PackageChange::Adopt(_) => {
                     panic!("INTERNAL ERROR: remove for installed");
                 }
                 PackageChange::Remove(e) => {
@@ -272,10 +355,30 @@ impl<'env> Builder<'env> {
         self
     }
 
+    pub fn eject(&mut self, name: Box<str>, eject: Eject) -> &mut Self {
+        match self.package_changes.entry(name) {
+            Entry::Occupied(_) => panic!("INTERNAL ERROR: eject for changed package"),
+            Entry::Vacant(e) => {
+                e.insert(PackageChange::Eject(eject));
+            }
+        }
+        self
+    }
+
+    pub fn adopt(&mut self, name: Box<str>, adopt: Adopt) -> &mut Self {
+        match self.package_changes.entry(name) {
+            Entry::Occupied(_) => panic!("INTERNAL ERROR: adopt for changed package"),
+            Entry::Vacant(e) => {
+                e.insert(PackageChange::Adopt(adopt));
+            }
+        }
+        self
+    }
+
     fn remove_unused(&mut self, name: Box<str>) -> &mut Self {
         match self.package_changes.entry(name) {
             Entry::Occupied(mut e) => match e.get_mut() {
-                PackageChange::Install(_) => {
+                PackageChange::Install(_) | PackageChange::Eject(_) | PackageChange::Adopt(_) => {
                     panic!("INTERNAL ERROR: remove_unused for installed");
                 }
                 PackageChange::Remove(_) => {
@@ -326,6 +429,8 @@ impl<'env> Builder<'env> {
                 PackageChange::Remove(_) => {
                     panic!("INTERNAL ERROR: remove requires resolve")
                 }
+                // eject and adopt do not move any files, so nothing to resolve
+                PackageChange::Eject(_) | PackageChange::Adopt(_) => {}
             }
         }
 
@@ -335,6 +440,8 @@ impl<'env> Builder<'env> {
 
             remove_legacy_files: vec![],
             remove_legacy_folders: vec![],
+
+            dependency_overrides: vec![],
         }
     }
 
@@ -379,6 +486,13 @@ impl<'env> Builder<'env> {
 
             remove_legacy_files: legacy_assets.files,
             remove_legacy_folders: legacy_assets.folders,
+
+            dependency_overrides: unity_project
+                .manifest
+                .overrides()
+                .iter()
+                .map(|(key, range)| (key.into(), range.clone()))
+                .collect(),
         }
     }
 
@@ -427,6 +541,7 @@ impl<'env> Builder<'env> {
                         PackageChange::Remove(_) => {
                             unity_project.get_locked(name.as_ref()).map(|x| x.name())
                         }
+                        PackageChange::Eject(_) | PackageChange::Adopt(_) => None,
                     });
 
             mark_recursive(entrypoint, |dep_name| {
@@ -522,6 +637,13 @@ impl PendingProjectChanges<'_> {
     pub fn conflicts(&self) -> &HashMap<Box<str>, ConflictInfo> {
         &self.conflicts
     }
+
+    /// Returns the overrides in vpm-manifest.json applied when resolving the changes.
+    ///
+    /// See [`UnityProject::dependency_overrides`] for the format of the keys.
+    pub fn dependency_overrides(&self) -> &[(Box<str>, VersionRange)] {
+        self.dependency_overrides.as_slice()
+    }
 }
 
 static TEMP_DIR: &str = "Temp";
@@ -568,6 +690,13 @@ impl<IO: ProjectIo> UnityProject<IO> {
         let mut installs = Vec::new();
         let mut remove_names = Vec::new();
         let mut remove_unlocked_names = Vec::new();
+        let mut eject_names = Vec::new();
+
+        if self.unfinished_changes.is_some() {
+            return Err(io::Error::other(
+                "the project has unfinished changes. roll forward or rollback them first",
+            ));
+        }
 
         for (name, change) in &request.package_changes {
             match change {
@@ -579,6 +708,10 @@ impl<IO: ProjectIo> UnityProject<IO> {
                 PackageChange::Remove(_) => {
                     remove_names.push(name.as_ref());
                 }
+                PackageChange::Eject(_) => {
+                    eject_names.push(name.as_ref());
+                }
+                PackageChange::Adopt(_) => {}
             }
         }
 
@@ -588,75 +721,203 @@ impl<IO: ProjectIo> UnityProject<IO> {
             }
         }
 
-        // remove packages
-        let remove_temp_dir = format!("{}/{}", PKG_TEMP_DIR, uuid::Uuid::new_v4());
-        let remove_temp_dir = Path::new(&remove_temp_dir);
-
-        self.io.create_dir_all(remove_temp_dir).await?;
+        // compute changes to manifest. this will be saved after removing packages
+        let mut manifest = self.manifest.clone();
 
-        move_packages_to_temp(
-            &self.io,
-            (remove_names.iter().copied())
-                .chain(installs.iter().map(|x| x.name()))
-                .chain(remove_unlocked_names.iter().copied()),
-            remove_temp_dir,
-        )
-        .await?;
-
-        // apply changes to manifest
         for (name, change) in &request.package_changes {
             match change {
                 PackageChange::Install(change) => {
                     if let Some(package) = change.package {
                         if change.add_to_locked {
-                            self.manifest.add_locked(
+                            manifest.add_locked(
                                 package.name(),
                                 package.version().clone(),
                                 package.vpm_dependencies().clone(),
+                                package.git_package(),
                             );
                         }
                     }
 
                     if let Some(version) = &change.to_dependencies {
-                        self.manifest.add_dependency(name, version.clone());
+                        manifest.add_dependency(name, version.clone());
+                        let git = change.package.and_then(|x| x.git_package());
+                        if let Some(git) = git {
+                            manifest.set_dependency_git(name, &git.source().to_string());
+                        }
+                    }
+                }
+                PackageChange::Adopt(change) => {
+                    manifest.add_locked(
+                        name,
+                        change.version.clone(),
+                        change.dependencies.clone(),
+                        None,
+                    );
+
+                    if let Some(version) = &change.to_dependencies {
+                        manifest.add_dependency(name, version.clone());
                     }
                 }
-                PackageChange::Remove(_) => {}
+                PackageChange::Remove(_) | PackageChange::Eject(_) => {}
             }
         }
 
-        self.manifest.remove_packages(remove_names.iter().copied());
+        manifest.remove_packages(remove_names.iter().chain(&eject_names).copied());
 
-        // save manifest
+        // record the changes to the journal before modifying anything
+        let remove_temp_dir = format!("{}/{}", PKG_TEMP_DIR, uuid::Uuid::new_v4());
 
-        self.save().await?;
+        let journal = UnfinishedChanges {
+            step: JournalStep::MovingPackages,
+            temp_dir: PathBuf::from(remove_temp_dir).into_boxed_path(),
+            move_packages: (remove_names.iter().copied())
+                .chain(installs.iter().map(|x| x.name()))
+                .chain(remove_unlocked_names.iter().copied())
+                .collect::<IndexSet<_>>()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            install_packages: installs
+                .iter()
+                .map(|x| (x.name().into(), x.version().clone()))
+                .collect(),
+            remove_legacy_files: request
+                .remove_legacy_files
+                .iter()
+                .map(|(p, _)| p.clone())
+                .collect(),
+            remove_legacy_folders: request
+                .remove_legacy_folders
+                .iter()
+                .map(|(p, _)| p.clone())
+                .collect(),
+            manifest_before: self.manifest.to_json()?,
+            manifest_after: manifest.to_json()?,
+        };
 
-        // add packages
+        self.io.create_dir_all(&journal.temp_dir).await?;
+        journal.save(&self.io).await?;
 
-        install_packages(&self.io, env, &installs).await?;
+        self.continue_unfinished_changes(env, &installs, journal)
+            .await?;
 
-        self.io.remove_dir_all(remove_temp_dir).await.ok();
-        self.io.remove_dir_all(PKG_TEMP_DIR.as_ref()).await.ok();
-        // remove temp dir also if it's empty
-        self.io.remove_dir(TEMP_DIR.as_ref()).await.ok();
+        self.update_ejected_packages(request.package_changes).await
+    }
+
+    async fn update_ejected_packages(
+        &mut self,
+        changes: HashMap<Box<str>, PackageChange<'_>>,
+    ) -> io::Result<()> {
+        let mut updated = false;
+
+        for (name, change) in changes {
+            match change {
+                PackageChange::Eject(eject) => {
+                    self.ejected_packages.insert(name, eject.package);
+                    updated = true;
+                }
+                // adding to dependencies keeps the package unlocked
+                PackageChange::Install(ref install) if install.package.is_none() => {}
+                // the package is locked again, or replaced with other version
+                PackageChange::Adopt(_) | PackageChange::Install(_) | PackageChange::Remove(_) => {
+                    updated |= self.ejected_packages.shift_remove(&name).is_some();
+                }
+            }
+        }
+
+        if updated {
+            self.save_ejected_packages().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the remaining steps of the changes recorded in the journal.
+    ///
+    /// If this fails after removing packages, the journal is kept as unfinished changes.
+    pub(super) async fn continue_unfinished_changes(
+        &mut self,
+        env: &impl PackageInstaller,
+        installs: &[PackageInfo<'_>],
+        mut journal: UnfinishedChanges,
+    ) -> io::Result<()> {
+        // remove packages
+        if journal.step == JournalStep::MovingPackages {
+            if let Err(err) = move_packages_to_temp(
+                &self.io,
+                journal.move_packages.iter().map(|x| x.as_ref()),
+                &journal.temp_dir,
+            )
+            .await
+            {
+                // moved packages are restored so revert manifest and discard the journal
+                self.manifest.set_json(journal.manifest_before)?;
+                self.save().await?;
+                UnfinishedChanges::remove(&self.io).await;
+                self.io.remove_dir(&journal.temp_dir).await.ok();
+                remove_temp_dirs(&self.io).await;
+                return Err(err);
+            }
+
+            journal.step = JournalStep::InstallingPackages;
+        }
+
+        match self.finish_changes(env, installs, &mut journal).await {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                self.unfinished_changes = Some(journal);
+                Err(err)
+            }
+        }
+    }
+
+    async fn finish_changes(
+        &mut self,
+        env: &impl PackageInstaller,
+        installs: &[PackageInfo<'_>],
+        journal: &mut UnfinishedChanges,
+    ) -> io::Result<()> {
+        if journal.step == JournalStep::InstallingPackages {
+            journal.save(&self.io).await?;
+
+            // apply changes to manifest
+            self.manifest.set_json(journal.manifest_after.clone())?;
+            self.save().await?;
+
+            // add packages
+            install_packages(&self.io, env, installs).await?;
+
+            journal.step = JournalStep::RemovingLegacyAssets;
+            journal.save(&self.io).await?;
+        }
+
+        self.io.remove_dir_all(&journal.temp_dir).await.ok();
 
         // remove legacy assets
 
         remove_assets(
             &self.io,
-            request.remove_legacy_files.iter().map(|(p, _)| p.as_ref()),
-            request
-                .remove_legacy_folders
-                .iter()
-                .map(|(p, _)| p.as_ref()),
+            journal.remove_legacy_files.iter().map(|p| p.as_ref()),
+            journal.remove_legacy_folders.iter().map(|p| p.as_ref()),
         )
         .await;
 
+        UnfinishedChanges::remove(&self.io).await;
+        self.io.remove_dir_all(PKG_TEMP_DIR.as_ref()).await.ok();
+        // remove temp dir also if it's empty
+        self.io.remove_dir(TEMP_DIR.as_ref()).await.ok();
+
         Ok(())
     }
 }
 
-static REMOVED_FILE_PREFIX: &str = ".__removed_";
+/// Removes the temp directories if they are empty
+pub(super) async fn remove_temp_dirs(io: &impl ProjectIo) {
+    io.remove_dir(PKG_TEMP_DIR.as_ref()).await.ok();
+    io.remove_dir(TEMP_DIR.as_ref()).await.ok();
+}
+
+pub(super) static REMOVED_FILE_PREFIX: &str = ".__removed_";
 
 async fn move_packages_to_temp<'a>(
     io: &impl ProjectIo,
@@ -795,7 +1056,7 @@ async fn install_packages<Env: PackageInstaller>(
     Ok(())
 }
 
-async fn remove_assets(
+pub(super) async fn remove_assets(
     io: &impl ProjectIo,
     legacy_files: impl Iterator<Item = &Path>,
     legacy_folders: impl Iterator<Item = &Path>,
@@ -0,0 +1,35 @@
+use crate::UnityProject;
+use crate::io;
+use crate::io::ProjectIo;
+use crate::repositories_file::RepositoriesFile;
+use futures::prelude::*;
+use log::warn;
+
+/// The file listing repositories the project depends on.
+///
+/// The file uses the same format as the repositories file for `vrc-get repo import`,
+/// and is expected to be shared through version control with the project.
+pub const PROJECT_REPOSITORIES_PATH: &str = "Packages/vpm-repositories.txt";
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Loads the repositories listed in `Packages/vpm-repositories.txt`.
+    ///
+    /// Returns `None` if the project doesn't have the file.
+    pub async fn project_repositories(&self) -> io::Result<Option<RepositoriesFile>> {
+        let mut file = match self.io.open(PROJECT_REPOSITORIES_PATH.as_ref()).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut buffer = String::new();
+        file.read_to_string(&mut buffer).await?;
+
+        let result = RepositoriesFile::parse(&buffer);
+        for line in result.unparseable_lines() {
+            warn!("{PROJECT_REPOSITORIES_PATH}: unparseable line: {line}");
+        }
+
+        Ok(Some(result.into_parsed()))
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::time::SystemTime;
 
 use itertools::Itertools;
 
@@ -7,6 +8,7 @@ use crate::io::ProjectIo;
 use crate::unity_project::package_resolution::MissingDependencies;
 use crate::unity_project::{
     LockedDependencyInfo, PendingProjectChanges, package_resolution, pending_project_changes,
+    upm_manifest, vpm_manifest,
 };
 use crate::version::{DependencyRange, PrereleaseAcceptance};
 use crate::{PackageCollection, UnityProject, VersionSelector};
@@ -39,6 +41,22 @@ impl fmt::Display for ResolvePackageErr {
 impl std::error::Error for ResolvePackageErr {}
 
 impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns the last modification time of `vpm-manifest.json` and `manifest.json` of the project.
+    ///
+    /// This is used to check [`should_resolve`](Self::should_resolve) only when the manifests are
+    /// changed by other tools like git, without loading the project every time.
+    /// Returns `None` if neither file exists or the file system does not support modification time.
+    pub async fn manifests_modified_time(io: &IO) -> Option<SystemTime> {
+        let modified = async |path: &str| {
+            let metadata = io.metadata(path.as_ref()).await.ok()?;
+            metadata.modified()
+        };
+
+        let vpm = modified(vpm_manifest::MANIFEST_PATH).await;
+        let upm = modified(upm_manifest::MANIFEST_PATH).await;
+        vpm.max(upm)
+    }
+
     /// Returns whether the project should be resolved.
     ///
     /// The project will be resolved if: (not exhaustive)
@@ -46,66 +64,11 @@ impl<IO: ProjectIo> UnityProject<IO> {
     /// - some packages defined in `dependencies` section are missing
     /// - some dependencies of unlocked packages are missing
     pub fn should_resolve(&self) -> bool {
-        let mut installed_or_legacy = HashSet::<&str>::new();
-
-        // check locked packages
-        for locked in self.manifest.all_locked() {
-            let Some(installed) = self.installed_packages.get(locked.name()) else {
-                log::info!("Package {} is not installed", locked.name());
-                return true;
-            };
-            if installed.version() != locked.version() {
-                log::info!(
-                    "Package {} is installed with version {} but locked to {}",
-                    locked.name(),
-                    installed.version(),
-                    locked.version()
-                );
-                return true;
-            }
-            installed_or_legacy.insert(locked.name());
-            for legacy in installed.legacy_packages() {
-                installed_or_legacy.insert(legacy.as_ref());
-            }
-        }
-
-        // add legacy packages of unlocked packages to installed_or_legacy
-        for (_, pkg) in self.unlocked_packages() {
-            if let Some(pkg) = pkg {
-                for legacy in pkg.legacy_packages() {
-                    installed_or_legacy.insert(legacy.as_ref());
-                }
-
-                // using unlocked packages for resolving dependencies means broken vpm-manifest.json
-                // however, we cannot install package with same id as unlocked package
-                // so we add to installed_or_legacy and use them for checking
-                // if dependencies are installed
-                // note: this logic might be different from official VCC but this is our decision,
-                // we won't change this behavior unless we have a good reason
-                installed_or_legacy.insert(pkg.name());
-            }
-        }
-
-        // check dependencies
-        for (dependency, _) in self.manifest.dependencies() {
-            if !installed_or_legacy.contains(dependency) {
-                return true;
-            }
-        }
-
-        // check dependencies of unlocked packages
-        for (_, pkg) in self.unlocked_packages() {
-            if let Some(pkg) = pkg {
-                for (dependency, _) in pkg.vpm_dependencies() {
-                    if !installed_or_legacy.contains(dependency.as_ref()) {
-                        return true;
-                    }
-                }
-            }
+        let problems = self.missing_package_problems();
+        for problem in &problems {
+            log::info!("{problem}");
         }
-
-        // all packages are installed! no need to resolve
-        false
+        !problems.is_empty()
     }
 
     pub async fn resolve_request<'env>(
@@ -184,6 +147,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             env,
             to_install,
             allow_prerelease,
+            self.manifest.overrides(),
             missing_dependencies,
         );
 
@@ -234,9 +198,10 @@ impl<IO: ProjectIo> UnityProject<IO> {
             .iter()
             .filter_map(|(_, pkg)| pkg.as_ref())
             .flat_map(|pkg| {
-                pkg.vpm_dependencies()
-                    .into_iter()
-                    .map(|(k, v)| (k, v, pkg.version().is_pre()))
+                pkg.vpm_dependencies().into_iter().map(|(k, v)| {
+                    let range = self.manifest.overrides().apply(pkg.name(), k, v);
+                    (k, range, pkg.version().is_pre())
+                })
             });
 
         let unlocked_dependencies_versions = dependencies_of_unlocked_packages
@@ -302,6 +267,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             env,
             unlocked_dependencies,
             allow_prerelease,
+            self.manifest.overrides(),
             missing_dependencies,
         );
 
@@ -0,0 +1,290 @@
+use crate::io::{DirEntry, ProjectIo};
+use crate::unity_project::pending_project_changes::{REMOVED_FILE_PREFIX, remove_temp_dirs};
+use crate::utils::{save_json, try_load_json, walk_dir_relative};
+use crate::version::Version;
+use crate::{PackageCollection, PackageInstaller, UnityProject, VersionSelector, io};
+use futures::prelude::*;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+
+/// The journal file of `apply_pending_changes`.
+///
+/// This file is placed in the temp directory with moved packages,
+/// since rollback is not possible without them.
+static JOURNAL_PATH: &str = "Temp/vrc-get/journal.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum JournalStep {
+    /// Moving packages to be removed or replaced to the temp directory
+    MovingPackages,
+    /// Saving manifest and installing packages.
+    /// All packages to be removed are in the temp directory.
+    InstallingPackages,
+    /// Removing temp directory and legacy assets.
+    /// All packages are installed and the manifest is saved.
+    RemovingLegacyAssets,
+}
+
+/// The changes by `apply_pending_changes` which were interrupted.
+///
+/// This is recorded to the journal file before each step of `apply_pending_changes`,
+/// and loaded with the project if the process was killed in the middle of applying changes.
+/// You can roll forward or rollback the changes with
+/// [`UnityProject::roll_forward_unfinished_changes`] or [`UnityProject::rollback_unfinished_changes`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnfinishedChanges {
+    pub(super) step: JournalStep,
+    pub(super) temp_dir: Box<Path>,
+    pub(super) move_packages: Vec<Box<str>>,
+    pub(super) install_packages: IndexMap<Box<str>, Version>,
+    pub(super) remove_legacy_files: Vec<Box<Path>>,
+    pub(super) remove_legacy_folders: Vec<Box<Path>>,
+    /// vpm-manifest.json before the changes
+    pub(super) manifest_before: serde_json::Value,
+    /// vpm-manifest.json after the changes
+    pub(super) manifest_after: serde_json::Value,
+}
+
+impl UnfinishedChanges {
+    /// Returns true if the changes can be rolled back.
+    ///
+    /// After all packages are installed, the removed packages are already deleted,
+    /// so we can only roll forward.
+    pub fn can_rollback(&self) -> bool {
+        self.step != JournalStep::RemovingLegacyAssets
+    }
+
+    /// The packages to be removed or replaced by the changes.
+    pub fn remove_packages(&self) -> impl Iterator<Item = &str> {
+        self.move_packages.iter().map(|x| x.as_ref())
+    }
+
+    /// The packages to be installed by the changes.
+    pub fn install_packages(&self) -> impl Iterator<Item = (&str, &Version)> {
+        self.install_packages
+            .iter()
+            .map(|(name, version)| (name.as_ref(), version))
+    }
+
+    pub(super) async fn load(io: &impl ProjectIo) -> Option<Self> {
+        match try_load_json::<Self>(io, JOURNAL_PATH.as_ref()).await {
+            Ok(journal) => journal,
+            Err(e) => {
+                log::error!("failed to load the journal of unfinished changes: {e}");
+                None
+            }
+        }
+    }
+
+    pub(super) async fn save(&self, io: &impl ProjectIo) -> io::Result<()> {
+        // write to another file and rename to avoid broken journal
+        let temp_path = format!("{JOURNAL_PATH}.tmp");
+        save_json(io, temp_path.as_ref(), self).await?;
+        io.rename(temp_path.as_ref(), JOURNAL_PATH.as_ref()).await
+    }
+
+    pub(super) async fn remove(io: &impl ProjectIo) {
+        match io.remove_file(JOURNAL_PATH.as_ref()).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => log::error!("error removing the journal: {e}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RecoverChangesErr {
+    NoUnfinishedChanges,
+    RollbackNotAvailable,
+    PackagesNotFound { packages: Vec<Box<str>> },
+    Io(io::Error),
+}
+
+impl fmt::Display for RecoverChangesErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecoverChangesErr::NoUnfinishedChanges => {
+                write!(f, "The project has no unfinished changes")
+            }
+            RecoverChangesErr::RollbackNotAvailable => write!(
+                f,
+                "All packages are already installed so the changes cannot be rolled back"
+            ),
+            RecoverChangesErr::PackagesNotFound { packages } => {
+                write!(f, "Following packages are not found: ")?;
+                let mut first = true;
+                for package in packages {
+                    if !first {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", package)?;
+                    first = false;
+                }
+                Ok(())
+            }
+            RecoverChangesErr::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RecoverChangesErr {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecoverChangesErr::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RecoverChangesErr {
+    fn from(err: io::Error) -> Self {
+        RecoverChangesErr::Io(err)
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns the changes interrupted in the middle of `apply_pending_changes` if exists.
+    ///
+    /// While the project has unfinished changes, `apply_pending_changes` will fail.
+    pub fn unfinished_changes(&self) -> Option<&UnfinishedChanges> {
+        self.unfinished_changes.as_ref()
+    }
+
+    /// Finishes the unfinished changes.
+    pub async fn roll_forward_unfinished_changes(
+        &mut self,
+        collection: &impl PackageCollection,
+        installer: &impl PackageInstaller,
+    ) -> Result<(), RecoverChangesErr> {
+        let Some(journal) = self.unfinished_changes.take() else {
+            return Err(RecoverChangesErr::NoUnfinishedChanges);
+        };
+
+        let mut installs = Vec::new();
+        let mut not_found = Vec::new();
+
+        if journal.step != JournalStep::RemovingLegacyAssets {
+            for (name, version) in &journal.install_packages {
+                match collection
+                    .find_package_by_name(name, VersionSelector::specific_version(version))
+                {
+                    Some(package) => installs.push(package),
+                    None => not_found.push(name.clone()),
+                }
+            }
+        }
+
+        if !not_found.is_empty() {
+            self.unfinished_changes = Some(journal);
+            return Err(RecoverChangesErr::PackagesNotFound {
+                packages: not_found,
+            });
+        }
+
+        if journal.step == JournalStep::InstallingPackages {
+            // remove partially installed packages
+            for name in journal.install_packages.keys() {
+                let package_dir = PathBuf::from(format!("Packages/{name}"));
+                if let Err(e) = self.io.remove_dir_all(&package_dir).await
+                    && e.kind() != io::ErrorKind::NotFound
+                {
+                    self.unfinished_changes = Some(journal);
+                    return Err(e.into());
+                }
+            }
+        }
+
+        let result = self
+            .continue_unfinished_changes(installer, &installs, journal)
+            .await;
+
+        self.reload_packages().await?;
+
+        Ok(result?)
+    }
+
+    /// Reverts the unfinished changes.
+    ///
+    /// The packages moved to the temp directory are restored and vpm-manifest.json is reverted.
+    pub async fn rollback_unfinished_changes(&mut self) -> Result<(), RecoverChangesErr> {
+        let Some(journal) = self.unfinished_changes.take() else {
+            return Err(RecoverChangesErr::NoUnfinishedChanges);
+        };
+
+        if !journal.can_rollback() {
+            self.unfinished_changes = Some(journal);
+            return Err(RecoverChangesErr::RollbackNotAvailable);
+        }
+
+        if let Err(e) = self.rollback_impl(&journal).await {
+            self.unfinished_changes = Some(journal);
+            return Err(e.into());
+        }
+
+        UnfinishedChanges::remove(&self.io).await;
+        self.io.remove_dir(&journal.temp_dir).await.ok();
+        remove_temp_dirs(&self.io).await;
+
+        self.reload_packages().await?;
+
+        Ok(())
+    }
+
+    async fn rollback_impl(&mut self, journal: &UnfinishedChanges) -> io::Result<()> {
+        if journal.step == JournalStep::InstallingPackages {
+            // remove installed packages
+            for name in journal.install_packages.keys() {
+                let package_dir = PathBuf::from(format!("Packages/{name}"));
+                match self.io.remove_dir_all(&package_dir).await {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        for name in &journal.move_packages {
+            restore_package(&self.io, &journal.temp_dir, name).await?;
+        }
+
+        self.manifest.set_json(journal.manifest_before.clone())?;
+        self.save().await?;
+
+        Ok(())
+    }
+}
+
+/// Moves back files of the package in the temp directory.
+///
+/// Unlike `restore_remove`, this can restore packages partially moved.
+async fn restore_package(io: &impl ProjectIo, temp_dir: &Path, name: &str) -> io::Result<()> {
+    let package_dir = PathBuf::from(format!("Packages/{name}"));
+    let moved_dir = temp_dir.join(name);
+
+    let mut iterator = pin!(walk_dir_relative(io, vec![moved_dir.clone()]));
+    while let Some((moved, entry)) = iterator.next().await {
+        if entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let relative = moved.strip_prefix(&moved_dir).unwrap();
+        let mut original = package_dir.join(relative);
+        if let Some(stripped) = (moved.file_name().and_then(|x| x.to_str()))
+            .and_then(|x| x.strip_prefix(REMOVED_FILE_PREFIX))
+        {
+            original.set_file_name(stripped);
+        }
+        io.create_dir_all(original.parent().unwrap()).await?;
+        io.rename(&moved, &original).await?;
+    }
+
+    match io.remove_dir_all(&moved_dir).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
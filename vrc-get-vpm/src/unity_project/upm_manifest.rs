@@ -2,30 +2,138 @@ use crate::io;
 use crate::io::ProjectIo;
 use crate::utils::{JsonMapExt, SaveController, load_json_or_default, save_json};
 use crate::version::Version;
+use indexmap::IndexMap;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Map, Value};
-use std::collections::HashMap;
+use std::fmt;
 use std::fmt::Formatter;
 use std::str::FromStr;
 
-const MANIFEST_PATH: &str = "Packages/manifest.json";
+pub(super) const MANIFEST_PATH: &str = "Packages/manifest.json";
 
 #[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct Parsed {
     #[serde(default)]
-    dependencies: HashMap<Box<str>, UpmDependency>,
+    dependencies: IndexMap<Box<str>, UpmDependency>,
+    #[serde(default)]
+    scoped_registries: Vec<ScopedRegistry>,
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
-pub(super) enum UpmDependency {
+/// The dependency specified in `dependencies` of `Packages/manifest.json`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UpmDependency {
     // minimum version name. build meta is not supported by upm
     Version(Version),
     // Other Notation including local file and git url
     OtherNotation(Box<str>),
 }
 
+impl UpmDependency {
+    pub fn as_version(&self) -> Option<&Version> {
+        match self {
+            UpmDependency::Version(version) => Some(version),
+            UpmDependency::OtherNotation(_) => None,
+        }
+    }
+
+    fn parse_lenient(value: &str) -> Self {
+        if let Ok(semver) = Version::from_str(value) {
+            UpmDependency::Version(semver)
+        } else {
+            UpmDependency::OtherNotation(value.into())
+        }
+    }
+}
+
+impl fmt::Display for UpmDependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UpmDependency::Version(version) => fmt::Display::fmt(version, f),
+            UpmDependency::OtherNotation(notation) => f.write_str(notation),
+        }
+    }
+}
+
+/// Parses the value for `dependencies` of `Packages/manifest.json`.
+///
+/// Unlike loading existing manifest, this rejects values Unity Package Manager would not accept.
+impl FromStr for UpmDependency {
+    type Err = ParseUpmDependencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(semver) = Version::from_str(s) {
+            return Ok(UpmDependency::Version(semver));
+        }
+
+        // the part before revision ('#') or path query ('?') of git url
+        let url_body = s.split(['#', '?']).next().unwrap_or(s);
+        if s.starts_with("file:")
+            || s.starts_with("git:")
+            || s.starts_with("git+")
+            || url_body.ends_with(".git")
+        {
+            Ok(UpmDependency::OtherNotation(s.into()))
+        } else {
+            Err(ParseUpmDependencyError {})
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseUpmDependencyError {}
+
+impl fmt::Display for ParseUpmDependencyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("expected one of: a 'SemVer' compatible value; a value starting with 'file:'; a Git URL starting with 'git:' or 'git+', or ending with '.git'.")
+    }
+}
+
+impl std::error::Error for ParseUpmDependencyError {}
+
+/// The registry specified in `scopedRegistries` of `Packages/manifest.json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScopedRegistry {
+    name: Box<str>,
+    url: Box<str>,
+    #[serde(default)]
+    scopes: Vec<Box<str>>,
+}
+
+impl ScopedRegistry {
+    pub fn new(name: Box<str>, url: Box<str>, scopes: Vec<Box<str>>) -> Self {
+        Self { name, url, scopes }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn scopes(&self) -> &[Box<str>] {
+        &self.scopes
+    }
+
+    fn to_json(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("name".into(), Value::String(self.name.to_string()));
+        map.insert("url".into(), Value::String(self.url.to_string()));
+        map.insert(
+            "scopes".into(),
+            Value::Array(
+                (self.scopes.iter())
+                    .map(|x| Value::String(x.to_string()))
+                    .collect(),
+            ),
+        );
+        Value::Object(map)
+    }
+}
+
 impl<'de> Deserialize<'de> for UpmDependency {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -44,22 +152,7 @@ impl<'de> Deserialize<'de> for UpmDependency {
             where
                 E: Error,
             {
-                if let Ok(semver) = Version::from_str(v) {
-                    Ok(UpmDependency::Version(semver))
-                } else {
-                    Ok(UpmDependency::OtherNotation(v.into()))
-                }
-            }
-
-            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-            where
-                E: Error,
-            {
-                if let Ok(semver) = Version::from_str(&v) {
-                    Ok(UpmDependency::Version(semver))
-                } else {
-                    Ok(UpmDependency::OtherNotation(v.into_boxed_str()))
-                }
+                Ok(UpmDependency::parse_lenient(v))
             }
         }
 
@@ -112,7 +205,6 @@ impl UpmManifest {
         })
     }
 
-    #[allow(dead_code)]
     pub(super) fn dependencies(&self) -> impl Iterator<Item = (&str, &UpmDependency)> {
         self.controller
             .as_json
@@ -121,35 +213,96 @@ impl UpmManifest {
             .map(|(name, dep)| (name.as_ref(), dep))
     }
 
-    #[allow(dead_code)]
     pub(super) fn get_dependency(&self, package: &str) -> Option<&UpmDependency> {
         self.controller.as_json.dependencies.get(package)
     }
 
-    #[allow(dead_code)]
-    pub(super) fn add_dependency(&mut self, name: &str, version: Version) {
+    pub(super) fn add_dependency(&mut self, name: &str, dependency: UpmDependency) {
         self.controller
             .as_mut()
             .raw
             .get_or_put_mut("dependencies", Map::new)
             .as_object_mut()
             .unwrap()
-            .insert(name.to_string(), Value::String(version.to_string()));
+            .insert(name.to_string(), Value::String(dependency.to_string()));
         self.controller
             .as_mut()
             .as_json
             .dependencies
-            .insert(name.into(), UpmDependency::Version(version));
+            .insert(name.into(), dependency);
     }
 
-    pub(super) fn remove_dependency(&mut self, name: &str) {
+    pub(super) fn remove_dependency(&mut self, name: &str) -> bool {
+        if self.get_dependency(name).is_none() {
+            return false;
+        }
         self.controller
             .as_mut()
             .raw
             .get_mut("dependencies")
             .and_then(|x| x.as_object_mut())
-            .map(|x| x.remove(name));
-        self.controller.as_mut().as_json.dependencies.remove(name);
+            .map(|x| x.shift_remove(name));
+        self.controller
+            .as_mut()
+            .as_json
+            .dependencies
+            .shift_remove(name);
+        true
+    }
+
+    pub(super) fn scoped_registries(&self) -> &[ScopedRegistry] {
+        &self.controller.as_json.scoped_registries
+    }
+
+    /// Adds the scoped registry.
+    ///
+    /// If the registry with same url already exists, the scopes are merged to the existing one.
+    pub(super) fn add_scoped_registry(&mut self, registry: ScopedRegistry) {
+        let index = (self.scoped_registries().iter()).position(|x| x.url == registry.url);
+        let as_mut = self.controller.as_mut();
+        let raw_registries = as_mut
+            .raw
+            .get_or_put_mut("scopedRegistries", Vec::<Value>::new)
+            .as_array_mut()
+            .unwrap();
+
+        if let Some(index) = index {
+            let existing = &mut as_mut.as_json.scoped_registries[index];
+            let raw_scopes = raw_registries[index]
+                .as_object_mut()
+                .unwrap()
+                .get_or_put_mut("scopes", Vec::<Value>::new)
+                .as_array_mut()
+                .unwrap();
+            for scope in registry.scopes {
+                if !existing.scopes.contains(&scope) {
+                    raw_scopes.push(Value::String(scope.to_string()));
+                    existing.scopes.push(scope);
+                }
+            }
+        } else {
+            raw_registries.push(registry.to_json());
+            as_mut.as_json.scoped_registries.push(registry);
+        }
+    }
+
+    /// Removes the scoped registry with specified name or url.
+    pub(super) fn remove_scoped_registry(&mut self, name_or_url: &str) -> bool {
+        let Some(index) = (self.scoped_registries().iter())
+            .position(|x| x.name.as_ref() == name_or_url || x.url.as_ref() == name_or_url)
+        else {
+            return false;
+        };
+        let as_mut = self.controller.as_mut();
+        if let Some(raw_registries) = as_mut
+            .raw
+            .get_mut("scopedRegistries")
+            .and_then(|x| x.as_array_mut())
+        {
+            raw_registries.remove(index);
+        }
+        as_mut.as_json.scoped_registries.remove(index);
+        true
     }
 
     pub(super) async fn save(&mut self, io: &impl ProjectIo) -> io::Result<()> {
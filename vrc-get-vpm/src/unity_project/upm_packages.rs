@@ -0,0 +1,111 @@
+use crate::UnityProject;
+use crate::io::ProjectIo;
+use crate::unity_project::upm_manifest::{ScopedRegistry, UpmDependency};
+use crate::version::Version;
+use std::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UpgradeUpmPackageErr {
+    NotInstalled { package_name: Box<str> },
+    NotRegistryVersion { package_name: Box<str> },
+    Downgrading { package_name: Box<str> },
+}
+
+impl fmt::Display for UpgradeUpmPackageErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpgradeUpmPackageErr::NotInstalled { package_name } => {
+                write!(f, "UPM Package {package_name} is not in manifest.json")
+            }
+            UpgradeUpmPackageErr::NotRegistryVersion { package_name } => write!(
+                f,
+                "UPM Package {package_name} is not installed from registry, so it cannot be upgraded"
+            ),
+            UpgradeUpmPackageErr::Downgrading { package_name } => write!(
+                f,
+                "UPM Package {package_name} is newer than requested, so it cannot be upgraded"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UpgradeUpmPackageErr {}
+
+// operations for Packages/manifest.json
+// Those changes are saved with UnityProject::save
+impl<IO: ProjectIo> UnityProject<IO> {
+    pub fn upm_dependencies(&self) -> impl Iterator<Item = (&str, &UpmDependency)> {
+        self.upm_manifest.dependencies()
+    }
+
+    pub fn get_upm_dependency(&self, name: &str) -> Option<&UpmDependency> {
+        self.upm_manifest.get_dependency(name)
+    }
+
+    /// Adds the dependency to manifest.json, or replaces if already exists.
+    pub fn add_upm_dependency(&mut self, name: &str, dependency: UpmDependency) {
+        self.upm_manifest.add_dependency(name, dependency);
+    }
+
+    /// Upgrades the dependency installed from the registry to the specified version.
+    ///
+    /// Returns the previous version.
+    pub fn upgrade_upm_dependency(
+        &mut self,
+        name: &str,
+        version: Version,
+    ) -> Result<Version, UpgradeUpmPackageErr> {
+        let current = match self.upm_manifest.get_dependency(name) {
+            None => {
+                return Err(UpgradeUpmPackageErr::NotInstalled {
+                    package_name: name.into(),
+                });
+            }
+            Some(UpmDependency::OtherNotation(_)) => {
+                return Err(UpgradeUpmPackageErr::NotRegistryVersion {
+                    package_name: name.into(),
+                });
+            }
+            Some(UpmDependency::Version(current)) => current.clone(),
+        };
+
+        if current > version {
+            return Err(UpgradeUpmPackageErr::Downgrading {
+                package_name: name.into(),
+            });
+        }
+
+        if current != version {
+            self.upm_manifest
+                .add_dependency(name, UpmDependency::Version(version));
+        }
+
+        Ok(current)
+    }
+
+    /// Removes the dependency from manifest.json.
+    ///
+    /// Returns false if the dependency does not exist.
+    pub fn remove_upm_dependency(&mut self, name: &str) -> bool {
+        self.upm_manifest.remove_dependency(name)
+    }
+
+    pub fn upm_scoped_registries(&self) -> &[ScopedRegistry] {
+        self.upm_manifest.scoped_registries()
+    }
+
+    /// Adds the scoped registry to manifest.json.
+    ///
+    /// If the registry with same url already exists, scopes are merged to existing one.
+    pub fn add_upm_scoped_registry(&mut self, registry: ScopedRegistry) {
+        self.upm_manifest.add_scoped_registry(registry);
+    }
+
+    /// Removes the scoped registry with specified name or url from manifest.json.
+    ///
+    /// Returns false if no registry matches.
+    pub fn remove_upm_scoped_registry(&mut self, name_or_url: &str) -> bool {
+        self.upm_manifest.remove_scoped_registry(name_or_url)
+    }
+}
@@ -1,35 +1,46 @@
+use crate::git::GitPackage;
 use crate::io;
 use crate::io::ProjectIo;
 use crate::unity_project::LockedDependencyInfo;
-use crate::utils::{SaveController, load_json_or_default, save_json};
+use crate::utils::{SaveController, deserialize_json, load_json_or_default, save_json};
 use crate::version::{DependencyRange, Version, VersionRange};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-const MANIFEST_PATH: &str = "Packages/vpm-manifest.json";
+pub(super) const MANIFEST_PATH: &str = "Packages/vpm-manifest.json";
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AsJson {
     #[serde(default)]
     dependencies: IndexMap<Box<str>, VpmDependency>,
     #[serde(default)]
     locked: IndexMap<Box<str>, VpmLockedDependency>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    overrides: IndexMap<Box<str>, VersionRange>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct VpmDependency {
     pub version: DependencyRange,
+    /// The git repository the package is from, like `https://example.com/package.git#v1.0.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<Box<str>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct VpmLockedDependency {
     pub version: Version,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<Box<str>>,
+    /// The commit of the git repository the package is installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<Box<str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dependencies: Option<IndexMap<Box<str>, VersionRange>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct VpmManifest {
     controller: SaveController<AsJson>,
 }
@@ -72,11 +83,59 @@ impl VpmManifest {
             })
     }
 
-    pub(super) fn add_dependency(&mut self, name: &str, version: DependencyRange) {
-        self.controller
-            .as_mut()
+    /// Returns the packages from git repositories with the source and the locked commit if any.
+    ///
+    /// The locked commit is only returned if the package is locked with the same source.
+    pub(super) fn git_dependencies(&self) -> impl Iterator<Item = (&str, &str, Option<&str>)> {
+        let dependencies = self
+            .controller
             .dependencies
-            .insert(name.into(), VpmDependency { version });
+            .iter()
+            .filter_map(|(name, dep)| {
+                let git = dep.git.as_deref()?;
+                let commit = (self.controller.locked.get(name))
+                    .filter(|locked| locked.git.as_deref() == Some(git))
+                    .and_then(|locked| locked.commit.as_deref());
+                Some((name.as_ref(), git, commit))
+            });
+
+        // packages only locked are dependencies of other packages
+        let locked = self.controller.locked.iter().filter_map(|(name, locked)| {
+            if self.controller.dependencies.contains_key(name) {
+                return None;
+            }
+            Some((
+                name.as_ref(),
+                locked.git.as_deref()?,
+                locked.commit.as_deref(),
+            ))
+        });
+
+        dependencies.chain(locked)
+    }
+
+    pub(super) fn overrides(&self) -> DependencyOverrides<'_> {
+        DependencyOverrides {
+            overrides: &self.controller.overrides,
+        }
+    }
+
+    /// Adds the package to dependencies, keeping the git repository of the dependency if any.
+    pub(super) fn add_dependency(&mut self, name: &str, version: DependencyRange) {
+        let dependencies = &mut self.controller.as_mut().dependencies;
+        match dependencies.get_mut(name) {
+            Some(dependency) => dependency.version = version,
+            None => {
+                dependencies.insert(name.into(), VpmDependency { version, git: None });
+            }
+        }
+    }
+
+    /// Sets the git repository of the dependency. The dependency should be added before.
+    pub(super) fn set_dependency_git(&mut self, name: &str, git: &str) {
+        if let Some(dependency) = self.controller.as_mut().dependencies.get_mut(name) {
+            dependency.git = Some(git.into());
+        }
     }
 
     pub(super) fn add_locked(
@@ -84,11 +143,14 @@ impl VpmManifest {
         name: &str,
         version: Version,
         dependencies: IndexMap<Box<str>, VersionRange>,
+        git: Option<&GitPackage>,
     ) {
         self.controller.as_mut().locked.insert(
             name.into(),
             VpmLockedDependency {
                 version,
+                git: git.map(|x| x.source().to_string().into()),
+                commit: git.map(|x| x.commit().into()),
                 dependencies: Some(dependencies),
             },
         );
@@ -105,9 +167,63 @@ impl VpmManifest {
         !self.controller.locked.is_empty() || !self.controller.dependencies.is_empty()
     }
 
+    pub(super) fn to_json(&self) -> io::Result<serde_json::Value> {
+        serde_json::to_value(&*self.controller).map_err(io::Error::other)
+    }
+
+    pub(super) fn set_json(&mut self, json: serde_json::Value) -> io::Result<()> {
+        *self.controller.as_mut() = deserialize_json(json)?;
+        Ok(())
+    }
+
     pub(super) async fn save(&mut self, io: &impl ProjectIo) -> io::Result<()> {
         self.controller
             .save(|json| save_json(io, MANIFEST_PATH.as_ref(), json))
             .await
     }
 }
+
+/// The dependency ranges overridden in `overrides` of vpm-manifest.json.
+///
+/// The key of `overrides` is the name of the dependency to override the range required by all packages,
+/// or `dependent/dependency` to override the range required by the one dependent package.
+/// The override for the dependent package takes precedence over the global one.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DependencyOverrides<'a> {
+    overrides: &'a IndexMap<Box<str>, VersionRange>,
+}
+
+impl<'a> DependencyOverrides<'a> {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&'a str, &'a VersionRange)> + use<'a> {
+        self.overrides
+            .iter()
+            .map(|(key, range)| (key.as_ref(), range))
+    }
+
+    /// Returns the range of `dependency` required by `dependent` with overrides applied.
+    pub(crate) fn apply(
+        &self,
+        dependent: &str,
+        dependency: &str,
+        range: &'a VersionRange,
+    ) -> &'a VersionRange {
+        if self.overrides.is_empty() {
+            return range;
+        }
+
+        if let Some(overridden) = self
+            .overrides
+            .get(format!("{dependent}/{dependency}").as_str())
+        {
+            log::debug!("dependency {dependency} of {dependent}: overridden to {overridden}");
+            return overridden;
+        }
+
+        if let Some(overridden) = self.overrides.get(dependency) {
+            log::debug!("dependency {dependency} of {dependent}: overridden to {overridden}");
+            return overridden;
+        }
+
+        range
+    }
+}
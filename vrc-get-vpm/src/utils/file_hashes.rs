@@ -0,0 +1,76 @@
+use crate::io;
+use crate::io::{DirEntry, IoTrait, SeekFrom};
+use crate::utils::{MapResultExt, walk_dir_relative};
+use async_zip::base::read::seek::ZipFileReader;
+use futures::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use std::pin::pin;
+
+/// Computes sha256 of the files in the folder, keyed by the path relative to the folder.
+pub(crate) async fn hash_folder_files(
+    io: &impl IoTrait,
+    folder: &Path,
+) -> io::Result<BTreeMap<Box<str>, Box<str>>> {
+    let mut files = BTreeMap::new();
+
+    let mut iterator = pin!(walk_dir_relative(io, vec![folder.to_path_buf()]));
+    while let Some((path, entry)) = iterator.next().await {
+        if entry.file_type().await?.is_dir() {
+            continue;
+        }
+
+        let mut file = io.open(&path).await?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer).await?;
+        let hash = hex::encode(Sha256::digest(&buffer));
+
+        let relative = path.strip_prefix(folder).unwrap_or(&path);
+        files.insert(relative_to_key(relative), hash.into());
+    }
+
+    Ok(files)
+}
+
+/// Computes sha256 of the files in the zip file, keyed by the path in the zip file.
+///
+/// The keys are the same as [`hash_folder_files`] for the folder the zip file is extracted to.
+pub(crate) async fn hash_zip_files(
+    mut zip_file: impl AsyncBufRead + AsyncSeek + Unpin,
+) -> io::Result<BTreeMap<Box<str>, Box<str>>> {
+    zip_file.seek(SeekFrom::Start(0)).await?;
+
+    let mut files = BTreeMap::new();
+    let mut zip_reader = ZipFileReader::new(zip_file).await.err_mapped()?;
+    for i in 0..zip_reader.file().entries().len() {
+        let entry = &zip_reader.file().entries()[i];
+        let Some(filename) = entry.filename().as_str().ok() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "path in zip file is not utf8".to_string(),
+            ));
+        };
+        if filename.ends_with('/') {
+            continue;
+        }
+        let key = relative_to_key(filename.as_ref());
+
+        let mut reader = zip_reader.reader_without_entry(i).await.err_mapped()?;
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).await?;
+        files.insert(key, hex::encode(Sha256::digest(&buffer)).into());
+    }
+
+    Ok(files)
+}
+
+fn relative_to_key(path: &Path) -> Box<str> {
+    // use '/' as separator to share the record between platforms
+    path.components()
+        .filter(|x| matches!(x, Component::Normal(_)))
+        .map(|x| x.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+        .into()
+}
@@ -2,6 +2,7 @@ mod copy_recursive;
 mod crlf_json_formatter;
 mod deup_deserializer;
 mod extract_zip;
+mod file_hashes;
 mod save_controller;
 mod sha256_async_write;
 
@@ -13,6 +14,7 @@ pub(crate) use crlf_json_formatter::to_vec_pretty_os_eol;
 pub(crate) use deup_deserializer::DedupForwarder;
 use either::Either;
 pub(crate) use extract_zip::extract_zip;
+pub(crate) use file_hashes::{hash_folder_files, hash_zip_files};
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use pin_project_lite::pin_project;
@@ -2,6 +2,8 @@ use crate::version::*;
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
 
+mod interval;
+
 // TODO: TEST
 
 #[derive(::serde::Serialize, ::serde::Deserialize, Debug, Clone, Eq, PartialEq)]
@@ -17,6 +19,11 @@ impl DependencyRange {
         })
     }
 
+    /// create from version range
+    pub fn range(range: VersionRange) -> DependencyRange {
+        Self(range)
+    }
+
     pub fn as_single_version(&self) -> Option<Version> {
         let [ComparatorSet(the_set)] = self.0.comparators.as_slice() else {
             return None;
@@ -200,7 +207,7 @@ impl Display for Comparator {
             Comparator::GreaterThanOrEqual(v) => write!(f, ">={v}"),
             Comparator::LessThan(v) => write!(f, "<{v}"),
             Comparator::LessThanOrEqual(v) => write!(f, "<={v}"),
-            Comparator::Hyphen(a, b) => write!(f, "{a}-{b}"),
+            Comparator::Hyphen(a, b) => write!(f, "{a} - {b}"),
             Comparator::Star(v) => Display::fmt(v, f),
         }
     }
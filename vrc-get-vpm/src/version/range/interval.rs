@@ -0,0 +1,782 @@
+//! Set operations on [VersionRange].
+//!
+//! Each [ComparatorSet] is converted to an interval of [Version]s, which is
+//! the set of versions matching the comparator set with [PrereleaseAcceptance::Allow].
+//! With [PrereleaseAcceptance::Minimum], prerelease versions matches the comparator set only if
+//! all comparators in the set have prerelease, so we track that flag along with interval.
+
+use super::*;
+use std::cmp::Ordering;
+use std::ops::Bound;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+
+impl VersionRange {
+    /// Returns the range matches versions matched by both `self` and `other`.
+    ///
+    /// The result is not simplified. Use [VersionRange::simplified] to get the simple representation.
+    pub fn intersect(&self, other: &VersionRange) -> VersionRange {
+        let mut comparators = vec![];
+        for lhs in &self.comparators {
+            for rhs in &other.comparators {
+                let set = ComparatorSet(lhs.0.iter().chain(&rhs.0).cloned().collect());
+                // comparator sets without any version would never match
+                if set.interval().has_any() {
+                    comparators.push(set);
+                }
+            }
+        }
+        VersionRange { comparators }
+    }
+
+    /// Returns the range matches versions matched by `self` or `other`.
+    ///
+    /// The result is not simplified. Use [VersionRange::simplified] to get the simple representation.
+    pub fn union(&self, other: &VersionRange) -> VersionRange {
+        VersionRange {
+            comparators: (self.comparators.iter())
+                .chain(&other.comparators)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns true if no version can match this range.
+    pub fn is_empty(&self) -> bool {
+        self.is_empty_pre(PrereleaseAcceptance::Minimum)
+    }
+
+    pub fn is_empty_pre(&self, allow_prerelease: PrereleaseAcceptance) -> bool {
+        !self
+            .intervals(allow_prerelease)
+            .any(|(interval, pre)| interval.has_stable() || pre && interval.has_prerelease())
+    }
+
+    /// Returns true if all versions matching `self` also match `other`.
+    pub fn is_subset_of(&self, other: &VersionRange) -> bool {
+        self.is_subset_of_pre(other, PrereleaseAcceptance::Minimum)
+    }
+
+    pub fn is_subset_of_pre(
+        &self,
+        other: &VersionRange,
+        allow_prerelease: PrereleaseAcceptance,
+    ) -> bool {
+        let other = other.intervals(allow_prerelease).collect::<Vec<_>>();
+
+        for (interval, pre) in self.intervals(allow_prerelease) {
+            let stable_rest = interval.subtract_all(other.iter().map(|(x, _)| x));
+            if stable_rest.iter().any(Interval::has_stable) {
+                return false;
+            }
+
+            if pre {
+                let others = other.iter().filter(|(_, pre)| *pre).map(|(x, _)| x);
+                let prerelease_rest = interval.subtract_all(others);
+                if prerelease_rest.iter().any(Interval::has_prerelease) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the simplest range matches same versions as `self` for all [PrereleaseAcceptance].
+    ///
+    /// Overlapping comparator sets are merged and each comparator set is represented with lower and upper bounds,
+    /// so the result is like `>=1.2.0 <1.3.0 || =2.0.0`.
+    /// If the range matches nothing, this returns `<0.0.0-0`.
+    pub fn simplified(&self) -> VersionRange {
+        // empty comparator set matches everything regardless of PrereleaseAcceptance,
+        // which cannot be represented in other way
+        if self.comparators.iter().any(|set| set.0.is_empty()) {
+            return VersionRange {
+                comparators: vec![ComparatorSet(vec![])],
+            };
+        }
+
+        let mut entries = self
+            .comparators
+            .iter()
+            .map(|set| {
+                let interval = set.interval();
+                // the flag is only meaningful for intervals with prerelease versions
+                let pre = set.all_pre() && interval.has_prerelease();
+                (interval, pre, Some(set))
+            })
+            .filter(|(interval, _, _)| interval.has_any())
+            .collect::<Vec<_>>();
+
+        // remove intervals covered by another interval
+        let mut index = 0;
+        while index < entries.len() {
+            let (interval, pre, _) = &entries[index];
+            let covered = (entries.iter().enumerate())
+                .filter(|&(other_index, _)| other_index != index)
+                .any(|(_, (other, other_pre, _))| {
+                    (*other_pre || !*pre) && interval.subtract(other).next().is_none()
+                });
+            if covered {
+                entries.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        entries.sort_by(|(a, _, _), (b, _, _)| cmp_lower(&a.lower, &b.lower));
+
+        // merge overlapping or adjacent intervals
+        let mut merged: Vec<(Interval, bool, Option<&ComparatorSet>)> = vec![];
+        for (interval, pre, source) in entries {
+            let mergeable = merged.iter_mut().rev().find(|(last, last_pre, _)| {
+                *last_pre == pre && !last.gap_to(&interval).is_some_and(|x| x.has_any())
+            });
+            if let Some((last, _, last_source)) = mergeable {
+                let union = Interval {
+                    lower: min_lower(&last.lower, &interval.lower),
+                    upper: max_upper(&last.upper, &interval.upper),
+                };
+                if union.render(pre).is_some() {
+                    *last = union;
+                    *last_source = None;
+                    continue;
+                }
+            }
+            merged.push((interval, pre, source));
+        }
+
+        merged.sort_by(|(a, _, _), (b, _, _)| cmp_lower(&a.lower, &b.lower));
+
+        let comparators = merged
+            .into_iter()
+            .map(|(interval, pre, source)| match interval.render(pre) {
+                Some(rendered) => rendered,
+                // merged interval always can be rendered so source exists
+                None => source.expect("merged interval cannot be rendered").clone(),
+            })
+            .collect::<Vec<_>>();
+
+        if comparators.is_empty() {
+            let zero = Version::new_pre(0, 0, 0, pre_zero());
+            VersionRange {
+                comparators: vec![ComparatorSet(vec![Comparator::LessThan(zero.into())])],
+            }
+        } else {
+            VersionRange { comparators }
+        }
+    }
+
+    fn intervals(
+        &self,
+        allow_prerelease: PrereleaseAcceptance,
+    ) -> impl Iterator<Item = (Interval, bool)> + '_ {
+        self.comparators.iter().map(move |set| {
+            let pre = match allow_prerelease {
+                // empty comparator set matches everything including prerelease
+                PrereleaseAcceptance::Deny => set.0.is_empty(),
+                PrereleaseAcceptance::Allow => true,
+                PrereleaseAcceptance::Minimum => set.all_pre(),
+            };
+            (set.interval(), pre)
+        })
+    }
+}
+
+impl DependencyRange {
+    /// Returns the range matches versions matched by both `self` and `other`.
+    pub fn intersect(&self, other: &DependencyRange) -> VersionRange {
+        self.as_range().intersect(&other.as_range())
+    }
+
+    /// Returns the range matches versions matched by `self` or `other`.
+    pub fn union(&self, other: &DependencyRange) -> VersionRange {
+        self.as_range().union(&other.as_range())
+    }
+
+    /// Returns true if no version can match this range.
+    ///
+    /// Like [DependencyRange::matches], this allows prerelease versions.
+    pub fn is_empty(&self) -> bool {
+        self.as_range().is_empty_pre(PrereleaseAcceptance::Allow)
+    }
+
+    /// Returns true if all versions matching `self` also match `other`.
+    ///
+    /// Like [DependencyRange::matches], this allows prerelease versions.
+    pub fn is_subset_of(&self, other: &DependencyRange) -> bool {
+        (self.as_range()).is_subset_of_pre(&other.as_range(), PrereleaseAcceptance::Allow)
+    }
+
+    /// Returns the simplest [VersionRange] matches same versions as `self`.
+    pub fn simplified(&self) -> VersionRange {
+        self.as_range().simplified()
+    }
+}
+
+impl ComparatorSet {
+    fn interval(&self) -> Interval {
+        (self.0.iter())
+            .map(Comparator::interval)
+            .fold(Interval::ALL, |a, b| a.intersect(&b))
+    }
+
+    /// Whether prerelease versions are matched with [PrereleaseAcceptance::Minimum]
+    fn all_pre(&self) -> bool {
+        self.0.iter().all(Comparator::contains_pre)
+    }
+}
+
+impl Comparator {
+    /// The interval of versions matched by [Comparator::matches_internal]
+    fn interval(&self) -> Interval {
+        let interval = match self {
+            Comparator::Tilde(v) => {
+                let upper = match v.minor() {
+                    Some(minor) => Version::new_pre(v.major_or(0), minor + 1, 0, pre_zero()),
+                    None => Version::new_pre(v.major_or(0) + 1, 0, 0, pre_zero()),
+                };
+                Interval::new(Included(v.to_zeros()), Excluded(upper))
+            }
+            Comparator::Caret(v) => {
+                let upper = match (v.major(), v.minor(), v.patch()) {
+                    // ^* is always true
+                    (None, _, _) => Unbounded,
+                    (Some(0), Some(0), Some(patch)) => {
+                        Excluded(Version::new_pre(0, 0, patch + 1, pre_zero()))
+                    }
+                    (Some(0), Some(minor), _) => {
+                        Excluded(Version::new_pre(0, minor + 1, 0, pre_zero()))
+                    }
+                    (Some(major), _, _) => Excluded(Version::new_pre(major + 1, 0, 0, pre_zero())),
+                };
+                Interval::new(Included(v.to_zeros()), upper)
+            }
+            Comparator::Star(v) | Comparator::Exact(v) => match v.to_full_or_next() {
+                (full, true) => Interval::new(Included(full.clone()), Included(full)),
+                (next, false) => Interval::new(Included(v.to_zeros_with_pre()), Excluded(next)),
+            },
+            Comparator::GreaterThan(v) => Interval::new(greater_than(v), Unbounded),
+            Comparator::GreaterThanOrEqual(v) => Interval::new(greater_than_or_equal(v), Unbounded),
+            Comparator::LessThan(v) => Interval::new(Unbounded, less_than(v)),
+            Comparator::LessThanOrEqual(v) => Interval::new(Unbounded, less_than_or_equal(v)),
+            Comparator::Hyphen(lower, upper) => {
+                Interval::new(greater_than_or_equal(lower), less_than_or_equal(upper))
+            }
+        };
+
+        return interval.normalized();
+
+        fn greater_than(v: &PartialVersion) -> Bound<Version> {
+            match v.to_full_or_next() {
+                (full, true) => Excluded(full),
+                (next, false) => Included(next),
+            }
+        }
+        fn greater_than_or_equal(v: &PartialVersion) -> Bound<Version> {
+            Included(v.to_full().unwrap_or_else(|| v.to_zeros_with_pre()))
+        }
+        fn less_than(v: &PartialVersion) -> Bound<Version> {
+            Excluded(v.to_full().unwrap_or_else(|| v.to_zeros_with_pre()))
+        }
+        fn less_than_or_equal(v: &PartialVersion) -> Bound<Version> {
+            match v.to_full_or_next() {
+                (full, true) => Included(full),
+                (next, false) => Excluded(next),
+            }
+        }
+    }
+}
+
+fn pre_zero() -> Prerelease {
+    Prerelease::new("0").unwrap()
+}
+
+fn is_pre_zero(version: &Version) -> bool {
+    version.pre.as_str() == "0"
+}
+
+fn partial_major_minor(major: u64, minor: u64) -> PartialVersion {
+    PartialVersion {
+        major: Segment::new(major).unwrap(),
+        minor: Segment::new(minor).unwrap(),
+        patch: Segment::NOT_EXISTS,
+        pre: Prerelease::EMPTY,
+        build: BuildMetadata::EMPTY,
+    }
+}
+
+/// The interval of versions. both bounds are compared without build metadata.
+#[derive(Debug, Clone)]
+struct Interval {
+    lower: Bound<Version>,
+    upper: Bound<Version>,
+}
+
+impl Interval {
+    const ALL: Interval = Interval {
+        lower: Unbounded,
+        upper: Unbounded,
+    };
+
+    fn new(lower: Bound<Version>, upper: Bound<Version>) -> Self {
+        Self { lower, upper }
+    }
+
+    /// Replaces bounds that covers all parsable versions with [Unbounded]
+    fn normalized(self) -> Self {
+        let lower = match self.lower {
+            Included(v) if v == Version::new_pre(0, 0, 0, pre_zero()) => Unbounded,
+            other => other,
+        };
+        // to_full_or_next returns this for '*', which is larger than any parsable versions
+        let upper = match self.upper {
+            Excluded(v) if v.major > Segment::MAX.0 => Unbounded,
+            other => other,
+        };
+        Self { lower, upper }
+    }
+
+    fn intersect(&self, other: &Interval) -> Interval {
+        Interval {
+            lower: max_lower(&self.lower, &other.lower),
+            upper: min_upper(&self.upper, &other.upper),
+        }
+    }
+
+    /// Returns the parts of `self` not in `other`.
+    fn subtract(&self, other: &Interval) -> impl Iterator<Item = Interval> {
+        let below = match &other.lower {
+            Unbounded => None,
+            Included(v) => Some(Excluded(v.clone())),
+            Excluded(v) => Some(Included(v.clone())),
+        }
+        .map(|upper| Interval::new(Unbounded, upper).intersect(self));
+        let above = match &other.upper {
+            Unbounded => None,
+            Included(v) => Some(Excluded(v.clone())),
+            Excluded(v) => Some(Included(v.clone())),
+        }
+        .map(|lower| Interval::new(lower, Unbounded).intersect(self));
+
+        below.into_iter().chain(above).filter(Interval::has_any)
+    }
+
+    fn subtract_all<'a>(&self, others: impl Iterator<Item = &'a Interval>) -> Vec<Interval> {
+        let mut rest = vec![self.clone()];
+        for other in others {
+            rest = rest.iter().flat_map(|x| x.subtract(other)).collect();
+        }
+        rest
+    }
+
+    /// Returns the interval between `self` and `next`, if `next` starts after `self`.
+    fn gap_to(&self, next: &Interval) -> Option<Interval> {
+        let lower = match &self.upper {
+            Unbounded => return None,
+            Included(v) => Excluded(v.clone()),
+            Excluded(v) => Included(v.clone()),
+        };
+        let upper = match &next.lower {
+            Unbounded => return None,
+            Included(v) => Excluded(v.clone()),
+            Excluded(v) => Included(v.clone()),
+        };
+        Some(Interval::new(lower, upper))
+    }
+
+    fn has_any(&self) -> bool {
+        self.has_stable() || self.has_prerelease()
+    }
+
+    fn has_stable(&self) -> bool {
+        let smallest = match &self.lower {
+            Unbounded => Some(Version::new(0, 0, 0)),
+            Included(v) | Excluded(v) if v.is_pre() => Some(v.base_version()),
+            Included(v) => Some(v.clone()),
+            Excluded(v) => (v.patch.checked_add(1)).map(|p| Version::new(v.major, v.minor, p)),
+        };
+        smallest.is_some_and(|x| self.below_upper(&x))
+    }
+
+    fn has_prerelease(&self) -> bool {
+        let smallest = match &self.lower {
+            Unbounded => Some(Version::new_pre(0, 0, 0, pre_zero())),
+            Included(v) if v.is_pre() => Some(v.clone()),
+            // the smallest prerelease after 'x.y.z-pre' is 'x.y.z-pre.0'
+            Excluded(v) if v.is_pre() => {
+                let pre = Prerelease::new(&format!("{}.0", v.pre)).unwrap();
+                Some(Version::new_pre(v.major, v.minor, v.patch, pre))
+            }
+            Included(v) | Excluded(v) => {
+                (v.patch.checked_add(1)).map(|p| Version::new_pre(v.major, v.minor, p, pre_zero()))
+            }
+        };
+        smallest.is_some_and(|x| self.below_upper(&x))
+    }
+
+    fn below_upper(&self, version: &Version) -> bool {
+        match &self.upper {
+            Unbounded => true,
+            Included(upper) => version <= upper,
+            Excluded(upper) => version < upper,
+        }
+    }
+
+    /// Creates the comparator set matches this interval.
+    ///
+    /// If `pre` is true, the all comparators must contain prerelease, otherwise some comparator must not.
+    /// Returns None if the interval cannot be represented with that constraint.
+    fn render(&self, pre: bool) -> Option<ComparatorSet> {
+        // the flag is only meaningful for intervals with prerelease versions
+        let need_pre = self.has_prerelease().then_some(pre);
+
+        if let (Included(lower), Included(upper)) = (&self.lower, &self.upper)
+            && lower == upper
+        {
+            let exact = Comparator::Exact(lower.clone().into());
+            return match (need_pre, lower.is_pre()) {
+                (Some(true), false) => None,
+                (Some(false), true) => Some(ComparatorSet(vec![exact, star()])),
+                _ => Some(ComparatorSet(vec![exact])),
+            };
+        }
+
+        let lower = lower_forms(&self.lower);
+        let upper = upper_forms(&self.upper);
+
+        if need_pre == Some(true) {
+            let mut comparators = vec![];
+            for forms in [&lower, &upper].into_iter().flatten() {
+                match forms {
+                    (Some(pre), _) => comparators.push(pre.clone()),
+                    (None, _) => {
+                        // '1.0.0-beta - 2.0.0' contains prerelease
+                        if let (Included(lower), Included(upper)) = (&self.lower, &self.upper) {
+                            return Some(ComparatorSet(vec![Comparator::Hyphen(
+                                lower.clone().into(),
+                                upper.clone().into(),
+                            )]));
+                        }
+                        return None;
+                    }
+                }
+            }
+            if comparators.is_empty() {
+                let zero = Version::new_pre(0, 0, 0, pre_zero());
+                comparators.push(Comparator::GreaterThanOrEqual(zero.into()));
+            }
+            Some(ComparatorSet(comparators))
+        } else {
+            let mut comparators = vec![];
+            let mut has_stable_comparator = false;
+            for forms in [lower, upper].into_iter().flatten() {
+                match forms {
+                    (_, Some(stable)) => {
+                        has_stable_comparator = true;
+                        comparators.push(stable);
+                    }
+                    (Some(pre), None) => comparators.push(pre),
+                    (None, None) => unreachable!(),
+                }
+            }
+            if need_pre == Some(false) && !has_stable_comparator || comparators.is_empty() {
+                comparators.push(star());
+            }
+            Some(ComparatorSet(comparators))
+        }
+    }
+}
+
+fn star() -> Comparator {
+    Comparator::Star(PartialVersion {
+        major: Segment::STAR,
+        minor: Segment::NOT_EXISTS,
+        patch: Segment::NOT_EXISTS,
+        pre: Prerelease::EMPTY,
+        build: BuildMetadata::EMPTY,
+    })
+}
+
+/// Returns comparators with and without prerelease for the lower bound
+fn lower_forms(bound: &Bound<Version>) -> Option<(Option<Comparator>, Option<Comparator>)> {
+    match bound {
+        Unbounded => None,
+        Included(v) if v.is_stable() => {
+            Some((None, Some(Comparator::GreaterThanOrEqual(v.clone().into()))))
+        }
+        Included(v) => {
+            // '>=x.y.0-0' is same as '>=x.y' and '>=x.y.z-0' is same as '>x.y.(z-1)'
+            let stable = is_pre_zero(v).then(|| match v.patch {
+                0 => Comparator::GreaterThanOrEqual(partial_major_minor(v.major, v.minor)),
+                patch => Comparator::GreaterThan(Version::new(v.major, v.minor, patch - 1).into()),
+            });
+            Some((
+                Some(Comparator::GreaterThanOrEqual(v.clone().into())),
+                stable,
+            ))
+        }
+        Excluded(v) if v.is_stable() => {
+            Some((None, Some(Comparator::GreaterThan(v.clone().into()))))
+        }
+        Excluded(v) => Some((Some(Comparator::GreaterThan(v.clone().into())), None)),
+    }
+}
+
+/// Returns comparators with and without prerelease for the upper bound
+fn upper_forms(bound: &Bound<Version>) -> Option<(Option<Comparator>, Option<Comparator>)> {
+    match bound {
+        Unbounded => None,
+        Included(v) if v.is_stable() => {
+            Some((None, Some(Comparator::LessThanOrEqual(v.clone().into()))))
+        }
+        Included(v) => Some((Some(Comparator::LessThanOrEqual(v.clone().into())), None)),
+        Excluded(v) if v.is_stable() => Some((None, Some(Comparator::LessThan(v.clone().into())))),
+        Excluded(v) => {
+            // '<x.y.0-0' is same as '<x.y' and '<x.y.z-0' is same as '<=x.y.(z-1)'
+            let stable = is_pre_zero(v).then(|| match v.patch {
+                0 => Comparator::LessThan(partial_major_minor(v.major, v.minor)),
+                patch => {
+                    Comparator::LessThanOrEqual(Version::new(v.major, v.minor, patch - 1).into())
+                }
+            });
+            Some((Some(Comparator::LessThan(v.clone().into())), stable))
+        }
+    }
+}
+
+fn cmp_lower(a: &Bound<Version>, b: &Bound<Version>) -> Ordering {
+    match (a, b) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Less,
+        (_, Unbounded) => Ordering::Greater,
+        (Included(a), Included(b)) | (Excluded(a), Excluded(b)) => a.cmp(b),
+        (Included(a), Excluded(b)) => a.cmp(b).then(Ordering::Less),
+        (Excluded(a), Included(b)) => a.cmp(b).then(Ordering::Greater),
+    }
+}
+
+fn cmp_upper(a: &Bound<Version>, b: &Bound<Version>) -> Ordering {
+    match (a, b) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Greater,
+        (_, Unbounded) => Ordering::Less,
+        (Included(a), Included(b)) | (Excluded(a), Excluded(b)) => a.cmp(b),
+        (Included(a), Excluded(b)) => a.cmp(b).then(Ordering::Greater),
+        (Excluded(a), Included(b)) => a.cmp(b).then(Ordering::Less),
+    }
+}
+
+fn max_lower(a: &Bound<Version>, b: &Bound<Version>) -> Bound<Version> {
+    std::cmp::max_by(a, b, |a, b| cmp_lower(a, b)).clone()
+}
+
+fn min_lower(a: &Bound<Version>, b: &Bound<Version>) -> Bound<Version> {
+    std::cmp::min_by(a, b, |a, b| cmp_lower(a, b)).clone()
+}
+
+fn min_upper(a: &Bound<Version>, b: &Bound<Version>) -> Bound<Version> {
+    std::cmp::min_by(a, b, |a, b| cmp_upper(a, b)).clone()
+}
+
+fn max_upper(a: &Bound<Version>, b: &Bound<Version>) -> Bound<Version> {
+    std::cmp::max_by(a, b, |a, b| cmp_upper(a, b)).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(range: &str) -> VersionRange {
+        VersionRange::from_str(range).expect(range)
+    }
+
+    const ACCEPTANCES: [PrereleaseAcceptance; 3] = [
+        PrereleaseAcceptance::Deny,
+        PrereleaseAcceptance::Allow,
+        PrereleaseAcceptance::Minimum,
+    ];
+
+    const VERSIONS: &[&str] = &[
+        "0.0.0-0",
+        "0.0.0",
+        "0.0.1",
+        "0.0.2-beta",
+        "0.1.0",
+        "0.1.5",
+        "0.2.0-0",
+        "0.2.0",
+        "1.0.0-0",
+        "1.0.0-alpha",
+        "1.0.0-beta",
+        "1.0.0-beta.1",
+        "1.0.0",
+        "1.0.1-0",
+        "1.0.1",
+        "1.1.9",
+        "1.2.0-0",
+        "1.2.0-rc",
+        "1.2.0",
+        "1.2.4",
+        "1.2.5",
+        "1.3.0-0",
+        "1.3.0",
+        "1.4.0",
+        "1.5.0",
+        "1.9.9",
+        "2.0.0-0",
+        "2.0.0-beta",
+        "2.0.0",
+        "2.0.1",
+        "2.5.0",
+        "3.0.0",
+        "10.0.0",
+    ];
+
+    fn assert_same_matches(expected: &VersionRange, actual: &VersionRange) {
+        for version in VERSIONS {
+            let version = Version::from_str(version).unwrap();
+            for acceptance in ACCEPTANCES {
+                assert_eq!(
+                    expected.match_pre(&version, acceptance),
+                    actual.match_pre(&version, acceptance),
+                    "{expected} and {actual} for {version}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn simplified_display() {
+        fn test(source: &str, simplified: &str) {
+            let source = range(source);
+            let actual = source.simplified();
+            assert_eq!(actual.to_string(), simplified, "simplified {source}");
+            assert_same_matches(&source, &actual);
+            assert_same_matches(&source, &range(simplified));
+        }
+
+        test("1.0.0", "=1.0.0");
+        test("=1.0.0-beta", "=1.0.0-beta");
+        test("1.2.x", ">=1.2 <1.3");
+        test("^1.2.3", ">=1.2.3 <2.0");
+        test("^0.1.5", ">=0.1.5 <0.2");
+        test("~1.2.3", ">=1.2.3 <1.3");
+        test("^1.2.0 >=1.2.5 <2.0.0", ">=1.2.5 <2.0");
+        test("1.0.0 - 1.5.0 || 1.4.0 - 2.0.0", ">=1.0.0 <=2.0.0");
+        test("<=1.0.0 || >1.0.0", "*");
+        test("*", "*");
+        // empty comparator set matches everything regardless of PrereleaseAcceptance
+        test("", "");
+        test("1.0.0 ||", "");
+        // 2.0.0-beta is not matched with Allow
+        test(">=2.0.0 || ^1.0.0", ">=1.0.0 <2.0 || >=2.0.0");
+        test(">=2.0 || ^1.0.0", ">=1.0.0");
+        test("1.2.x || 2.x", ">=1.2 <1.3 || >=2.0 <3.0");
+        test(">=1.2 <1.3 >=2.0", "<0.0.0-0");
+        test("^1.0.0-beta", ">=1.0.0-beta <2.0.0-0");
+        test(">=1.0.0-beta <1.0.0", ">=1.0.0-beta <1.0.0");
+        test(
+            ">=1.0.0-beta <=1.0.0-rc <3.0.0",
+            ">=1.0.0-beta <=1.0.0-rc *",
+        );
+        test("1.0.0-beta - 2.0.0", "1.0.0-beta - 2.0.0");
+        test(">=1.0.1-0", ">=1.0.1-0");
+        test(">=1.0.1-0 <2.0.0", ">1.0.0 <2.0.0");
+        test("^1.0.0-beta || ^1.1.0", ">=1.0.0-beta <2.0.0-0");
+    }
+
+    #[test]
+    fn intersect() {
+        fn test(a: &str, b: &str, simplified: &str) {
+            let (a, b) = (range(a), range(b));
+            let actual = a.intersect(&b);
+            assert_eq!(actual.simplified().to_string(), simplified, "{a} and {b}");
+            for version in VERSIONS {
+                let version = Version::from_str(version).unwrap();
+                for acceptance in ACCEPTANCES {
+                    assert_eq!(
+                        a.match_pre(&version, acceptance) && b.match_pre(&version, acceptance),
+                        actual.match_pre(&version, acceptance),
+                        "{a} and {b} for {version}",
+                    );
+                }
+            }
+        }
+
+        test("^1.0.0", ">=1.2.5", ">=1.2.5 <2.0");
+        test(
+            "^1.0.0 || ^2.0.0",
+            "~1.2.0 || >=2.0.1",
+            ">=1.2.0 <1.3 || >=2.0.1 <3.0",
+        );
+        test("1.2.x", ">=2.0", "<0.0.0-0");
+        test("^1.0.0-beta", ">=1.0.0 <1.0.0", "<0.0.0-0");
+    }
+
+    #[test]
+    fn union() {
+        fn test(a: &str, b: &str, simplified: &str) {
+            let (a, b) = (range(a), range(b));
+            let actual = a.union(&b);
+            assert_eq!(actual.simplified().to_string(), simplified, "{a} or {b}");
+            for version in VERSIONS {
+                let version = Version::from_str(version).unwrap();
+                for acceptance in ACCEPTANCES {
+                    assert_eq!(
+                        a.match_pre(&version, acceptance) || b.match_pre(&version, acceptance),
+                        actual.match_pre(&version, acceptance),
+                        "{a} or {b} for {version}",
+                    );
+                }
+            }
+        }
+
+        test("^1.0.0", "^2.0.0", ">=1.0.0 <2.0 || >=2.0.0 <3.0");
+        test("^1.0.0", ">=1.5.0 <3.0.0-0", ">=1.0.0 <3.0");
+        test("<1.0.0", ">=1.0.0", "*");
+        test("1.0.0", "2.0.0", "=1.0.0 || =2.0.0");
+    }
+
+    #[test]
+    fn is_empty() {
+        assert!(!range("^1.0.0").is_empty());
+        assert!(!range("1.0.0").is_empty());
+        assert!(range(">=1.2 <1.3 >=2.0").is_empty());
+        assert!(range(">1.0.0 <1.0.1-0").is_empty_pre(PrereleaseAcceptance::Allow));
+        assert!(range(">1.0.0 <1.0.1").is_empty());
+        assert!(!range(">1.0.0 <1.0.1").is_empty_pre(PrereleaseAcceptance::Allow));
+        assert!(range(">=1.0.0-beta <1.0.0").is_empty());
+        assert!(!range(">=1.0.0-beta <1.0.0").is_empty_pre(PrereleaseAcceptance::Allow));
+        assert!(!range(">=1.0.0-beta <1.0.0-rc").is_empty());
+        assert!(range(">=1.0.0-beta <1.0.0-rc").is_empty_pre(PrereleaseAcceptance::Deny));
+        assert!(!DependencyRange::version(Version::new(1, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn is_subset_of() {
+        assert!(range("^1.2.3").is_subset_of(&range("^1.0.0")));
+        assert!(!range("^1.0.0").is_subset_of(&range("^1.2.3")));
+        assert!(range("1.2.x || 1.3.x").is_subset_of(&range(">=1.2.0 <1.4.0")));
+        assert!(range("=1.0.0").is_subset_of(&range("1.0.0 || 2.0.0")));
+        assert!(range(">=1.2 <1.3 >=2.0").is_subset_of(&range("=1.0.0")));
+        assert!(range("*").is_subset_of(&range(">=0.0.0")));
+        assert!(!range("*").is_subset_of_pre(&range(">=0.0.0"), PrereleaseAcceptance::Allow));
+        assert!(range("*").is_subset_of(&range(">=0.0.0 || <0.0.0")));
+
+        // prerelease handling
+        assert!(!range("^1.0.0-beta").is_subset_of(&range("^1.0.0")));
+        assert!(
+            range("^1.0.0-beta").is_subset_of_pre(&range("^1.0.0"), PrereleaseAcceptance::Deny)
+        );
+        assert!(
+            !range(">=1.0.0 <2.0.0")
+                .is_subset_of_pre(&range("^1.0.0"), PrereleaseAcceptance::Allow)
+        );
+        assert!(range("^1.0.0").is_subset_of(&range(">=1.0.0 <2.0.0")));
+
+        let single = DependencyRange::version(Version::new(1, 0, 0));
+        let ranged = "^1.0.0".parse::<VersionRange>().unwrap();
+        assert!(!single.is_subset_of(&DependencyRange(ranged.clone())));
+        assert!(DependencyRange(ranged).is_subset_of(&single));
+    }
+}
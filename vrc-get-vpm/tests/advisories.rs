@@ -0,0 +1,98 @@
+use serde_json::json;
+use vrc_get_vpm::repository::{AdvisorySeverity, RemoteRepository};
+use vrc_get_vpm::version::Version;
+
+fn repository() -> RemoteRepository {
+    let json = json!({
+        "name": "Example Repository",
+        "id": "com.example.repository",
+        "url": "https://example.com/index.json",
+        "packages": {
+            "com.example.package": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.example.package",
+                        "version": "1.0.0",
+                        "url": "https://example.com/com.example.package-1.0.0.zip",
+                    },
+                },
+            },
+        },
+        "advisories": [
+            {
+                "id": "EXAMPLE-2024-0001",
+                "package": "com.example.package",
+                "versions": ">=1.0.0 <1.2.0",
+                "severity": "high",
+                "message": "remote code execution in the editor",
+                "url": "https://example.com/advisories/EXAMPLE-2024-0001",
+            },
+            {
+                // not provided by this repository
+                "package": "com.vrchat.base",
+                "versions": "*",
+                "severity": "critical",
+                "message": "this should be ignored",
+            },
+            {
+                // malformed advisory
+                "package": "com.example.package",
+                "versions": "*",
+                "severity": "unknown",
+                "message": "this should be skipped",
+            },
+        ],
+    });
+    let serde_json::Value::Object(json) = json else {
+        unreachable!()
+    };
+    RemoteRepository::parse(json).unwrap()
+}
+
+#[test]
+fn parse_advisories() {
+    let repository = repository();
+    let advisories = repository.advisories().collect::<Vec<_>>();
+
+    assert_eq!(advisories.len(), 1);
+    let advisory = advisories[0];
+    assert_eq!(advisory.id(), Some("EXAMPLE-2024-0001"));
+    assert_eq!(advisory.package(), "com.example.package");
+    assert_eq!(advisory.severity(), AdvisorySeverity::High);
+    assert_eq!(advisory.message(), "remote code execution in the editor");
+    assert_eq!(
+        advisory.url().map(|x| x.as_str()),
+        Some("https://example.com/advisories/EXAMPLE-2024-0001")
+    );
+}
+
+#[test]
+fn advisory_affected_versions() {
+    let repository = repository();
+    let advisory = repository.advisories().next().unwrap();
+
+    let affects = |package: &str, version: &str| {
+        advisory.affects(package, &version.parse::<Version>().unwrap())
+    };
+
+    assert!(affects("com.example.package", "1.0.0"));
+    assert!(affects("com.example.package", "1.1.5"));
+    assert!(affects("com.example.package", "1.2.0-beta.1"));
+    assert!(!affects("com.example.package", "1.2.0"));
+    assert!(!affects("com.example.package", "0.9.0"));
+    assert!(!affects("com.example.other", "1.0.0"));
+}
+
+#[test]
+fn no_advisories() {
+    let json = json!({
+        "name": "Example Repository",
+        "packages": {},
+    });
+    let serde_json::Value::Object(json) = json else {
+        unreachable!()
+    };
+    let repository = RemoteRepository::parse(json).unwrap();
+
+    assert_eq!(repository.advisories().count(), 0);
+}
@@ -0,0 +1,194 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::UnityProject;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::LegacyAssetMatch;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const MOVED_GUID: &str = "0123456789abcdef0123456789abcdef";
+
+async fn project_with_legacy_assets() -> UnityProject<VirtualFileSystem> {
+    VirtualProjectBuilder::new()
+        .add_locked("com.anatawa12.tools", Version::new(1, 0, 0), &[])
+        .add_package_json(
+            "com.anatawa12.tools",
+            format!(
+                r#"{{
+                    "name": "com.anatawa12.tools",
+                    "version": "1.0.0",
+                    "legacyFolders": {{ "Assets/OldTools": "" }},
+                    "legacyFiles": {{
+                        "Assets/OldTools/Tool.cs": "",
+                        "Assets/OldTool.dll": "{MOVED_GUID}",
+                        "Assets/NotExists.cs": ""
+                    }}
+                }}"#
+            ),
+        )
+        .add_file("Assets/OldTools/Tool.cs", "12345")
+        .add_file("Assets/OldTools/Tool.cs.meta", "123")
+        .add_file("Assets/OldTools.meta", "1")
+        .add_file("Assets/Moved/OldTool.dll", "1234567890")
+        .add_file(
+            "Assets/Moved/OldTool.dll.meta",
+            format!("guid: {MOVED_GUID}\n"),
+        )
+        .add_file("Assets/Scene.unity", "scene")
+        .build()
+        .await
+        .unwrap()
+}
+
+#[test]
+fn find_legacy_assets() {
+    block_on(async {
+        let project = project_with_legacy_assets().await;
+
+        let assets = project.find_legacy_assets().await;
+        let assets = assets
+            .iter()
+            .map(|x| (x.path(), x.is_file(), x.matched_by(), x.size()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            assets,
+            vec![
+                (
+                    Path::new("Assets/Moved/OldTool.dll"),
+                    true,
+                    LegacyAssetMatch::Guid,
+                    10 + 6 + MOVED_GUID.len() as u64 + 1,
+                ),
+                (
+                    Path::new("Assets/OldTools"),
+                    false,
+                    LegacyAssetMatch::Path,
+                    5 + 3 + 1
+                ),
+                (
+                    Path::new("Assets/OldTools/Tool.cs"),
+                    true,
+                    LegacyAssetMatch::Path,
+                    5 + 3
+                ),
+            ]
+        );
+    })
+}
+
+#[test]
+fn remove_legacy_assets() {
+    block_on(async {
+        let project = project_with_legacy_assets().await;
+
+        let assets = project.find_legacy_assets().await;
+        project.remove_legacy_assets(&assets).await;
+
+        let io = project.io();
+        assert!(!io.is_dir("Assets/OldTools".as_ref()).await);
+        assert!(!io.is_file("Assets/OldTools.meta".as_ref()).await);
+        assert!(!io.is_file("Assets/Moved/OldTool.dll".as_ref()).await);
+        assert!(!io.is_file("Assets/Moved/OldTool.dll.meta".as_ref()).await);
+        assert!(io.is_file("Assets/Scene.unity".as_ref()).await);
+
+        assert!(project.find_legacy_assets().await.is_empty());
+    })
+}
+
+#[test]
+fn move_legacy_assets() {
+    block_on(async {
+        let project = project_with_legacy_assets().await;
+
+        let assets = project.find_legacy_assets().await;
+        project
+            .move_legacy_assets(&assets, "LegacyAssets".as_ref())
+            .await
+            .unwrap();
+
+        let io = project.io();
+        assert!(!io.is_dir("Assets/OldTools".as_ref()).await);
+        assert!(!io.is_file("Assets/Moved/OldTool.dll".as_ref()).await);
+        assert!(
+            io.is_file("LegacyAssets/Assets/OldTools/Tool.cs".as_ref())
+                .await
+        );
+        assert!(
+            io.is_file("LegacyAssets/Assets/OldTools.meta".as_ref())
+                .await
+        );
+        assert!(
+            io.is_file("LegacyAssets/Assets/Moved/OldTool.dll".as_ref())
+                .await
+        );
+        assert!(
+            io.is_file("LegacyAssets/Assets/Moved/OldTool.dll.meta".as_ref())
+                .await
+        );
+        assert!(io.is_file("Assets/Scene.unity".as_ref()).await);
+
+        assert!(project.find_legacy_assets().await.is_empty());
+    })
+}
+
+#[test]
+fn move_legacy_assets_into_assets() {
+    block_on(async {
+        let project = project_with_legacy_assets().await;
+
+        let assets = project.find_legacy_assets().await;
+        let error = project
+            .move_legacy_assets(&assets, "Assets/Legacy".as_ref())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(project.io().is_dir("Assets/OldTools".as_ref()).await);
+    })
+}
+
+#[test]
+fn move_legacy_assets_into_assets_ignoring_case() {
+    block_on(async {
+        let project = project_with_legacy_assets().await;
+
+        let assets = project.find_legacy_assets().await;
+        let error = project
+            .move_legacy_assets(&assets, "assets/Legacy".as_ref())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(project.io().is_dir("Assets/OldTools".as_ref()).await);
+    })
+}
+
+#[test]
+fn move_legacy_assets_to_existing_destination() {
+    block_on(async {
+        let project = project_with_legacy_assets().await;
+        let io = project.io();
+        io.create_dir_all("LegacyAssets/Assets/Moved".as_ref())
+            .await
+            .unwrap();
+        io.write_sync(
+            "LegacyAssets/Assets/Moved/OldTool.dll.meta".as_ref(),
+            b"existing",
+        )
+        .await
+        .unwrap();
+
+        let assets = project.find_legacy_assets().await;
+        let error = project
+            .move_legacy_assets(&assets, "LegacyAssets".as_ref())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+
+        // nothing is moved
+        assert!(io.is_dir("Assets/OldTools".as_ref()).await);
+        assert!(io.is_file("Assets/Moved/OldTool.dll".as_ref()).await);
+        assert!(!io.is_dir("LegacyAssets/Assets/OldTools".as_ref()).await);
+    })
+}
@@ -0,0 +1,139 @@
+use common::*;
+use futures::AsyncReadExt;
+use futures::executor::block_on;
+use vrc_get_vpm::UnityProject;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const PROJECT_SETTINGS: &str = "%YAML 1.1\n\
+%TAG !u! tag:unity3d.com,2011:\n\
+--- !u!129 &1\n\
+PlayerSettings:\n\
+\x20 companyName: DefaultCompany\n\
+\x20 productName: Original\n\
+\x20 productGUID: 0123456789abcdef0123456789abcdef\n\
+\x20 defaultScreenWidth: 1024\n";
+
+async fn read_file(io: &VirtualFileSystem, path: &str) -> String {
+    let mut buffer = String::new();
+    io.open(path.as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    buffer
+}
+
+async fn original_project() -> UnityProject<VirtualFileSystem> {
+    VirtualProjectBuilder::new()
+        .add_dependency("com.vrchat.avatars", Version::new(3, 4, 2))
+        .add_locked("com.vrchat.avatars", Version::new(3, 4, 2), &[])
+        .add_file(
+            "Packages/com.vrchat.avatars/package.json",
+            r#"{"name":"com.vrchat.avatars","version":"3.4.2"}"#,
+        )
+        .add_file("ProjectSettings/ProjectSettings.asset", PROJECT_SETTINGS)
+        .add_file("Assets/Scene.unity", "scene")
+        .add_file("Assets/Library/Script.cs", "script")
+        .add_file("Library/ArtifactDB", "artifacts")
+        .add_file("Library-Android/ArtifactDB", "artifacts")
+        .add_file("Temp/UnityLockfile", "lock")
+        .add_file("Logs/Editor.log", "log")
+        .add_file(".git/HEAD", "ref: refs/heads/master")
+        .add_file("Assets/Submodule/.git/HEAD", "ref: refs/heads/master")
+        .add_dir("Assets/Empty")
+        .build()
+        .await
+        .unwrap()
+}
+
+#[test]
+fn clone_project() {
+    block_on(async {
+        let project = original_project().await;
+
+        let cloned = project
+            .clone_project(VirtualFileSystem::new(), "My \"New\" World")
+            .await
+            .unwrap();
+        let io = cloned.io();
+
+        assert!(io.is_file("Assets/Scene.unity".as_ref()).await);
+        assert!(io.is_file("Assets/Library/Script.cs".as_ref()).await);
+        assert!(io.is_dir("Assets/Empty".as_ref()).await);
+        assert!(!io.is_dir("Library".as_ref()).await);
+        assert!(!io.is_dir("Library-Android".as_ref()).await);
+        assert!(!io.is_dir("Temp".as_ref()).await);
+        assert!(!io.is_dir("Logs".as_ref()).await);
+        assert!(!io.is_dir(".git".as_ref()).await);
+        assert!(!io.is_dir("Assets/Submodule/.git".as_ref()).await);
+
+        assert_eq!(
+            cloned
+                .get_locked("com.vrchat.avatars")
+                .map(|x| x.version().clone()),
+            Some(Version::new(3, 4, 2))
+        );
+        assert!(cloned.get_installed_package("com.vrchat.avatars").is_some());
+
+        let settings = read_file(io, "ProjectSettings/ProjectSettings.asset").await;
+        assert!(settings.contains("  productName: \"My \\\"New\\\" World\"\n"));
+        assert!(settings.contains("  companyName: DefaultCompany\n"));
+        assert!(settings.contains("  defaultScreenWidth: 1024\n"));
+        let guid = settings
+            .lines()
+            .find_map(|x| x.strip_prefix("  productGUID: "))
+            .unwrap();
+        assert_ne!(guid, "0123456789abcdef0123456789abcdef");
+        assert_eq!(guid.len(), 32);
+
+        // the original project is not changed
+        let original = read_file(project.io(), "ProjectSettings/ProjectSettings.asset").await;
+        assert_eq!(original, PROJECT_SETTINGS);
+    })
+}
+
+#[test]
+fn reset_packages_to_template() {
+    block_on(async {
+        let project = original_project().await;
+        let cloned = project
+            .clone_project(VirtualFileSystem::new(), "Cloned")
+            .await
+            .unwrap();
+
+        let environment = VirtualFileSystem::new();
+        environment
+            .add_file(
+                "Templates/Worlds/Packages/vpm-manifest.json".as_ref(),
+                br#"{"dependencies":{"com.vrchat.worlds":{"version":"3.4.2"}},"locked":{}}"#,
+            )
+            .await
+            .unwrap();
+        environment
+            .add_file(
+                "Templates/Worlds/Assets/Scene.unity".as_ref(),
+                b"template scene",
+            )
+            .await
+            .unwrap();
+
+        let cloned = cloned
+            .reset_packages_to_template(&environment, "Templates/Worlds".as_ref())
+            .await
+            .unwrap();
+        let io = cloned.io();
+
+        assert!(!io.is_dir("Packages/com.vrchat.avatars".as_ref()).await);
+        assert!(cloned.get_locked("com.vrchat.avatars").is_none());
+        assert_eq!(
+            cloned.dependencies().collect::<Vec<_>>(),
+            vec!["com.vrchat.worlds"]
+        );
+        // only the packages are taken from the template
+        assert_eq!(read_file(io, "Assets/Scene.unity").await, "scene");
+    })
+}
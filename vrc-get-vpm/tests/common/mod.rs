@@ -4,14 +4,13 @@
 
 mod package_collection;
 mod virtual_environment;
-mod virtual_file_system;
 mod virtual_project_builder;
 
 pub use package_collection::PackageCollection;
 pub use package_collection::PackageCollectionBuilder;
 pub use virtual_environment::VirtualEnvironment;
-pub use virtual_file_system::VirtualFileSystem;
 pub use virtual_project_builder::VirtualProjectBuilder;
+pub use vrc_get_vpm::io::memory::VirtualFileSystem;
 
 use vrc_get_vpm::PackageInfo;
 use vrc_get_vpm::unity_project::PendingProjectChanges;
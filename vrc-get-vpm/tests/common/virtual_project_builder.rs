@@ -9,6 +9,7 @@ use vrc_get_vpm::{PackageManifest, UnityProject};
 pub struct VirtualProjectBuilder {
     dependencies: IndexMap<String, String>,
     locked: IndexMap<String, (Version, IndexMap<String, VersionRange>)>,
+    overrides: IndexMap<String, String>,
     files: IndexMap<String, String>,
     directories: Vec<String>,
 }
@@ -18,6 +19,7 @@ impl VirtualProjectBuilder {
         Self {
             dependencies: IndexMap::new(),
             locked: IndexMap::new(),
+            overrides: IndexMap::new(),
             files: IndexMap::new(),
             directories: vec![],
         }
@@ -51,6 +53,11 @@ impl VirtualProjectBuilder {
         self
     }
 
+    pub fn add_override(&mut self, key: &str, range: &str) -> &mut VirtualProjectBuilder {
+        self.overrides.insert(key.into(), range.into());
+        self
+    }
+
     pub fn add_file(
         &mut self,
         path: impl Into<String>,
@@ -103,6 +110,7 @@ impl VirtualProjectBuilder {
             json!({
                 "dependencies": dependencies,
                 "locked": locked,
+                "overrides": self.overrides,
             })
         };
 
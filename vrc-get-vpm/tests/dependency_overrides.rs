@@ -0,0 +1,140 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+fn collection() -> PackageCollection {
+    PackageCollectionBuilder::new()
+        .add(
+            PackageManifest::new("com.vrchat.avatars", Version::new(3, 5, 0))
+                .add_vpm_dependency("com.vrchat.base", "3.5.0"),
+        )
+        .add(
+            PackageManifest::new("com.anatawa12.gists", Version::new(1, 0, 0))
+                .add_vpm_dependency("com.vrchat.base", ">=3.0.0 <3.5.0"),
+        )
+        .add(PackageManifest::new(
+            "com.vrchat.base",
+            Version::new(3, 4, 0),
+        ))
+        .add(PackageManifest::new(
+            "com.vrchat.base",
+            Version::new(3, 5, 0),
+        ))
+        .build()
+}
+
+#[test]
+fn conflicts_without_overrides() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 5, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 5, 0),
+                &[("com.vrchat.base", "3.5.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 5, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        let collection = collection();
+
+        let gists = collection.get_package("com.anatawa12.gists", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[gists],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.conflicts().contains_key("com.vrchat.base"));
+        assert!(result.dependency_overrides().is_empty());
+    })
+}
+
+#[test]
+fn global_override() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 5, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 5, 0),
+                &[("com.vrchat.base", "3.5.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 5, 0), &[])
+            .add_override("com.vrchat.base", ">=3.0.0")
+            .build()
+            .await
+            .unwrap();
+        let collection = collection();
+
+        let gists = collection.get_package("com.anatawa12.gists", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[gists],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.conflicts().len(), 0);
+        assert_installing_to_both(&result, &gists);
+        assert!(!result.package_changes().contains_key("com.vrchat.base"));
+        assert_eq!(result.dependency_overrides().len(), 1);
+        assert_eq!(&*result.dependency_overrides()[0].0, "com.vrchat.base");
+    })
+}
+
+#[test]
+fn override_for_dependent() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 5, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 5, 0),
+                &[("com.vrchat.base", "3.5.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 5, 0), &[])
+            // the global override is less prior than the one for the dependent package,
+            // so the locked base 3.5.0 is allowed for avatars and gists but not for others
+            .add_override("com.vrchat.base", "<3.5.0")
+            .add_override("com.vrchat.avatars/com.vrchat.base", ">=3.5.0")
+            .add_override("com.anatawa12.gists/com.vrchat.base", ">=3.0.0")
+            .build()
+            .await
+            .unwrap();
+        let collection = collection();
+
+        assert_eq!(project.dependency_overrides().count(), 3);
+
+        let gists = collection.get_package("com.anatawa12.gists", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[gists],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        // the override for gists is used instead of the global one, so base is not downgraded
+        assert_eq!(result.conflicts().len(), 0);
+        assert!(!result.package_changes().contains_key("com.vrchat.base"));
+        assert_installing_to_both(&result, &gists);
+    })
+}
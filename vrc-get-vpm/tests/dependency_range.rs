@@ -0,0 +1,204 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::unity_project::{AddPackageOperation, SetDependencyRangeErr};
+use vrc_get_vpm::version::{DependencyRange, Version, VersionRange};
+
+mod common;
+
+fn range(range: &str) -> DependencyRange {
+    DependencyRange::range(range.parse::<VersionRange>().unwrap())
+}
+
+fn collection() -> PackageCollection {
+    PackageCollectionBuilder::new()
+        .add(PackageManifest::new(
+            "com.anatawa12.gists",
+            Version::new(1, 0, 0),
+        ))
+        .add(PackageManifest::new(
+            "com.anatawa12.gists",
+            Version::new(1, 5, 0),
+        ))
+        .add(PackageManifest::new(
+            "com.anatawa12.gists",
+            Version::new(2, 0, 0),
+        ))
+        .build()
+}
+
+#[test]
+fn install_with_range() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let collection = collection();
+
+        let package = collection.get_package("com.anatawa12.gists", Version::new(1, 5, 0));
+
+        let result = project
+            .add_package_request_with_ranges(&collection, &[(package, range("^1.0"))], false)
+            .await
+            .unwrap();
+
+        assert_eq!(result.package_changes().len(), 1);
+        let install = result.package_changes()["com.anatawa12.gists"]
+            .as_install()
+            .unwrap();
+        assert!(install.is_adding_to_locked());
+        assert_eq!(install.to_dependencies(), Some(&range("^1.0")));
+        assert_eq!(
+            install.install_package().unwrap().version(),
+            &Version::new(1, 5, 0)
+        );
+    })
+}
+
+#[test]
+fn install_with_range_locked_out_of_range() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(2, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(2, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        let collection = collection();
+
+        let package = collection.get_package("com.anatawa12.gists", Version::new(1, 5, 0));
+
+        let result = project
+            .add_package_request_with_ranges(&collection, &[(package, range("^1.0"))], false)
+            .await
+            .unwrap();
+
+        // downgrade to the version in the range even if the locked version is newer
+        let install = result.package_changes()["com.anatawa12.gists"]
+            .as_install()
+            .unwrap();
+        assert!(install.is_adding_to_locked());
+        assert_eq!(install.to_dependencies(), Some(&range("^1.0")));
+        assert_eq!(
+            install.install_package().unwrap().version(),
+            &Version::new(1, 5, 0)
+        );
+    })
+}
+
+#[test]
+fn upgrade_out_of_range_replaces_range() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        let collection = collection();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .set_dependency_range_request("com.anatawa12.gists", range("^1.0"))
+            .unwrap();
+        project.apply_pending_changes(&env, changes).await.unwrap();
+
+        let package = collection.get_package("com.anatawa12.gists", Version::new(2, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .unwrap();
+
+        // explicitly upgrading out of the range replaces the range with the version
+        let install = result.package_changes()["com.anatawa12.gists"]
+            .as_install()
+            .unwrap();
+        assert!(install.is_adding_to_locked());
+        assert_eq!(
+            install.to_dependencies(),
+            Some(&DependencyRange::version(Version::new(2, 0, 0)))
+        );
+        assert_eq!(install.replaced_dependency(), Some(&range("^1.0")));
+    })
+}
+
+#[test]
+fn upgrade_in_range_keeps_range() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        let collection = collection();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .set_dependency_range_request("com.anatawa12.gists", range("^1.0"))
+            .unwrap();
+        project.apply_pending_changes(&env, changes).await.unwrap();
+
+        let package = collection.get_package("com.anatawa12.gists", Version::new(1, 5, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let install = result.package_changes()["com.anatawa12.gists"]
+            .as_install()
+            .unwrap();
+        assert_eq!(install.to_dependencies(), None);
+        assert_eq!(install.replaced_dependency(), None);
+    })
+}
+
+#[test]
+fn set_dependency_range() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .set_dependency_range_request("com.anatawa12.gists", range(">=1.0 <2"))
+            .unwrap();
+
+        // not reinstalling the package
+        let change = changes.package_changes()["com.anatawa12.gists"]
+            .as_install()
+            .unwrap();
+        assert!(change.install_package().is_none());
+        assert!(!change.is_adding_to_locked());
+
+        project.apply_pending_changes(&env, changes).await.unwrap();
+
+        assert_eq!(
+            project.get_dependency("com.anatawa12.gists"),
+            Some(&range(">=1.0 <2"))
+        );
+        assert!(project.is_locked("com.anatawa12.gists"));
+
+        let error = project
+            .set_dependency_range_request("com.anatawa12.missing", range("^1.0"))
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            SetDependencyRangeErr::NotInDependencies { .. }
+        ));
+    })
+}
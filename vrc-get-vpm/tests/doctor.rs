@@ -0,0 +1,204 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::unity_project::{ProblemSeverity, ProjectProblem, diagnose_manifest_files};
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+#[test]
+fn healthy_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.base", Version::new(1, 0, 0))
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.vrchat.base",
+                r#"{"name":"com.vrchat.base","version":"1.0.0"}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        assert!(
+            diagnose_manifest_files(project.io())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(project.diagnose().await.is_empty());
+        assert!(!project.should_resolve());
+    })
+}
+
+#[test]
+fn package_problems() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(1, 0, 0))
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.vrchat.avatars", Version::new(1, 0, 0), &[])
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.vrchat.base",
+                r#"{"name":"com.vrchat.base","version":"1.1.0"}"#,
+            )
+            .add_package_json(
+                "com.anatawa12.avatar-optimizer",
+                r#"{"name":"com.anatawa12.avatar-optimizer","version":"1.0.0"}"#,
+            )
+            .add_dir("Packages/empty")
+            .build()
+            .await
+            .unwrap();
+
+        let problems = project.diagnose().await;
+        assert_eq!(problems.len(), 5, "{problems:?}");
+
+        assert!(matches!(
+            &problems[0],
+            ProjectProblem::MissingLockedPackage { package_name, version }
+                if package_name.as_ref() == "com.vrchat.avatars" && *version == Version::new(1, 0, 0)
+        ));
+        assert!(matches!(
+            &problems[1],
+            ProjectProblem::LockedVersionMismatch { package_name, installed, .. }
+                if package_name.as_ref() == "com.vrchat.base" && *installed == Version::new(1, 1, 0)
+        ));
+        assert!(matches!(
+            &problems[2],
+            ProjectProblem::MissingDependency { package_name }
+                if package_name.as_ref() == "com.anatawa12.gists"
+        ));
+
+        let mut unlocked = problems[3..]
+            .iter()
+            .map(|x| match x {
+                ProjectProblem::UnlockedPackage {
+                    folder_name,
+                    package_name,
+                } => (folder_name.as_ref(), package_name.as_deref()),
+                _ => panic!("unexpected problem: {x:?}"),
+            })
+            .collect::<Vec<_>>();
+        unlocked.sort();
+        assert_eq!(
+            unlocked,
+            vec![
+                (
+                    "com.anatawa12.avatar-optimizer",
+                    Some("com.anatawa12.avatar-optimizer")
+                ),
+                ("empty", None),
+            ]
+        );
+
+        assert!(
+            problems
+                .iter()
+                .all(|x| x.severity() == ProblemSeverity::Error
+                    || matches!(x, ProjectProblem::UnlockedPackage { .. }))
+        );
+    })
+}
+
+#[test]
+fn legacy_assets_and_guid() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.tools", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.tools",
+                r#"{
+                    "name": "com.anatawa12.tools",
+                    "version": "1.0.0",
+                    "legacyFolders": { "Assets/OldTools": "" }
+                }"#,
+            )
+            .add_file(
+                "Packages/com.anatawa12.tools/Runtime.cs.meta",
+                "fileFormatVersion: 2\nguid: 0123456789abcdef0123456789abcdef\n",
+            )
+            .add_file("Packages/com.anatawa12.tools/Runtime.cs", "")
+            .add_file(
+                "Assets/OldTools/Runtime.cs.meta",
+                "fileFormatVersion: 2\nguid: 0123456789abcdef0123456789abcdef\n",
+            )
+            .add_file("Assets/OldTools/Runtime.cs", "")
+            .build()
+            .await
+            .unwrap();
+
+        let problems = project.diagnose().await;
+        assert_eq!(problems.len(), 2, "{problems:?}");
+
+        assert!(matches!(
+            &problems[0],
+            ProjectProblem::LegacyAsset { path, package_name }
+                if path.as_ref() == Path::new("Assets/OldTools") && package_name.as_ref() == "com.anatawa12.tools"
+        ));
+        let ProjectProblem::DuplicateGuid { guid, paths } = &problems[1] else {
+            panic!("unexpected problem: {:?}", problems[1]);
+        };
+        assert_eq!(guid.as_ref(), "0123456789abcdef0123456789abcdef");
+        assert_eq!(
+            paths,
+            &[
+                Path::new("Assets/OldTools/Runtime.cs").into(),
+                Path::new("Packages/com.anatawa12.tools/Runtime.cs").into(),
+            ]
+        );
+    })
+}
+
+#[test]
+fn malformed_manifest() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("Packages/vpm-manifest.json".as_ref(), b"{\"locked\": ")
+            .await
+            .unwrap();
+        fs.add_file("Packages/manifest.json".as_ref(), b"{}")
+            .await
+            .unwrap();
+
+        let problems = diagnose_manifest_files(&fs).await.unwrap();
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(matches!(
+            &problems[0],
+            ProjectProblem::MalformedManifest { path, .. }
+                if path.as_ref() == Path::new("Packages/vpm-manifest.json")
+        ));
+    })
+}
+
+#[test]
+fn missing_dependency_of_unlocked_package() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_package_json(
+                "com.anatawa12.gists",
+                r#"{
+                    "name": "com.anatawa12.gists",
+                    "version": "1.0.0",
+                    "vpmDependencies": { "com.vrchat.base": "^1.0.0" }
+                }"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let problems = project.diagnose().await;
+        assert!(
+            problems.iter().any(|x| matches!(
+                x,
+                ProjectProblem::MissingUnlockedDependency { package_name, required_by }
+                    if package_name.as_ref() == "com.vrchat.base"
+                        && required_by.as_ref() == "com.anatawa12.gists"
+            )),
+            "{problems:?}"
+        );
+        // doctor reports the problem whenever resolving is needed
+        assert!(project.should_resolve());
+    })
+}
@@ -0,0 +1,258 @@
+use common::*;
+use futures::AsyncReadExt;
+use futures::executor::block_on;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::io::memory::{MemoryPackageInstaller, create_zip};
+use vrc_get_vpm::unity_project::{AdoptPackageErr, EJECTED_PACKAGES_PATH};
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageManifest, UnityProject};
+
+mod common;
+
+async fn read_file(project: &UnityProject<VirtualFileSystem>, path: &str) -> String {
+    let mut buffer = String::new();
+    project
+        .io()
+        .open(path.as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    buffer
+}
+
+#[test]
+fn eject_package() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.gists",
+                r#"{"name":"com.anatawa12.gists","version":"1.0.0"}"#,
+            )
+            .add_file(
+                "Packages/com.anatawa12.gists/Runtime/Gist.cs",
+                "class Gist {}",
+            )
+            .build()
+            .await
+            .unwrap();
+        let collection = PackageCollectionBuilder::new().build();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .eject_request(&["com.anatawa12.gists"], &collection)
+            .await
+            .unwrap();
+
+        assert_eq!(changes.package_changes().len(), 1);
+        let eject = changes.package_changes()["com.anatawa12.gists"]
+            .as_eject()
+            .unwrap();
+        assert_eq!(eject.ejected_package().version(), &Version::new(1, 0, 0));
+
+        project.apply_pending_changes(&env, changes).await.unwrap();
+
+        assert!(!project.is_locked("com.anatawa12.gists"));
+        assert_eq!(project.dependencies().count(), 0);
+        let ejected = project.get_ejected("com.anatawa12.gists").unwrap();
+        assert!(ejected.dependency().is_some());
+
+        // files are kept
+        assert_eq!(
+            read_file(&project, "Packages/com.anatawa12.gists/Runtime/Gist.cs").await,
+            "class Gist {}"
+        );
+        let record = read_file(&project, EJECTED_PACKAGES_PATH).await;
+        assert!(record.contains("Runtime/Gist.cs"));
+    })
+}
+
+#[test]
+fn eject_not_locked() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_package_json(
+                "com.anatawa12.gists",
+                r#"{"name":"com.anatawa12.gists","version":"1.0.0"}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+        let collection = PackageCollectionBuilder::new().build();
+
+        project
+            .eject_request(&["com.anatawa12.gists"], &collection)
+            .await
+            .unwrap_err();
+    })
+}
+
+#[test]
+fn adopt_ejected_package() {
+    block_on(async {
+        let package_json = r#"{"name":"com.anatawa12.gists","version":"1.0.0"}"#;
+        let record = json!({
+            "com.anatawa12.gists": {
+                "version": "1.0.0",
+                "dependency": "1.0.0",
+                "files": {
+                    "package.json": hex::encode(Sha256::digest(package_json)),
+                    "Runtime/Gist.cs": hex::encode(Sha256::digest("class Gist {}")),
+                    "Runtime/Removed.cs": hex::encode(Sha256::digest("class Removed {}")),
+                },
+            },
+        });
+
+        let mut project = VirtualProjectBuilder::new()
+            .add_package_json("com.anatawa12.gists", package_json)
+            .add_file(
+                "Packages/com.anatawa12.gists/Runtime/Gist.cs",
+                "class Gist { int modified; }",
+            )
+            .add_file(EJECTED_PACKAGES_PATH, record.to_string())
+            .build()
+            .await
+            .unwrap();
+        let collection = PackageCollectionBuilder::new().build();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        assert!(project.get_ejected("com.anatawa12.gists").is_some());
+        // ejected packages are not reported as problems
+        assert!(project.diagnose().await.is_empty());
+
+        // the zip file is not available so the files are compared with the record of ejecting
+        let changes = project
+            .adopt_request(&["com.anatawa12.gists"], &collection, &env)
+            .await
+            .unwrap();
+
+        let adopt = changes.package_changes()["com.anatawa12.gists"]
+            .as_adopt()
+            .unwrap();
+        assert_eq!(adopt.version(), &Version::new(1, 0, 0));
+        assert_eq!(
+            adopt.modified_files().unwrap(),
+            &["Runtime/Gist.cs".into(), "Runtime/Removed.cs".into()]
+        );
+
+        project.apply_pending_changes(&env, changes).await.unwrap();
+
+        assert!(project.is_locked("com.anatawa12.gists"));
+        assert_eq!(
+            project.dependencies().collect::<Vec<_>>(),
+            vec!["com.anatawa12.gists"]
+        );
+        assert!(project.get_ejected("com.anatawa12.gists").is_none());
+        assert!(
+            project
+                .io()
+                .metadata(EJECTED_PACKAGES_PATH.as_ref())
+                .await
+                .is_err()
+        );
+    })
+}
+
+#[test]
+fn adopt_unlocked_package_not_ejected() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_package_json(
+                "com.anatawa12.gists",
+                r#"{"name":"com.anatawa12.gists","version":"1.0.0"}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+        let collection = PackageCollectionBuilder::new().build();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .adopt_request(&["com.anatawa12.gists"], &collection, &env)
+            .await
+            .unwrap();
+
+        let adopt = changes.package_changes()["com.anatawa12.gists"]
+            .as_adopt()
+            .unwrap();
+        assert!(adopt.modified_files().is_none());
+        assert!(adopt.to_dependencies().is_none());
+
+        let error = project
+            .adopt_request(&["com.anatawa12.missing"], &collection, &env)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AdoptPackageErr::NotUnlocked(_)));
+    })
+}
+
+#[test]
+fn adopt_compares_with_repository_zip() {
+    block_on(async {
+        let package_json = r#"{"name":"com.anatawa12.gists","version":"1.0.0"}"#;
+        // the record of ejecting is made after the package was modified
+        let record = json!({
+            "com.anatawa12.gists": {
+                "version": "1.0.0",
+                "files": {
+                    "package.json": hex::encode(Sha256::digest(package_json)),
+                    "Runtime/Gist.cs": hex::encode(Sha256::digest("class Gist { int modified; }")),
+                    "Runtime/Added.cs": hex::encode(Sha256::digest("class Added {}")),
+                },
+            },
+        });
+
+        let project = VirtualProjectBuilder::new()
+            .add_package_json("com.anatawa12.gists", package_json)
+            .add_file(
+                "Packages/com.anatawa12.gists/Runtime/Gist.cs",
+                "class Gist { int modified; }",
+            )
+            .add_file(
+                "Packages/com.anatawa12.gists/Runtime/Added.cs",
+                "class Added {}",
+            )
+            .add_file(EJECTED_PACKAGES_PATH, record.to_string())
+            .build()
+            .await
+            .unwrap();
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.anatawa12.gists",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+        let mut installer = MemoryPackageInstaller::new();
+        let zip = create_zip(&[
+            ("package.json", package_json),
+            ("Runtime/", ""),
+            ("Runtime/Gist.cs", "class Gist {}"),
+            ("Runtime/Removed.cs", "class Removed {}"),
+        ])
+        .await
+        .unwrap();
+        installer.add_package("com.anatawa12.gists", Version::new(1, 0, 0), zip);
+
+        let changes = project
+            .adopt_request(&["com.anatawa12.gists"], &collection, &installer)
+            .await
+            .unwrap();
+
+        let adopt = changes.package_changes()["com.anatawa12.gists"]
+            .as_adopt()
+            .unwrap();
+        assert_eq!(
+            adopt.modified_files().unwrap(),
+            &[
+                "Runtime/Added.cs".into(),
+                "Runtime/Gist.cs".into(),
+                "Runtime/Removed.cs".into()
+            ]
+        );
+    })
+}
@@ -0,0 +1,336 @@
+use common::*;
+use futures::executor::block_on;
+use serde_json::json;
+use std::convert::Infallible;
+use std::path::Path;
+use url::Url;
+use vrc_get_vpm::environment::{PackageCollection, PackageInstaller, Settings, add_remote_repo};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::io::memory::create_zip;
+use vrc_get_vpm::repositories_file::RepositoriesFile;
+use vrc_get_vpm::repository::{LocalCachedRepository, RemoteRepository};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageCollection as _, PackageInfo, VersionSelector};
+
+mod common;
+
+fn repository_json(versions: &[&str]) -> Vec<u8> {
+    let versions = versions
+        .iter()
+        .map(|version| {
+            (
+                version.to_string(),
+                json!({
+                    "name": "com.anatawa12.tools",
+                    "version": version,
+                    "url": format!("file:///share/com.anatawa12.tools-{version}.zip"),
+                }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    json!({
+        "name": "Shared Repository",
+        "id": "com.anatawa12.shared",
+        "packages": {
+            "com.anatawa12.tools": {
+                "versions": versions,
+            },
+        },
+    })
+    .to_string()
+    .into_bytes()
+}
+
+#[test]
+fn read_index_file() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("/share/index.json".as_ref(), &repository_json(&["1.0.0"]))
+            .await
+            .unwrap();
+
+        let url = Url::parse("file:///share/index.json").unwrap();
+        let (repo, modified) = RemoteRepository::read_file(&fs, &url).await.unwrap();
+
+        assert_eq!(repo.id(), Some("com.anatawa12.shared"));
+        assert_eq!(repo.url(), Some(&url));
+        assert!(modified.is_some());
+        let package = repo
+            .get_package_version("com.anatawa12.tools", &Version::new(1, 0, 0))
+            .unwrap();
+        assert_eq!(
+            package.url().map(Url::as_str),
+            Some("file:///share/com.anatawa12.tools-1.0.0.zip")
+        );
+    })
+}
+
+#[test]
+fn read_directory_of_archives() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        for version in ["1.0.0", "1.1.0"] {
+            let package_json = format!(r#"{{"name":"com.anatawa12.tools","version":"{version}"}}"#);
+            let zip = create_zip(&[("package.json", package_json)]).await.unwrap();
+            fs.add_file(format!("/share/tools-{version}.zip").as_ref(), &zip)
+                .await
+                .unwrap();
+        }
+        fs.add_file("/share/readme.txt".as_ref(), b"not a package")
+            .await
+            .unwrap();
+
+        let url = Url::parse("file:///share/").unwrap();
+        let (repo, _) = RemoteRepository::read_file(&fs, &url).await.unwrap();
+
+        assert_eq!(repo.name(), Some("share"));
+        assert_eq!(repo.id(), Some("file:///share/"));
+        assert_eq!(repo.get_versions_of("com.anatawa12.tools").count(), 2);
+        let package = repo
+            .get_package_version("com.anatawa12.tools", &Version::new(1, 1, 0))
+            .unwrap();
+        assert_eq!(
+            package.url().map(Url::as_str),
+            Some("file:///share/tools-1.1.0.zip")
+        );
+    })
+}
+
+#[test]
+fn add_and_refresh_file_repository() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("/share/index.json".as_ref(), &repository_json(&["1.0.0"]))
+            .await
+            .unwrap();
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        let url = Url::parse("file:///share/index.json").unwrap();
+        // file repositories can be added in offline mode
+        add_remote_repo(
+            &mut settings,
+            url,
+            None,
+            Default::default(),
+            &fs,
+            None::<&Infallible>,
+        )
+        .await
+        .unwrap();
+        assert_eq!(settings.get_user_repos().len(), 1);
+
+        let count_versions = async |settings: &Settings| {
+            let collection = PackageCollection::load(settings, &fs, None::<&Infallible>)
+                .await
+                .unwrap();
+            collection
+                .get_remote()
+                .filter(|x| x.id() == Some("com.anatawa12.shared"))
+                .flat_map(|x| x.get_versions_of("com.anatawa12.tools"))
+                .count()
+        };
+
+        assert_eq!(count_versions(&settings).await, 1);
+
+        fs.write(
+            Path::new("/share/index.json"),
+            &repository_json(&["1.0.0", "1.1.0"]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count_versions(&settings).await, 2);
+    })
+}
+
+#[test]
+fn repository_is_used_when_writing_cache_fails() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("/share/index.json".as_ref(), &repository_json(&["1.0.0"]))
+            .await
+            .unwrap();
+        // the cache folder does not exist so writing the cache fails
+        let settings = json!({
+            "userRepos": [{
+                "localPath": "Repos/missing/shared.json",
+                "url": "file:///share/index.json",
+            }],
+        });
+        fs.add_file("settings.json".as_ref(), settings.to_string().as_bytes())
+            .await
+            .unwrap();
+
+        let settings = Settings::load(&fs).await.unwrap();
+        let collection = PackageCollection::load(&settings, &fs, None::<&Infallible>)
+            .await
+            .unwrap();
+
+        assert!(!fs.is_file("Repos/missing/shared.json".as_ref()).await);
+        assert!(
+            collection
+                .get_remote()
+                .any(|x| x.id() == Some("com.anatawa12.shared"))
+        );
+    })
+}
+
+#[test]
+fn repositories_file_with_file_url() {
+    let result = RepositoriesFile::parse(
+        "file:///share/index.json\nhttps://vpm.anatawa12.com/vpm.json\nftp://example.com/vpm.json\n",
+    );
+
+    let urls = result
+        .parsed()
+        .repositories()
+        .iter()
+        .map(|x| x.url().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        urls,
+        [
+            "file:///share/index.json",
+            "https://vpm.anatawa12.com/vpm.json"
+        ]
+    );
+    assert_eq!(result.unparseable_lines(), ["ftp://example.com/vpm.json"]);
+}
+
+#[test]
+fn project_repository_with_configured_id() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("/share/index.json".as_ref(), &repository_json(&["1.0.0"]))
+            .await
+            .unwrap();
+        // declares the same id as the configured repository
+        fs.add_file("/project/index.json".as_ref(), &repository_json(&["2.0.0"]))
+            .await
+            .unwrap();
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        let url = Url::parse("file:///share/index.json").unwrap();
+        add_remote_repo(
+            &mut settings,
+            url,
+            None,
+            Default::default(),
+            &fs,
+            None::<&Infallible>,
+        )
+        .await
+        .unwrap();
+        settings.set_package_pin("com.anatawa12.tools", "com.anatawa12.shared");
+
+        let mut collection = PackageCollection::load(&settings, &fs, None::<&Infallible>)
+            .await
+            .unwrap();
+        let project_repositories = RepositoriesFile::parse("file:///project/index.json");
+        let project_repositories = project_repositories.parsed().repositories();
+        let duplicated = collection
+            .add_repositories(
+                &project_repositories.iter().collect::<Vec<_>>(),
+                &fs,
+                None::<&Infallible>,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            duplicated
+                .iter()
+                .map(|x| x.url().as_str())
+                .collect::<Vec<_>>(),
+            ["file:///project/index.json"]
+        );
+
+        let versions = collection
+            .find_packages("com.anatawa12.tools")
+            .map(|x| x.version().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, ["1.0.0"]);
+    })
+}
+
+/// The collection of the packages in one repository
+struct RepositoryCollection(LocalCachedRepository);
+
+impl vrc_get_vpm::PackageCollection for RepositoryCollection {
+    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo<'_>> {
+        (self.0.get_packages())
+            .flat_map(|x| x.all_versions())
+            .map(|pkg| PackageInfo::remote(pkg, &self.0))
+    }
+
+    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo<'_>> {
+        self.get_all_packages()
+            .filter(move |pkg| pkg.name() == package)
+    }
+
+    fn find_package_by_name(
+        &self,
+        name: &str,
+        version: VersionSelector,
+    ) -> Option<PackageInfo<'_>> {
+        self.find_packages(name)
+            .find(|pkg| version.satisfies(pkg.package_json()))
+    }
+}
+
+async fn install_from_repository(repository_url: &str) -> std::io::Result<()> {
+    let fs = VirtualFileSystem::new();
+    let package_json = r#"{"name":"com.anatawa12.tools","version":"1.0.0"}"#;
+    let zip = create_zip(&[("package.json", package_json)]).await.unwrap();
+    fs.add_file("/share/com.anatawa12.tools-1.0.0.zip".as_ref(), &zip)
+        .await
+        .unwrap();
+
+    let mut json =
+        serde_json::from_slice::<serde_json::Map<_, _>>(&repository_json(&["1.0.0"])).unwrap();
+    json.insert("url".into(), repository_url.into());
+    let repository = RemoteRepository::parse(json).unwrap();
+    let collection =
+        RepositoryCollection(LocalCachedRepository::new(repository, Default::default()));
+
+    let mut project = VirtualProjectBuilder::new().build().await.unwrap();
+    let package = collection
+        .find_package_by_name(
+            "com.anatawa12.tools",
+            VersionSelector::specific_version(&Version::new(1, 0, 0)),
+        )
+        .unwrap();
+    let changes = project
+        .add_package_request(
+            &collection,
+            &[package],
+            AddPackageOperation::InstallToDependencies,
+            false,
+        )
+        .await
+        .unwrap();
+    let installer = PackageInstaller::new(&fs, None::<&Infallible>);
+    project.apply_pending_changes(&installer, changes).await?;
+    Ok(())
+}
+
+#[test]
+fn install_file_package_from_file_repository() {
+    block_on(async {
+        install_from_repository("file:///share/index.json")
+            .await
+            .unwrap();
+    })
+}
+
+#[test]
+fn install_file_package_from_remote_repository() {
+    block_on(async {
+        // remote repositories cannot read local files
+        let error = install_from_repository("https://vpm.anatawa12.com/vpm.json")
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+    })
+}
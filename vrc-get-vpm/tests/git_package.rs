@@ -0,0 +1,220 @@
+#![cfg(feature = "git")]
+
+use common::*;
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use vrc_get_vpm::environment::{PackageCollection, PackageInstaller};
+use vrc_get_vpm::git::GitSource;
+use vrc_get_vpm::io::{DefaultEnvironmentIo, IoTrait};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageCollection as _, VersionSelector};
+
+mod common;
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "-c",
+            "user.name=vrc-get",
+            "-c",
+            "user.email=vrc-get@example.com",
+        ])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed: {output:?}");
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
+
+fn commit_package(work: &Path, version: &str) {
+    let package_json =
+        format!(r#"{{"name": "com.anatawa12.git-package", "version": "{version}"}}"#);
+    std::fs::write(work.join("package.json"), package_json).unwrap();
+    std::fs::write(work.join("Tool.cs"), format!("// {version}")).unwrap();
+    git(work, &["add", "."]);
+    git(work, &["commit", "--quiet", "-m", version]);
+}
+
+/// Creates a bare repository with lightweight tag `v1.0.0`, annotated tag `1.1.0`, and non-version tag `latest`
+/// and returns the temporary folder and the url of the repository.
+fn create_repository() -> (PathBuf, String) {
+    let temp = std::env::temp_dir().join(format!("vrc-get-git-test-{}", uuid::Uuid::new_v4()));
+    let work = temp.join("work");
+    std::fs::create_dir_all(&work).unwrap();
+
+    git(&work, &["init", "--quiet"]);
+    commit_package(&work, "1.0.0");
+    git(&work, &["tag", "v1.0.0"]);
+    commit_package(&work, "1.1.0");
+    git(&work, &["tag", "-a", "-m", "1.1.0", "1.1.0"]);
+    git(&work, &["tag", "latest"]);
+
+    let bare = temp.join("package.git");
+    git(
+        &temp,
+        &["clone", "--quiet", "--bare", "work", "package.git"],
+    );
+
+    let url = bare.to_str().unwrap().to_owned();
+    (temp, url)
+}
+
+#[test]
+fn parse_git_source() {
+    let source = GitSource::parse("git+https://example.com/package.git#v1.0.0");
+    assert_eq!(source.url(), "https://example.com/package.git");
+    assert_eq!(source.reference(), Some("v1.0.0"));
+    assert_eq!(source.to_string(), "https://example.com/package.git#v1.0.0");
+
+    let source = GitSource::parse("https://example.com/package.git");
+    assert_eq!(source.url(), "https://example.com/package.git");
+    assert_eq!(source.reference(), None);
+}
+
+#[tokio::test]
+async fn option_like_url_is_refused() {
+    let temp = std::env::temp_dir().join(format!("vrc-get-git-test-{}", uuid::Uuid::new_v4()));
+    let io = DefaultEnvironmentIo::new(temp.join("env").into_boxed_path());
+
+    let mut collection = PackageCollection::empty();
+    let source = GitSource::parse("--upload-pack=touch pwned");
+    let error = collection
+        .add_git_packages(&io, &source, None, None, true)
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!temp.join("env").exists());
+}
+
+#[tokio::test]
+async fn ext_transport_is_refused() {
+    let temp = std::env::temp_dir().join(format!("vrc-get-git-test-{}", uuid::Uuid::new_v4()));
+    let io = DefaultEnvironmentIo::new(temp.join("env").into_boxed_path());
+    let marker = temp.join("pwned");
+
+    let mut collection = PackageCollection::empty();
+    let source = GitSource::parse(&format!("ext::touch {}", marker.display()));
+    let result = collection
+        .add_git_packages(&io, &source, None, None, true)
+        .await;
+    assert!(result.is_err());
+    assert!(!marker.exists());
+
+    std::fs::remove_dir_all(temp).ok();
+}
+
+#[tokio::test]
+async fn option_like_reference_is_refused() {
+    let (temp, url) = create_repository();
+    let io = DefaultEnvironmentIo::new(temp.join("env").into_boxed_path());
+
+    let mut collection = PackageCollection::empty();
+    let source = GitSource::parse(&format!("{url}#--output=pwned"));
+    let error = collection
+        .add_git_packages(&io, &source, None, None, true)
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    std::fs::remove_dir_all(temp).unwrap();
+}
+
+#[tokio::test]
+async fn load_versions_from_tags() {
+    let (temp, url) = create_repository();
+    let io = DefaultEnvironmentIo::new(temp.join("env").into_boxed_path());
+
+    let mut collection = PackageCollection::empty();
+    let name = collection
+        .add_git_packages(&io, &GitSource::parse(&url), None, None, true)
+        .await
+        .unwrap();
+    assert_eq!(name.as_deref(), Some("com.anatawa12.git-package"));
+
+    let mut versions = collection
+        .find_packages("com.anatawa12.git-package")
+        .map(|x| x.version().clone())
+        .collect::<Vec<_>>();
+    versions.sort();
+    assert_eq!(versions, vec![Version::new(1, 0, 0), Version::new(1, 1, 0)]);
+
+    // the cloned repository is used without fetching
+    let mut collection = PackageCollection::empty();
+    let pinned = GitSource::parse(&format!("{url}#v1.0.0"));
+    collection
+        .add_git_packages(&io, &pinned, None, None, false)
+        .await
+        .unwrap();
+    let packages = collection.git_packages();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].manifest().version(), &Version::new(1, 0, 0));
+    assert_eq!(
+        packages[0].commit(),
+        git(temp.join("work").as_ref(), &["rev-parse", "v1.0.0"])
+    );
+
+    std::fs::remove_dir_all(temp).unwrap();
+}
+
+#[tokio::test]
+async fn install_from_git_repository() {
+    let (temp, url) = create_repository();
+    let io = DefaultEnvironmentIo::new(temp.join("env").into_boxed_path());
+    let mut project = VirtualProjectBuilder::new().build().await.unwrap();
+
+    let mut collection = PackageCollection::empty();
+    let source = GitSource::parse(&url);
+    collection
+        .add_git_packages(&io, &source, None, None, true)
+        .await
+        .unwrap();
+
+    let package = collection
+        .find_package_by_name(
+            "com.anatawa12.git-package",
+            VersionSelector::latest_for(None, false),
+        )
+        .unwrap();
+    assert_eq!(package.version(), &Version::new(1, 1, 0));
+
+    let changes = project
+        .add_package_request(
+            &collection,
+            &[package],
+            AddPackageOperation::InstallToDependencies,
+            false,
+        )
+        .await
+        .unwrap();
+    let installer = PackageInstaller::new(&io, None::<&Infallible>);
+    project
+        .apply_pending_changes(&installer, changes)
+        .await
+        .unwrap();
+
+    assert!(
+        project
+            .io()
+            .is_file("Packages/com.anatawa12.git-package/Tool.cs".as_ref())
+            .await
+    );
+    assert_eq!(
+        project
+            .get_locked("com.anatawa12.git-package")
+            .map(|x| x.version().clone()),
+        Some(Version::new(1, 1, 0))
+    );
+
+    let commit = git(temp.join("work").as_ref(), &["rev-parse", "1.1.0^{commit}"]);
+    let dependencies = project.git_dependencies().collect::<Vec<_>>();
+    assert_eq!(
+        dependencies,
+        vec![("com.anatawa12.git-package", source, Some(commit.as_str()))]
+    );
+
+    std::fs::remove_dir_all(temp).unwrap();
+}
@@ -0,0 +1,111 @@
+use common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const TOOL_GUID: &str = "0123456789abcdef0123456789abcdef";
+const SCENE_GUID: &str = "fedcba9876543210fedcba9876543210";
+
+fn meta(guid: &str) -> String {
+    format!("fileFormatVersion: 2\nguid: {guid}\n")
+}
+
+#[test]
+fn build_guid_index() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.tools", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.tools",
+                r#"{"name": "com.anatawa12.tools", "version": "1.0.0"}"#,
+            )
+            .add_file(
+                "Packages/com.anatawa12.tools/package.json.meta",
+                meta("11111111111111111111111111111111"),
+            )
+            .add_file("Packages/com.anatawa12.tools/Tool.cs", "")
+            .add_file("Packages/com.anatawa12.tools/Tool.cs.meta", meta(TOOL_GUID))
+            .add_file("Packages/com.anatawa12.tools/Editor.cs", "")
+            .add_file("Packages/com.anatawa12.tools/Samples~/Sample.cs", "")
+            .add_file("Packages/com.anatawa12.tools/.git/HEAD", "")
+            // the copy of the package imported with unitypackage
+            .add_file("Assets/Tools/Tool.cs", "")
+            .add_file("Assets/Tools/Tool.cs.meta", meta(TOOL_GUID))
+            .add_file(
+                "Assets/Tools.meta",
+                meta("22222222222222222222222222222222"),
+            )
+            .add_file("Assets/Scene.unity", "")
+            .add_file("Assets/Scene.unity.meta", meta(SCENE_GUID))
+            .add_file("Assets/Texture.png", "")
+            .build()
+            .await
+            .unwrap();
+
+        let index = project.build_guid_index().await;
+
+        let tools = index.get(TOOL_GUID);
+        assert_eq!(
+            tools
+                .iter()
+                .map(|x| (x.path(), x.package()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new("Assets/Tools/Tool.cs"), None),
+                (
+                    Path::new("Packages/com.anatawa12.tools/Tool.cs"),
+                    Some("com.anatawa12.tools")
+                ),
+            ]
+        );
+        assert_eq!(index.find_package(TOOL_GUID), Some("com.anatawa12.tools"));
+        assert_eq!(index.find_package(SCENE_GUID), None);
+        assert_eq!(
+            index.get(SCENE_GUID)[0].path(),
+            Path::new("Assets/Scene.unity")
+        );
+        assert!(index.get("33333333333333333333333333333333").is_empty());
+        assert!(index.get("not a guid").is_empty());
+
+        let duplicates = index
+            .duplicates()
+            .map(|(guid, assets)| (guid, assets.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(duplicates, vec![(TOOL_GUID.to_owned(), 2)]);
+
+        assert_eq!(
+            index.missing_meta_files(),
+            &[
+                Path::new("Assets/Texture.png").into(),
+                Path::new("Packages/com.anatawa12.tools/Editor.cs").into(),
+            ]
+        );
+    })
+}
+
+#[test]
+fn package_name_from_package_json() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            // embedded package in the folder with a different name
+            .add_file(
+                "Packages/Tools/package.json",
+                r#"{"name": "com.anatawa12.tools", "version": "1.0.0"}"#,
+            )
+            .add_file("Packages/Tools/Tool.cs", "")
+            .add_file("Packages/Tools/Tool.cs.meta", meta(TOOL_GUID))
+            // folder without package.json
+            .add_file("Packages/Scenes/Scene.unity", "")
+            .add_file("Packages/Scenes/Scene.unity.meta", meta(SCENE_GUID))
+            .build()
+            .await
+            .unwrap();
+
+        let index = project.build_guid_index().await;
+
+        assert_eq!(index.find_package(TOOL_GUID), Some("com.anatawa12.tools"));
+        assert_eq!(index.find_package(SCENE_GUID), Some("Scenes"));
+    })
+}
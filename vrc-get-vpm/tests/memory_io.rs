@@ -0,0 +1,160 @@
+use common::*;
+use futures::executor::block_on;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::io::memory::{MemoryPackageInstaller, SnapshotEntry, create_zip};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+#[test]
+fn snapshot_diff() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("kept.txt".as_ref(), b"kept").await.unwrap();
+        fs.add_file("modified.txt".as_ref(), b"before")
+            .await
+            .unwrap();
+        fs.add_file("dir/removed.txt".as_ref(), b"removed")
+            .await
+            .unwrap();
+
+        let before = fs.snapshot();
+        assert_eq!(before.file(Path::new("kept.txt")), Some(&b"kept"[..]));
+        assert_eq!(
+            before.get(Path::new("dir")),
+            Some(&SnapshotEntry::Directory)
+        );
+
+        fs.write("modified.txt".as_ref(), b"after").await.unwrap();
+        fs.remove_file("dir/removed.txt".as_ref()).await.unwrap();
+        fs.add_file("new/added.txt".as_ref(), b"added")
+            .await
+            .unwrap();
+
+        let after = fs.snapshot();
+        let diff = before.diff(&after);
+
+        assert_eq!(
+            diff.added(),
+            [PathBuf::from("new"), PathBuf::from("new/added.txt")]
+        );
+        assert_eq!(diff.removed(), [PathBuf::from("dir/removed.txt")]);
+        assert_eq!(diff.modified(), [PathBuf::from("modified.txt")]);
+        assert!(after.diff(&fs.snapshot()).is_empty());
+    })
+}
+
+#[test]
+fn parent_of_root_is_invalid() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("dir/file.txt".as_ref(), b"file").await.unwrap();
+
+        assert!(fs.is_file("dir/../dir/file.txt".as_ref()).await);
+
+        let error = fs.metadata("../file.txt".as_ref()).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        let error = fs
+            .write("dir/../../file.txt".as_ref(), b"")
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    })
+}
+
+#[test]
+fn install_with_memory_installer() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.anatawa12.tools",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+        let package = collection.get_package("com.anatawa12.tools", Version::new(1, 0, 0));
+
+        let mut installer = MemoryPackageInstaller::new();
+        let zip = create_zip(&[
+            (
+                "package.json",
+                r#"{"name":"com.anatawa12.tools","version":"1.0.0"}"#,
+            ),
+            ("Runtime/", ""),
+            ("Runtime/Tool.cs", "class Tool {}"),
+        ])
+        .await
+        .unwrap();
+        installer.add_package("com.anatawa12.tools", Version::new(1, 0, 0), zip);
+
+        let before = project.io().snapshot();
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+        project
+            .apply_pending_changes(&installer, changes)
+            .await
+            .unwrap();
+
+        let after = project.io().snapshot();
+        let diff = before.diff(&after);
+
+        assert_eq!(
+            after.file(Path::new("Packages/com.anatawa12.tools/Runtime/Tool.cs")),
+            Some(&b"class Tool {}"[..])
+        );
+        assert!(
+            diff.added()
+                .contains(&PathBuf::from("Packages/com.anatawa12.tools/package.json"))
+        );
+        assert_eq!(
+            diff.modified(),
+            [PathBuf::from("Packages/vpm-manifest.json")]
+        );
+    })
+}
+
+#[test]
+fn install_not_registered_package() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.anatawa12.tools",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+        let package = collection.get_package("com.anatawa12.tools", Version::new(1, 0, 0));
+
+        let installer = MemoryPackageInstaller::new();
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+        let error = project
+            .apply_pending_changes(&installer, changes)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    })
+}
@@ -0,0 +1,70 @@
+use common::*;
+use futures::executor::block_on;
+use std::io::ErrorKind;
+use std::path::Path;
+use vrc_get_vpm::environment::{read_package_archive, read_package_folder};
+use vrc_get_vpm::io::memory::create_zip;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const PACKAGE_JSON: &str = r#"{
+    "name": "com.anatawa12.tools",
+    "version": "1.0.0",
+    "vpmDependencies": { "com.vrchat.base": "^1.0.0" }
+}"#;
+
+#[test]
+fn read_archive() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let zip = create_zip(&[
+            ("package.json", PACKAGE_JSON),
+            ("Runtime/Tool.cs", "class Tool {}"),
+        ])
+        .await
+        .unwrap();
+        fs.add_file("package.zip".as_ref(), &zip).await.unwrap();
+
+        let manifest = read_package_archive(&fs, Path::new("package.zip"))
+            .await
+            .unwrap();
+        assert_eq!(manifest.name(), "com.anatawa12.tools");
+        assert_eq!(manifest.version(), &Version::new(1, 0, 0));
+        assert!(manifest.vpm_dependencies().contains_key("com.vrchat.base"));
+    })
+}
+
+#[test]
+fn read_archive_without_package_json() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let zip = create_zip(&[("tools/package.json", PACKAGE_JSON)])
+            .await
+            .unwrap();
+        fs.add_file("package.zip".as_ref(), &zip).await.unwrap();
+
+        let error = read_package_archive(&fs, Path::new("package.zip"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    })
+}
+
+#[test]
+fn read_folder() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file("tools/package.json".as_ref(), PACKAGE_JSON.as_bytes())
+            .await
+            .unwrap();
+
+        let manifest = read_package_folder(&fs, Path::new("tools")).await.unwrap();
+        assert_eq!(manifest.name(), "com.anatawa12.tools");
+
+        let error = read_package_folder(&fs, Path::new("missing"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    })
+}
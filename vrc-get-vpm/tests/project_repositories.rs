@@ -0,0 +1,48 @@
+use common::*;
+use futures::executor::block_on;
+
+mod common;
+
+#[test]
+fn no_project_repositories() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        assert!(project.project_repositories().await.unwrap().is_none());
+    })
+}
+
+#[test]
+fn load_project_repositories() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file(
+                "Packages/vpm-repositories.txt",
+                "https://vpm.anatawa12.com/vpm.json\n\
+                 vcc://vpm/addRepo?url=https%3A%2F%2Fvpm.example.com%2Fvpm.json&headers[]=Authorization:token\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let repositories = project.project_repositories().await.unwrap().unwrap();
+        let repositories = repositories.repositories();
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(
+            repositories[0].url().as_str(),
+            "https://vpm.anatawa12.com/vpm.json"
+        );
+        assert!(repositories[0].headers().is_empty());
+        assert_eq!(
+            repositories[1].url().as_str(),
+            "https://vpm.example.com/vpm.json"
+        );
+        assert_eq!(
+            repositories[1]
+                .headers()
+                .get("Authorization")
+                .map(AsRef::as_ref),
+            Some("token")
+        );
+    })
+}
@@ -1,7 +1,8 @@
 use crate::common::*;
 use futures::executor::block_on;
-use vrc_get_vpm::PackageManifest;
+use vrc_get_vpm::io::IoTrait;
 use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageManifest, UnityProject};
 
 mod common;
 
@@ -252,3 +253,29 @@ fn resolve_both_dependencies_of_unlocked_and_dependencies() {
         assert_installing_to_both(&result, &library2);
     })
 }
+
+#[test]
+fn manifests_modified_time_changes_on_write() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.package", Version::new(1, 0, 0))
+            .build()
+            .await
+            .unwrap();
+
+        let before = UnityProject::manifests_modified_time(project.io()).await;
+        assert!(before.is_some());
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        (project.io())
+            .write(
+                "Packages/vpm-manifest.json".as_ref(),
+                br#"{"dependencies":{},"locked":{}}"#,
+            )
+            .await
+            .unwrap();
+
+        let after = UnityProject::manifests_modified_time(project.io()).await;
+        assert!(after > before);
+    })
+}
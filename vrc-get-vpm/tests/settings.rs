@@ -0,0 +1,104 @@
+use common::*;
+use futures::AsyncReadExt;
+use futures::executor::block_on;
+use serde_json::{Value, json};
+use std::time::Duration;
+use vrc_get_vpm::environment::Settings;
+use vrc_get_vpm::io::IoTrait;
+
+mod common;
+
+async fn read_json(fs: &VirtualFileSystem, path: &str) -> Value {
+    let mut buffer = String::new();
+    fs.open(path.as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    serde_json::from_str(&buffer).unwrap()
+}
+
+#[test]
+fn unknown_keys_are_preserved() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file(
+            "settings.json".as_ref(),
+            json!({ "showPrereleasePackages": false, "unknownVccKey": 1 })
+                .to_string()
+                .as_bytes(),
+        )
+        .await
+        .unwrap();
+        fs.add_file(
+            "vrc-get/settings.json".as_ref(),
+            json!({ "unknownVrcGetKey": "value" })
+                .to_string()
+                .as_bytes(),
+        )
+        .await
+        .unwrap();
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        settings.set_show_prerelease_packages(true);
+        settings.set_ignore_curated_repository(true);
+        settings.save(&fs).await.unwrap();
+
+        let vpm = read_json(&fs, "settings.json").await;
+        assert_eq!(vpm["showPrereleasePackages"], json!(true));
+        assert_eq!(vpm["unknownVccKey"], json!(1));
+
+        let vrc_get = read_json(&fs, "vrc-get/settings.json").await;
+        assert_eq!(vrc_get["ignoreCuratedRepository"], json!(true));
+        assert_eq!(vrc_get["unknownVrcGetKey"], json!("value"));
+    })
+}
+
+#[test]
+fn clear_paths() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        settings.set_default_project_path("/projects");
+        settings.set_project_backup_path("/backups");
+        settings.save(&fs).await.unwrap();
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        assert_eq!(settings.default_project_path(), Some("/projects"));
+        assert_eq!(settings.project_backup_path(), Some("/backups"));
+
+        settings.clear_default_project_path();
+        settings.clear_project_backup_path();
+        settings.save(&fs).await.unwrap();
+
+        let settings = Settings::load(&fs).await.unwrap();
+        assert_eq!(settings.default_project_path(), None);
+        assert_eq!(settings.project_backup_path(), None);
+    })
+}
+
+#[test]
+fn clear_repository_cache_ttl() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        settings.set_repository_cache_ttl(Duration::from_secs(60));
+        settings.save(&fs).await.unwrap();
+
+        let vrc_get = read_json(&fs, "vrc-get/settings.json").await;
+        assert_eq!(vrc_get["repositoryCacheTtl"], json!(60));
+
+        let mut settings = Settings::load(&fs).await.unwrap();
+        settings.clear_repository_cache_ttl();
+        settings.save(&fs).await.unwrap();
+
+        // the key is removed so that the default is used
+        let vrc_get = read_json(&fs, "vrc-get/settings.json").await;
+        assert_eq!(vrc_get.get("repositoryCacheTtl"), None);
+        let settings = Settings::load(&fs).await.unwrap();
+        assert_eq!(settings.repository_cache_ttl(), Duration::ZERO);
+    })
+}
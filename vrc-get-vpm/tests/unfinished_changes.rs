@@ -0,0 +1,183 @@
+use common::*;
+use futures::AsyncReadExt;
+use futures::executor::block_on;
+use serde_json::json;
+use std::io;
+use vrc_get_vpm::UnityProject;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::RecoverChangesErr;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+/// The project interrupted while removing com.vrchat.avatars
+async fn interrupted_project(step: &str) -> UnityProject<VirtualFileSystem> {
+    let manifest_before = json!({
+        "dependencies": {
+            "com.vrchat.avatars": { "version": "3.4.2" },
+        },
+        "locked": {
+            "com.vrchat.avatars": { "version": "3.4.2", "dependencies": {} },
+        },
+    });
+    let manifest_after = json!({
+        "dependencies": {},
+        "locked": {},
+    });
+    let journal = json!({
+        "step": step,
+        "tempDir": "Temp/vrc-get/interrupted",
+        "movePackages": ["com.vrchat.avatars"],
+        "installPackages": {},
+        "removeLegacyFiles": [],
+        "removeLegacyFolders": [],
+        "manifestBefore": manifest_before,
+        "manifestAfter": manifest_after,
+    });
+
+    VirtualProjectBuilder::new()
+        .add_dependency("com.vrchat.avatars", Version::new(3, 4, 2))
+        .add_locked("com.vrchat.avatars", Version::new(3, 4, 2), &[])
+        .add_file(
+            "Packages/com.vrchat.avatars/package.json",
+            r#"{"name":"com.vrchat.avatars","version":"3.4.2"}"#,
+        )
+        .add_file(
+            "Temp/vrc-get/interrupted/com.vrchat.avatars/.__removed_content.txt",
+            "text",
+        )
+        .add_file("Temp/vrc-get/journal.json", journal.to_string())
+        .build()
+        .await
+        .unwrap()
+}
+
+async fn read_file(project: &UnityProject<VirtualFileSystem>, path: &str) -> String {
+    let mut buffer = String::new();
+    project
+        .io()
+        .open(path.as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    buffer
+}
+
+async fn assert_not_found(project: &UnityProject<VirtualFileSystem>, path: &str) {
+    assert_eq!(
+        project
+            .io()
+            .metadata(path.as_ref())
+            .await
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn load_unfinished_changes() {
+    block_on(async {
+        let project = interrupted_project("MovingPackages").await;
+
+        let changes = project.unfinished_changes().unwrap();
+        assert!(changes.can_rollback());
+        assert_eq!(
+            changes.remove_packages().collect::<Vec<_>>(),
+            vec!["com.vrchat.avatars"]
+        );
+        assert_eq!(changes.install_packages().count(), 0);
+
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        assert!(project.unfinished_changes().is_none());
+    })
+}
+
+#[test]
+fn rollback_moving_packages() {
+    block_on(async {
+        let mut project = interrupted_project("MovingPackages").await;
+
+        project.rollback_unfinished_changes().await.unwrap();
+
+        assert!(project.unfinished_changes().is_none());
+        assert_eq!(
+            read_file(&project, "Packages/com.vrchat.avatars/content.txt").await,
+            "text"
+        );
+        assert!(project.get_locked("com.vrchat.avatars").is_some());
+        assert!(
+            project
+                .get_installed_package("com.vrchat.avatars")
+                .is_some()
+        );
+        assert_not_found(&project, "Temp").await;
+    })
+}
+
+#[test]
+fn roll_forward_moving_packages() {
+    block_on(async {
+        let mut project = interrupted_project("MovingPackages").await;
+
+        let collection = PackageCollectionBuilder::new().build();
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        project
+            .roll_forward_unfinished_changes(&collection, &env)
+            .await
+            .unwrap();
+
+        assert!(project.unfinished_changes().is_none());
+        assert!(project.get_locked("com.vrchat.avatars").is_none());
+        assert!(
+            project
+                .get_installed_package("com.vrchat.avatars")
+                .is_none()
+        );
+        assert_not_found(&project, "Packages/com.vrchat.avatars").await;
+        assert_not_found(&project, "Temp").await;
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&read_file(&project, "Packages/vpm-manifest.json").await).unwrap();
+        assert_eq!(manifest, json!({ "dependencies": {}, "locked": {} }));
+    })
+}
+
+#[test]
+fn rollback_not_available_after_install() {
+    block_on(async {
+        let mut project = interrupted_project("RemovingLegacyAssets").await;
+
+        assert!(!project.unfinished_changes().unwrap().can_rollback());
+        assert!(matches!(
+            project.rollback_unfinished_changes().await,
+            Err(RecoverChangesErr::RollbackNotAvailable)
+        ));
+        assert!(project.unfinished_changes().is_some());
+    })
+}
+
+#[test]
+fn apply_fails_with_unfinished_changes() {
+    block_on(async {
+        let mut project = interrupted_project("MovingPackages").await;
+
+        let env = VirtualEnvironment::new(VirtualFileSystem::new());
+
+        let changes = project
+            .remove_request(&["com.vrchat.avatars"])
+            .await
+            .unwrap();
+
+        project
+            .apply_pending_changes(&env, changes)
+            .await
+            .unwrap_err();
+
+        assert!(project.unfinished_changes().is_some());
+        read_file(&project, "Temp/vrc-get/journal.json").await;
+    })
+}
@@ -0,0 +1,82 @@
+// the version of the editor is read from the folder name on linux
+#![cfg(target_os = "linux")]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use vrc_get_vpm::environment::find_unity_in_search_roots;
+use vrc_get_vpm::io::DefaultEnvironmentIo;
+use vrc_get_vpm::version::UnityVersion;
+
+fn temp_dir() -> PathBuf {
+    let temp = std::env::temp_dir().join(format!("vrc-get-unity-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&temp).unwrap();
+    temp
+}
+
+/// Creates a fake editor that prints the version and records each launch to `launches` in the folder.
+fn create_editor(folder: &Path, version: &str) -> PathBuf {
+    let editor = folder.join("Editor/Unity");
+    std::fs::create_dir_all(editor.parent().unwrap()).unwrap();
+    let launches = folder.join("launches");
+    let script = format!(
+        "#!/bin/sh\necho launched >> '{}'\necho '{version} (0123456789ab)'\n",
+        launches.display()
+    );
+    std::fs::write(&editor, script).unwrap();
+    std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+    editor
+}
+
+fn launches(folder: &Path) -> usize {
+    std::fs::read_to_string(folder.join("launches"))
+        .map(|x| x.lines().count())
+        .unwrap_or(0)
+}
+
+#[tokio::test]
+async fn version_from_folder_name() {
+    let temp = temp_dir();
+    let io = DefaultEnvironmentIo::new(temp.clone().into_boxed_path());
+    let folder = temp.join("2022.3.22f1");
+    let editor = create_editor(&folder, "2019.4.31f1");
+
+    let found = find_unity_in_search_roots(&io, &[temp.clone(), temp.join("not-exists")]).await;
+
+    assert_eq!(found, vec![(UnityVersion::new_f1(2022, 3, 22), editor)]);
+    assert_eq!(launches(&folder), 0);
+
+    std::fs::remove_dir_all(&temp).unwrap();
+}
+
+#[tokio::test]
+async fn launched_version_is_cached() {
+    let temp = temp_dir();
+    let io = DefaultEnvironmentIo::new(temp.clone().into_boxed_path());
+    let folder = temp.join("custom");
+    let editor = create_editor(&folder, "2022.3.6f1");
+
+    for _ in 0..2 {
+        let found = find_unity_in_search_roots(&io, std::slice::from_ref(&folder)).await;
+        assert_eq!(
+            found,
+            vec![(UnityVersion::new_f1(2022, 3, 6), editor.clone())]
+        );
+    }
+    assert_eq!(launches(&folder), 1);
+
+    // replacing the editor invalidates the cache
+    create_editor(&folder, "2022.3.22f1");
+    std::fs::File::options()
+        .write(true)
+        .open(&editor)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+
+    let found = find_unity_in_search_roots(&io, std::slice::from_ref(&folder)).await;
+    assert_eq!(found, vec![(UnityVersion::new_f1(2022, 3, 22), editor)]);
+    assert_eq!(launches(&folder), 2);
+
+    std::fs::remove_dir_all(&temp).unwrap();
+}
@@ -0,0 +1,203 @@
+use crate::common::*;
+use futures::AsyncReadExt;
+use futures::executor::block_on;
+use serde_json::{Value, json};
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::{ScopedRegistry, UpgradeUpmPackageErr, UpmDependency};
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const MANIFEST: &str = r#"{
+  "dependencies": {
+    "com.unity.timeline": "1.2.6",
+    "com.example.git": "https://github.com/example/package.git#v1.0.0"
+  },
+  "scopedRegistries": [
+    {
+      "name": "package.openupm.com",
+      "url": "https://package.openupm.com",
+      "scopes": ["com.openupm"],
+      "overrideBuiltIns": false
+    }
+  ],
+  "testables": ["com.unity.timeline"]
+}"#;
+
+async fn read_manifest(project: &vrc_get_vpm::UnityProject<VirtualFileSystem>) -> Value {
+    let mut buffer = String::new();
+    project
+        .io()
+        .open("Packages/manifest.json".as_ref())
+        .await
+        .unwrap()
+        .read_to_string(&mut buffer)
+        .await
+        .unwrap();
+    serde_json::from_str(&buffer).unwrap()
+}
+
+#[test]
+fn read_upm_dependencies() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Packages/manifest.json", MANIFEST)
+            .build()
+            .await
+            .unwrap();
+
+        let dependencies = project.upm_dependencies().collect::<Vec<_>>();
+        assert_eq!(
+            dependencies,
+            vec![
+                (
+                    "com.unity.timeline",
+                    &UpmDependency::Version(Version::new(1, 2, 6))
+                ),
+                (
+                    "com.example.git",
+                    &UpmDependency::OtherNotation(
+                        "https://github.com/example/package.git#v1.0.0".into()
+                    )
+                ),
+            ]
+        );
+
+        let registries = project.upm_scoped_registries();
+        assert_eq!(registries.len(), 1);
+        assert_eq!(registries[0].name(), "package.openupm.com");
+        assert_eq!(registries[0].url(), "https://package.openupm.com");
+        assert_eq!(registries[0].scopes(), &["com.openupm".into()]);
+    })
+}
+
+#[test]
+fn modify_upm_dependencies() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file("Packages/manifest.json", MANIFEST)
+            .build()
+            .await
+            .unwrap();
+
+        project.add_upm_dependency("com.example.local", "file:../LocalPackage".parse().unwrap());
+        project.remove_upm_dependency("com.example.git");
+        project
+            .upgrade_upm_dependency("com.unity.timeline", Version::new(1, 4, 0))
+            .unwrap();
+
+        project.save().await.unwrap();
+
+        assert_eq!(
+            read_manifest(&project).await,
+            json!({
+                "dependencies": {
+                    "com.unity.timeline": "1.4.0",
+                    "com.example.local": "file:../LocalPackage",
+                },
+                "scopedRegistries": [
+                    {
+                        "name": "package.openupm.com",
+                        "url": "https://package.openupm.com",
+                        "scopes": ["com.openupm"],
+                        "overrideBuiltIns": false,
+                    }
+                ],
+                "testables": ["com.unity.timeline"],
+            })
+        );
+    })
+}
+
+#[test]
+fn upgrade_upm_dependency_errors() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file("Packages/manifest.json", MANIFEST)
+            .build()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            project.upgrade_upm_dependency("com.example.none", Version::new(1, 0, 0)),
+            Err(UpgradeUpmPackageErr::NotInstalled { .. })
+        ));
+        assert!(matches!(
+            project.upgrade_upm_dependency("com.example.git", Version::new(1, 0, 0)),
+            Err(UpgradeUpmPackageErr::NotRegistryVersion { .. })
+        ));
+        assert!(matches!(
+            project.upgrade_upm_dependency("com.unity.timeline", Version::new(1, 0, 0)),
+            Err(UpgradeUpmPackageErr::Downgrading { .. })
+        ));
+    })
+}
+
+#[test]
+fn modify_scoped_registries() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_file("Packages/manifest.json", MANIFEST)
+            .build()
+            .await
+            .unwrap();
+
+        // merged to existing registry
+        project.add_upm_scoped_registry(ScopedRegistry::new(
+            "OpenUPM".into(),
+            "https://package.openupm.com".into(),
+            vec!["com.openupm".into(), "com.anatawa12".into()],
+        ));
+        project.add_upm_scoped_registry(ScopedRegistry::new(
+            "Example".into(),
+            "https://registry.example.com".into(),
+            vec!["com.example".into()],
+        ));
+        assert!(project.remove_upm_scoped_registry("https://registry.example.com"));
+        assert!(!project.remove_upm_scoped_registry("Example"));
+        project.add_upm_scoped_registry(ScopedRegistry::new(
+            "Example".into(),
+            "https://registry2.example.com".into(),
+            vec!["com.example".into()],
+        ));
+
+        project.save().await.unwrap();
+
+        assert_eq!(
+            read_manifest(&project).await["scopedRegistries"],
+            json!([
+                {
+                    "name": "package.openupm.com",
+                    "url": "https://package.openupm.com",
+                    "scopes": ["com.openupm", "com.anatawa12"],
+                    "overrideBuiltIns": false,
+                },
+                {
+                    "name": "Example",
+                    "url": "https://registry2.example.com",
+                    "scopes": ["com.example"],
+                },
+            ])
+        );
+    })
+}
+
+#[test]
+fn parse_upm_dependency() {
+    assert_eq!(
+        "1.0.0".parse::<UpmDependency>().unwrap(),
+        UpmDependency::Version(Version::new(1, 0, 0))
+    );
+    assert!(
+        "git+https://example.com/repo"
+            .parse::<UpmDependency>()
+            .is_ok()
+    );
+    assert!(
+        "https://example.com/repo.git?path=/Packages/pkg"
+            .parse::<UpmDependency>()
+            .is_ok()
+    );
+    assert!("file:../Package".parse::<UpmDependency>().is_ok());
+    assert!("latest".parse::<UpmDependency>().is_err());
+}
@@ -3,30 +3,36 @@ use indexmap::IndexMap;
 use itertools::Itertools;
 
 use futures::future::join_all;
-use log::warn;
+use log::{error, info, warn};
 use reqwest::Url;
 use reqwest::header::{HeaderName, HeaderValue, InvalidHeaderName, InvalidHeaderValue};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::error::Error as StdError;
 use std::ffi::OsStr;
 use std::fmt::{Debug, Display};
+use std::io::{self, IsTerminal};
 use std::num::NonZeroU32;
 use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::str::FromStr;
+use std::time::Duration;
 use tokio::fs::read_to_string;
 use vrc_get_vpm::environment::{
     AddRepositoryErr, AddUserPackageResult, PackageCollection, PackageInstaller, Settings,
     UserPackageCollection, add_remote_repo, cleanup_repos_folder, clear_package_cache,
+    download_package_archive, read_package_archive, read_package_folder,
 };
+use vrc_get_vpm::git::GitSource;
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, IoTrait};
 use vrc_get_vpm::repositories_file::RepositoriesFile;
-use vrc_get_vpm::repository::RemoteRepository;
+use vrc_get_vpm::repository::{RemoteRepository, is_file_url};
 use vrc_get_vpm::unity_project::pending_project_changes::{PackageChange, RemoveReason};
-use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
-use vrc_get_vpm::version::Version;
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, PROJECT_REPOSITORIES_PATH, PendingProjectChanges,
+};
+use vrc_get_vpm::version::{DependencyRange, PrereleaseAcceptance, Version, VersionRange};
 use vrc_get_vpm::{
     PackageCollection as _, PackageInfo, PackageManifest, UserRepoSetting, VersionSelector,
 };
@@ -69,11 +75,30 @@ macro_rules! multi_command {
 
 // small wrapper utilities
 
+/// Prints the error with the stable error code and exits.
+///
+/// The first argument is the variant of [`json::ErrorCode`], followed by the message.
 macro_rules! exit_with {
-    ($($tt:tt)*) => {{
-        eprintln!($($tt)*);
-        ::std::process::exit(1)
-    }};
+    ($code: ident, $($tt:tt)*) => {
+        $crate::commands::json::exit_with_error(
+            $crate::commands::json::ErrorCode::$code,
+            None,
+            ::std::format_args!($($tt)*),
+        )
+    };
+}
+
+/// Prints the human-readable message.
+///
+/// With `--json-format`, the message is printed to stderr to keep stdout for json.
+macro_rules! message {
+    ($($tt:tt)*) => {
+        if $crate::commands::json::enabled() {
+            eprintln!($($tt)*);
+        } else {
+            println!($($tt)*);
+        }
+    };
 }
 
 #[derive(Args, Default)]
@@ -84,41 +109,210 @@ struct EnvArgs {
     /// do not update local repository cache.
     #[arg(long)]
     no_update: bool,
+    /// update all local repository caches even if they are fresh.
+    #[arg(long, conflicts_with = "no_update")]
+    refresh: bool,
+    /// use the specified folder as the environment folder instead of the default one.
+    ///
+    /// The environment folder contains settings, repository caches, package caches,
+    /// the project and unity list, and templates.
+    /// If not specified, VRC_GET_ENV_DIR environment variable or the vrc-get-env folder
+    /// beside the vrc-get executable (portable mode) is used if exists.
+    #[arg(long, value_name = "PATH")]
+    env_dir: Option<Box<Path>>,
+}
+
+impl EnvArgs {
+    fn environment_io(&self) -> DefaultEnvironmentIo {
+        match &self.env_dir {
+            Some(env_dir) => DefaultEnvironmentIo::new_with_folder(env_dir),
+            None => DefaultEnvironmentIo::new_default(),
+        }
+    }
 }
 
 async fn load_collection(
     io: &DefaultEnvironmentIo,
     http: Option<&reqwest::Client>,
-    no_update: bool,
+    env_args: &EnvArgs,
 ) -> PackageCollection {
-    let mut settings = Settings::load(io).await.exit_context("loading settings");
-    let mut collection = PackageCollection::load(&settings, io, http.filter(|_| !no_update))
-        .await
-        .exit_context("loading repositories");
+    match try_load_collection(io, http, env_args).await {
+        Ok(collection) => collection,
+        Err((context, err)) => {
+            json::exit_with_error(json::ErrorCode::OperationFailed, Some(context), err)
+        }
+    }
+}
+
+/// Loads the collection like [`load_collection`], but returns the error with the context instead of exiting.
+async fn try_load_collection(
+    io: &DefaultEnvironmentIo,
+    http: Option<&reqwest::Client>,
+    env_args: &EnvArgs,
+) -> Result<PackageCollection, (&'static str, io::Error)> {
+    let no_update = env_args.no_update;
+    let mut settings = (Settings::load(io).await).map_err(|e| ("loading settings", e))?;
+    let http = http.filter(|_| !no_update);
+    let collection = if env_args.refresh {
+        PackageCollection::load_force(&settings, io, http).await
+    } else {
+        PackageCollection::load(&settings, io, http).await
+    };
+    let mut collection = collection.map_err(|e| ("loading repositories", e))?;
 
     if !no_update {
         // dedup
         settings.update_id(&collection);
         let removed = settings.remove_id_duplication();
         collection.remove_repositories(&removed, io).await;
-        settings.save(io).await.exit_context("saving settings");
+        (settings.save(io).await).map_err(|e| ("saving settings", e))?;
     }
 
+    Ok(collection)
+}
+
+async fn load_collection_for_project(
+    io: &DefaultEnvironmentIo,
+    http: Option<&reqwest::Client>,
+    env_args: &EnvArgs,
+    unity: &UnityProject,
+) -> PackageCollection {
+    let mut collection = load_collection(io, http, env_args).await;
+    let http = http.filter(|_| !env_args.no_update);
+    add_project_repositories(&mut collection, io, http, unity).await;
+    add_project_packages(&mut collection, io, http, unity)
+        .await
+        .exit_context("loading project-local packages");
+
     collection
 }
 
-async fn load_unity(path: Option<Box<Path>>) -> UnityProject {
-    let io = match path {
+/// Adds the project-local package archives and the packages of git dependencies to the collection.
+///
+/// Errors loading git repositories are only warned since the other packages are still usable.
+async fn add_project_packages(
+    collection: &mut PackageCollection,
+    io: &DefaultEnvironmentIo,
+    http: Option<&reqwest::Client>,
+    unity: &UnityProject,
+) -> io::Result<()> {
+    for (path, manifest) in unity.local_package_archives().await? {
+        collection.add_archive_package(path, manifest);
+    }
+
+    for (name, source, commit) in unity.git_dependencies() {
+        let result = collection
+            .add_git_packages(io, &source, Some(name), commit, http.is_some())
+            .await;
+        if let Err(e) = result {
+            warn!("loading {name} from git repository {source}: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds repositories listed in `Packages/vpm-repositories.txt` of the project to the collection.
+///
+/// Repositories not added to vrc-get are only used if the user accepts them.
+async fn add_project_repositories(
+    collection: &mut PackageCollection,
+    io: &DefaultEnvironmentIo,
+    http: Option<&reqwest::Client>,
+    unity: &UnityProject,
+) {
+    let Some(repositories) = unity
+        .project_repositories()
+        .await
+        .exit_context("loading project repositories")
+    else {
+        return;
+    };
+
+    let settings = Settings::load(io).await.exit_context("loading settings");
+    let untrusted = repositories
+        .repositories()
+        .iter()
+        .filter(|x| !settings.has_remote_repo(x.url()))
+        .collect::<Vec<_>>();
+
+    if untrusted.is_empty() {
+        return;
+    }
+
+    eprintln!("This project uses the following repositories not added to vrc-get:");
+    for repository in &untrusted {
+        eprintln!("  {}", repository.url());
+    }
+
+    if std::io::stdin().is_terminal() && confirm_prompt("Do you want to use them for this project?")
+    {
+        let duplicated = collection
+            .add_repositories(&untrusted, io, http)
+            .await
+            .exit_context("loading project repositories");
+        for repository in duplicated {
+            warn!(
+                "repository {} is not used since the id is already used by another repository",
+                repository.url()
+            );
+        }
+    } else {
+        warn_project_repositories_not_used();
+    }
+}
+
+/// Warns if the project lists repositories not added to vrc-get.
+///
+/// This is for the places the user cannot be asked to accept them, so they are not used.
+async fn warn_untrusted_project_repositories(
+    io: &DefaultEnvironmentIo,
+    unity: &UnityProject,
+) -> io::Result<()> {
+    let Some(repositories) = unity.project_repositories().await? else {
+        return Ok(());
+    };
+    let settings = Settings::load(io).await?;
+    if (repositories.repositories().iter()).any(|x| !settings.has_remote_repo(x.url())) {
+        warn_project_repositories_not_used();
+    }
+    Ok(())
+}
+
+fn warn_project_repositories_not_used() {
+    warn!(
+        "repositories listed in {PROJECT_REPOSITORIES_PATH} are not used. \
+        add them with `vrc-get repo add` to use them"
+    );
+}
+
+fn project_io(path: Option<Box<Path>>) -> DefaultProjectIo {
+    match path {
         None => {
             let current_dir = env::current_dir().exit_context("getting current directory");
             DefaultProjectIo::find_project_parent(current_dir).exit_context("finding unity project")
         }
         Some(path) => DefaultProjectIo::new(path),
-    };
+    }
+}
 
-    UnityProject::load(io)
+async fn load_unity(path: Option<Box<Path>>) -> UnityProject {
+    load_unity_with_io(project_io(path)).await
+}
+
+async fn load_unity_with_io(io: DefaultProjectIo) -> UnityProject {
+    let unity = UnityProject::load(io)
         .await
-        .exit_context("loading unity project")
+        .exit_context("loading unity project");
+
+    if unity.unfinished_changes().is_some() {
+        warn!(
+            "the last operation on this project was interrupted. \
+            run `vrc-get recover` to finish it or `vrc-get recover --rollback` to revert it."
+        );
+    }
+
+    unity
 }
 
 fn absolute_path(path: impl AsRef<Path>) -> PathBuf {
@@ -162,14 +356,19 @@ fn get_package<'env>(
     selector: VersionSelector,
 ) -> PackageInfo<'env> {
     env.find_package_by_name(name, selector)
-        .unwrap_or_else(|| exit_with!("no matching package not found"))
+        .unwrap_or_else(|| exit_with!(PackageNotFound, "no matching package not found"))
 }
 
 fn confirm_prompt(msg: &str) -> bool {
     use std::io;
     use std::io::Write;
     fn _impl(msg: &str) -> io::Result<bool> {
-        let mut stdout = io::stdout();
+        // keep stdout for json
+        let mut stdout: Box<dyn Write> = if json::enabled() {
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        };
         let stdin = io::stdin();
         let mut buf = String::new();
         loop {
@@ -190,17 +389,44 @@ fn confirm_prompt(msg: &str) -> bool {
         }
     }
 
+    if json::enabled() && !std::io::stdin().is_terminal() {
+        exit_with!(
+            ConfirmationRequired,
+            "confirmation is required but stdin is not a terminal. use --yes to skip confirm"
+        );
+    }
+
     _impl(msg).unwrap_or(false)
 }
 
+/// Asks the user to confirm and exits if not confirmed.
+fn confirm_or_exit(msg: &str) {
+    if !confirm_prompt(msg) {
+        if json::enabled() {
+            exit_with!(Cancelled, "cancelled");
+        }
+        exit(1);
+    }
+}
+
 fn print_prompt_install(changes: &PendingProjectChanges) {
     if changes.package_changes().is_empty() {
-        exit_with!("nothing to do")
+        exit_with!(NothingToDo, "nothing to do")
     }
 
+    if json::enabled() {
+        json::defer_field("changes", json::ChangesInfo::new(changes));
+    } else {
+        print_changes(changes);
+    }
+}
+
+fn print_changes(changes: &PendingProjectChanges) {
     let mut newly_installed = Vec::new();
     let mut adding_to_dependencies = Vec::new();
     let mut removed = Vec::new();
+    let mut ejected = Vec::new();
+    let mut adopted = Vec::new();
 
     for (name, change) in changes.package_changes() {
         match change {
@@ -209,12 +435,18 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
                     newly_installed.push(package);
                 }
                 if let Some(v) = change.to_dependencies() {
-                    adding_to_dependencies.push((name, v));
+                    adding_to_dependencies.push((name, v, change.replaced_dependency()));
                 }
             }
             PackageChange::Remove(change) => {
                 removed.push((change.reason(), name));
             }
+            PackageChange::Eject(change) => {
+                ejected.push((name, change.ejected_package().version()));
+            }
+            PackageChange::Adopt(change) => {
+                adopted.push((name, change));
+            }
         }
     }
 
@@ -231,8 +463,14 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
 
     if !adding_to_dependencies.is_empty() {
         println!("You're adding the following packages to dependencies:");
-        for (name, range) in &adding_to_dependencies {
-            println!("- {} version {}", name, range);
+        for (name, range, replaced) in &adding_to_dependencies {
+            if let Some(replaced) = replaced {
+                println!(
+                    "- {name} version {range} (replacing {replaced} since the version is out of the range)"
+                );
+            } else {
+                println!("- {} version {}", name, range);
+            }
         }
     }
 
@@ -260,6 +498,44 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
         }
     }
 
+    if !ejected.is_empty() {
+        println!("You're ejecting the following packages:");
+        ejected.sort_by_key(|(name, _)| *name);
+        for (name, version) in ejected {
+            println!("- {} version {}", name, version);
+        }
+    }
+
+    if !adopted.is_empty() {
+        println!("You're adopting the following packages:");
+        adopted.sort_by_key(|(name, _)| *name);
+        for (name, change) in &adopted {
+            println!("- {} version {}", name, change.version());
+        }
+
+        for (name, change) in adopted {
+            match change.modified_files() {
+                None => warn!(
+                    "{name} is not found in the repositories and was not ejected by vrc-get so local modifications cannot be checked"
+                ),
+                Some([]) => {}
+                Some(files) => {
+                    warn!("{name} has the following local modifications:");
+                    for file in files {
+                        warn!("- {file}");
+                    }
+                }
+            }
+        }
+    }
+
+    if !changes.dependency_overrides().is_empty() {
+        warn!("The following dependency overrides in vpm-manifest.json are active:");
+        for (key, range) in changes.dependency_overrides() {
+            warn!("- {key}: {range}");
+        }
+    }
+
     // process package conflicts
     {
         let mut conflicts = (changes.conflicts().iter())
@@ -314,9 +590,9 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
 
 fn prompt_install(yes: bool) {
     if yes {
-        println!("--yes is set. skipping confirm");
-    } else if !confirm_prompt("Do you want to apply those changes?") {
-        exit(1);
+        message!("--yes is set. skipping confirm");
+    } else {
+        confirm_or_exit("Do you want to apply those changes?");
     }
 }
 
@@ -346,6 +622,11 @@ fn require_prompt_for_install(
         return true;
     };
 
+    // replacing the range in dependencies should be confirmed
+    if install.replaced_dependency().is_some() {
+        return true;
+    }
+
     // if we're installing package,
     if let Some(package) = install.install_package() {
         if let Some(request_version) = version {
@@ -371,28 +652,69 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
     {
         match self {
             Ok(value) => value,
-            Err(err) => exit_with!("error {context}: {err}"),
+            Err(err) => json::exit_with_error(json::ErrorCode::OperationFailed, Some(context), err),
         }
     }
 }
 
+mod audit;
+mod batch;
+mod cleanup_legacy;
+mod clone_project;
+mod config;
+mod doctor;
+mod guid;
 mod info;
+mod json;
 mod migrate;
+mod serve;
+mod upm;
 #[cfg(feature = "experimental-vcc")]
 mod vcc;
 
 /// Open Source command line interface of VRChat Package Manager.
 #[derive(Parser)]
 #[command(author, version, about)]
+pub struct Cli {
+    /// Print machine-readable json with the specified format version instead of human-readable text
+    ///
+    /// Errors are also printed to stdout as a json object with a stable error code.
+    /// See docs/json-output.md in the repository for the schema.
+    #[arg(long = "json-format", global = true, value_name = "FORMAT")]
+    json_format: Option<NonZeroU32>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Cli {
+    pub async fn run(self) {
+        json::init(self.json_format);
+        self.command.run().await;
+        json::finish();
+    }
+}
+
+#[derive(Subcommand)]
 pub enum Command {
     #[command(alias = "i")]
     Install(Install),
     Resolve(Resolve),
     #[command(alias = "rm")]
     Remove(Remove),
+    Eject(Eject),
+    Adopt(Adopt),
+    EditRange(EditRange),
     Reinstall(Reinstall),
+    Recover(Recover),
+    CleanupLegacy(cleanup_legacy::CleanupLegacy),
+    Clone(clone_project::CloneProject),
+    Doctor(doctor::Doctor),
+    #[command(subcommand)]
+    Guid(guid::Guid),
     Update(Update),
     Outdated(Outdated),
+    Audit(audit::Audit),
     Upgrade(Upgrade),
     Downgrade(Downgrade),
     Search(Search),
@@ -401,11 +723,16 @@ pub enum Command {
     #[command(subcommand)]
     UserPackage(UserPackage),
     #[command(subcommand)]
+    Upm(upm::Upm),
+    #[command(subcommand)]
     Info(info::Info),
     #[command(subcommand)]
     Migrate(migrate::Migrate),
     #[command(subcommand)]
     Cache(Cache),
+    #[command(subcommand)]
+    Config(config::Config),
+    Serve(serve::Serve),
     #[cfg(feature = "experimental-vcc")]
     #[command(subcommand)]
     Vcc(vcc::Vcc),
@@ -420,17 +747,29 @@ multi_command!(Command is
     Install,
     Resolve,
     Remove,
+    Eject,
+    Adopt,
+    EditRange,
     Reinstall,
+    Recover,
+    CleanupLegacy,
+    Clone,
+    Doctor,
+    Guid,
     Update,
     Outdated,
+    Audit,
     Upgrade,
     Downgrade,
     Search,
     Repo,
     UserPackage,
+    Upm,
     Info,
     Migrate,
     Cache,
+    Config,
+    Serve,
     Vcc,
     Completion,
 );
@@ -442,12 +781,20 @@ multi_command!(Command is
 #[derive(Parser)]
 #[command(author, version)]
 pub struct Install {
-    /// id of Package
+    /// id of Package, or path or URL to the zip file or folder of the package
+    ///
+    /// Paths should contain a path separator or end with `.zip`, like `./package.zip`
+    ///
+    /// Packages in git repositories can be installed with `git+<url>`, optionally with a tag or commit like
+    /// `git+https://example.com/package.git#v1.0.0`. Without a tag or commit, the latest version tag is installed.
     #[arg()]
     id: Option<String>,
-    /// Version of package. if not specified, latest version will be used
+    /// Version or version range of package. if not specified, latest version will be used
+    ///
+    /// With a range like `^1.2` or `">=3.5 <4"`, the best matching version is installed
+    /// and the range is saved to the dependencies.
     #[arg(id = "VERSION")]
-    version: Option<Version>,
+    version: Option<VersionOrRange>,
     /// Include prerelease
     #[arg(long = "prerelease")]
     prerelease: bool,
@@ -458,6 +805,14 @@ pub struct Install {
     #[arg(long = "name", short = 'n')]
     name: bool,
 
+    /// Add the package folder to the user package list when installing from a folder
+    #[arg(long = "add-user-package")]
+    add_user_package: bool,
+
+    /// Copy the zip file into the project-local packages folder when installing from a zip file or URL
+    #[arg(long = "copy-to-project", conflicts_with = "add_user_package")]
+    copy_to_project: bool,
+
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
@@ -469,27 +824,95 @@ pub struct Install {
     yes: bool,
 }
 
+/// The version of the package specified in the command line
+#[derive(Clone)]
+enum VersionOrRange {
+    Version(Version),
+    Range(VersionRange),
+}
+
+impl FromStr for VersionOrRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Version::from_str(s) {
+            Ok(version) => Ok(Self::Version(version)),
+            Err(_) => VersionRange::from_str(s)
+                .map(Self::Range)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+fn prerelease_acceptance(prerelease: bool) -> PrereleaseAcceptance {
+    if prerelease {
+        PrereleaseAcceptance::Allow
+    } else {
+        PrereleaseAcceptance::Minimum
+    }
+}
+
+/// The package specified with path or URL instead of id
+enum PackageLocation {
+    Url(Url),
+    Path(PathBuf),
+}
+
+impl PackageLocation {
+    fn parse(id: &str) -> Option<Self> {
+        if id.starts_with("http://") || id.starts_with("https://") {
+            let url = Url::parse(id).exit_context("parsing package url");
+            return Some(Self::Url(url));
+        }
+
+        if id.contains('/') || id.contains(std::path::MAIN_SEPARATOR) || id.ends_with(".zip") {
+            return Some(Self::Path(absolute_path(id)));
+        }
+
+        None
+    }
+}
+
 impl Install {
     pub async fn run(self) {
-        let Some(name) = self.id else {
+        let Some(name) = self.id.clone() else {
             // if resolve
             return Resolve {
                 project: self.project,
+                projects: Default::default(),
                 env_args: self.env_args,
+                yes: false,
+                watch: false,
             }
             .run()
             .await;
         };
 
+        if name.starts_with(GitSource::PREFIX) {
+            return self.install_from_git(GitSource::parse(&name)).await;
+        }
+
+        if let Some(location) = PackageLocation::parse(&name) {
+            return self.install_from_location(location).await;
+        }
+
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let io = self.env_args.environment_io();
         let mut unity = load_unity(self.project).await;
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
 
         let version_selector = match self.version {
             None => VersionSelector::latest_for(unity.unity_version(), self.prerelease),
-            Some(ref version) => VersionSelector::specific_version(version),
+            Some(VersionOrRange::Version(ref version)) => {
+                VersionSelector::specific_version(version)
+            }
+            Some(VersionOrRange::Range(ref range)) => VersionSelector::range_for(
+                unity.unity_version(),
+                range,
+                prerelease_acceptance(self.prerelease),
+            ),
         };
         let packages = if self.name {
             warn!("--name is experimental and behavior may change in the future.");
@@ -511,17 +934,121 @@ impl Install {
                         .any(|x| normalize_name(x) == normalized)
             });
             if packages.is_empty() {
-                exit_with!("no matching package not found")
+                exit_with!(PackageNotFound, "no matching package not found")
             }
             packages.into_iter().unique_by(|x| x.name()).collect()
         } else {
             vec![get_package(&collection, &name, version_selector)]
         };
 
+        let changes = if let Some(VersionOrRange::Range(range)) = &self.version {
+            let packages = packages
+                .iter()
+                .map(|&x| (x, DependencyRange::range(range.clone())))
+                .collect::<Vec<_>>();
+            unity
+                .add_package_request_with_ranges(&collection, &packages, self.prerelease)
+                .await
+        } else {
+            unity
+                .add_package_request(
+                    &collection,
+                    &packages,
+                    AddPackageOperation::InstallToDependencies,
+                    self.prerelease,
+                )
+                .await
+        };
+        let changes = changes.exit_context("collecting packages to be installed");
+
+        print_prompt_install(&changes);
+
+        if require_prompt_for_install(&changes, name.as_str(), None) {
+            prompt_install(self.yes);
+        }
+
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .exit_context("adding package");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+
+    async fn install_from_location(self, location: PackageLocation) {
+        if self.version.is_some() {
+            exit_with!(
+                InvalidArgument,
+                "version cannot be specified when installing from path or url"
+            );
+        }
+        if self.name {
+            exit_with!(
+                InvalidArgument,
+                "--name cannot be used when installing from path or url"
+            );
+        }
+
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        let (path, is_archive) = match location {
+            PackageLocation::Url(url) => {
+                let Some(client) = &client else {
+                    exit_with!(
+                        Offline,
+                        "downloading package is not allowed in offline mode"
+                    );
+                };
+                let path = download_package_archive(&io, client, &url)
+                    .await
+                    .exit_context("downloading package");
+                (path, true)
+            }
+            PackageLocation::Path(path) => {
+                let metadata = tokio::fs::metadata(&path)
+                    .await
+                    .exit_context("reading package");
+                let is_archive = !metadata.is_dir();
+                (path, is_archive)
+            }
+        };
+
+        let manifest = if is_archive {
+            read_package_archive(&io, &path).await
+        } else {
+            read_package_folder(&io, &path).await
+        };
+        let manifest = manifest.exit_context("reading package.json");
+
+        if self.add_user_package && is_archive {
+            exit_with!(
+                InvalidArgument,
+                "--add-user-package can only be used with a package folder"
+            );
+        }
+        if self.copy_to_project && !is_archive {
+            exit_with!(
+                InvalidArgument,
+                "--copy-to-project can only be used with a zip file or url"
+            );
+        }
+
+        let package = if is_archive {
+            PackageInfo::archive(&manifest, &path)
+        } else {
+            PackageInfo::local(&manifest, &path)
+        };
+
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
         let changes = unity
             .add_package_request(
                 &collection,
-                &packages,
+                &[package],
                 AddPackageOperation::InstallToDependencies,
                 self.prerelease,
             )
@@ -530,7 +1057,86 @@ impl Install {
 
         print_prompt_install(&changes);
 
-        if require_prompt_for_install(&changes, name.as_str(), None) {
+        if require_prompt_for_install(&changes, manifest.name(), Some(manifest.version())) {
+            prompt_install(self.yes);
+        }
+
+        if self.add_user_package {
+            let mut settings = Settings::load(&io).await.exit_context("loading settings");
+            match settings.add_user_package(&path, &io).await {
+                AddUserPackageResult::BadPackage => {
+                    exit_with!(BadPackage, "bad package: {}", path.display())
+                }
+                AddUserPackageResult::AlreadyAdded | AddUserPackageResult::Success => {}
+                AddUserPackageResult::NonAbsolute => unreachable!("absolute path"),
+            }
+            settings.save(&io).await.exit_context("saving settings");
+        }
+
+        if self.copy_to_project {
+            unity
+                .add_local_package_archive(&io, &path, &manifest)
+                .await
+                .exit_context("copying package to the project");
+        }
+
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .exit_context("adding package");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+
+    async fn install_from_git(self, source: GitSource) {
+        if self.version.is_some() {
+            exit_with!(
+                InvalidArgument,
+                "version cannot be specified when installing from git repository. use tag or commit like git+<url>#v1.0.0"
+            );
+        }
+        if self.name || self.add_user_package || self.copy_to_project {
+            exit_with!(
+                InvalidArgument,
+                "--name, --add-user-package, and --copy-to-project cannot be used when installing from git repository"
+            );
+        }
+
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        let mut collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+        // the cloned repository is used in offline mode
+        let Some(name) = collection
+            .add_git_packages(&io, &source, None, None, client.is_some())
+            .await
+            .exit_context("loading git repository")
+        else {
+            exit_with!(
+                PackageNotFound,
+                "no package with version tags found in {source}"
+            );
+        };
+
+        let selector = VersionSelector::latest_for(unity.unity_version(), self.prerelease);
+        let package = get_package(&collection, &name, selector);
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
+        let changes = unity
+            .add_package_request(
+                &collection,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                self.prerelease,
+            )
+            .await
+            .exit_context("collecting packages to be installed");
+
+        print_prompt_install(&changes);
+
+        if require_prompt_for_install(&changes, &name, Some(package.version())) {
             prompt_install(self.yes);
         }
 
@@ -554,15 +1160,40 @@ pub struct Resolve {
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
     #[command(flatten)]
+    projects: batch::ProjectsArgs,
+    #[command(flatten)]
     env_args: EnvArgs,
+
+    /// skip confirm. only used with --all-projects
+    #[arg(short, long, requires = "all_projects")]
+    yes: bool,
+
+    /// Keep running and resolve the project whenever `vpm-manifest.json` or `manifest.json` is changed.
+    /// With --all-projects, all matching projects are watched.
+    /// The changes are applied without confirmation, and errors are reported without stopping watching.
+    /// Projects failed to resolve are retried on the next check.
+    /// Repositories listed in the project are used only if they are added to vrc-get.
+    #[arg(long, conflicts_with = "yes")]
+    watch: bool,
 }
 
 impl Resolve {
+    /// The interval to check the modification time of the manifests with `--watch`
+    const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
     pub async fn run(self) {
+        if self.watch {
+            return self.run_watch().await;
+        }
+        if self.projects.all_projects {
+            return self.run_all_projects().await;
+        }
+
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
+        let io = self.env_args.environment_io();
         let mut unity = load_unity(self.project).await;
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
 
         let installer = PackageInstaller::new(&io, client.as_ref());
 
@@ -578,6 +1209,129 @@ impl Resolve {
             .await
             .exit_context("installing packages");
     }
+
+    async fn run_all_projects(self) {
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let projects = self.projects.load_projects(&io).await;
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
+        self.projects
+            .apply_changes(projects, &io, &installer, self.yes, async |unity| {
+                unity
+                    .resolve_request(&collection)
+                    .await
+                    .map_err(|err| format!("collecting packages to be installed: {err}"))
+            })
+            .await;
+    }
+
+    async fn run_watch(self) {
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let projects = if self.projects.all_projects {
+            self.projects.load_projects(&io).await
+        } else {
+            let unity = load_unity(self.project.clone()).await;
+            let path = unity.project_dir();
+            vec![batch::BatchProject {
+                name: (path.file_name().unwrap_or(path.as_os_str()))
+                    .to_string_lossy()
+                    .into(),
+                path: path.into(),
+            }]
+        };
+
+        info!(
+            "watching {} project(s) for changes to the manifests",
+            projects.len()
+        );
+
+        // None at first so that the projects are checked on start
+        let mut last_modified = vec![None; projects.len()];
+        loop {
+            for (project, last_modified) in projects.iter().zip(&mut last_modified) {
+                let project_io = DefaultProjectIo::new(project.path.clone());
+                let modified = UnityProject::manifests_modified_time(&project_io).await;
+                if modified.is_none() || modified == *last_modified {
+                    continue;
+                }
+
+                // the time is kept on errors so that the project is retried on the next check
+                match self
+                    .resolve_watched(&io, client.as_ref(), project, project_io)
+                    .await
+                {
+                    Ok(()) => {
+                        // resolving updates the manifests, so take the time after resolving
+                        let project_io = DefaultProjectIo::new(project.path.clone());
+                        *last_modified = UnityProject::manifests_modified_time(&project_io).await;
+                    }
+                    Err(err) => error!("{}: {err}", project.name),
+                }
+            }
+
+            tokio::time::sleep(Self::WATCH_INTERVAL).await;
+        }
+    }
+
+    async fn resolve_watched(
+        &self,
+        io: &DefaultEnvironmentIo,
+        http: Option<&reqwest::Client>,
+        project: &batch::BatchProject,
+        project_io: DefaultProjectIo,
+    ) -> Result<(), String> {
+        let mut unity = UnityProject::load(project_io)
+            .await
+            .map_err(|err| format!("loading unity project: {err}"))?;
+        if !unity.should_resolve() {
+            return Ok(());
+        }
+
+        info!("{}: manifests changed, resolving", project.name);
+
+        // reload the collection every time to use packages added after starting to watch.
+        // errors are returned instead of exiting to keep watching
+        let mut collection = try_load_collection(io, http, &self.env_args)
+            .await
+            .map_err(|(context, err)| format!("{context}: {err}"))?;
+        if !self.projects.all_projects {
+            let http = http.filter(|_| !self.env_args.no_update);
+            warn_untrusted_project_repositories(io, &unity)
+                .await
+                .map_err(|err| format!("loading project repositories: {err}"))?;
+            add_project_packages(&mut collection, io, http, &unity)
+                .await
+                .map_err(|err| format!("loading project-local packages: {err}"))?;
+        }
+        let changes = unity
+            .resolve_request(&collection)
+            .await
+            .map_err(|err| format!("collecting packages to be installed: {err}"))?;
+
+        for (name, change) in changes.package_changes() {
+            let Some(package) = change.as_install().and_then(|x| x.install_package()) else {
+                continue;
+            };
+            info!(
+                "{}: installing {name} version {}",
+                project.name,
+                package.version()
+            );
+        }
+
+        let installer = PackageInstaller::new(io, http);
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .map_err(|err| format!("installing packages: {err}"))?;
+
+        info!("{}: resolved", project.name);
+
+        Ok(())
+    }
 }
 
 /// Remove package from Unity project.
@@ -599,31 +1353,291 @@ pub struct Remove {
     yes: bool,
 }
 
-impl Remove {
-    pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
-        let mut unity = load_unity(self.project).await;
+impl Remove {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        let changes = unity
+            .remove_request(&self.names.iter().map(String::as_ref).collect::<Vec<_>>())
+            .await
+            .exit_context("collecting packages to be removed");
+        let installer = PackageInstaller::new(&io, None::<&reqwest::Client>);
+
+        print_prompt_install(&changes);
+
+        let confirm =
+            changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
+
+        if confirm {
+            prompt_install(self.yes);
+        }
+
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .exit_context("removing packages");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Remove package from vpm-manifest.json but keep the files in the project to modify them.
+///
+/// The ejected package will not be restored by resolving.
+/// Use adopt command to lock the package again.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Eject {
+    /// Name of Packages to eject
+    #[arg(required = true)]
+    names: Vec<String>,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Eject {
+    pub async fn run(self) {
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+
+        let changes = unity
+            .eject_request(
+                &self.names.iter().map(String::as_ref).collect::<Vec<_>>(),
+                &collection,
+            )
+            .await
+            .exit_context("collecting packages to be ejected");
+        let installer = PackageInstaller::new(&io, None::<&reqwest::Client>);
+
+        print_prompt_install(&changes);
+        prompt_install(self.yes);
+
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .exit_context("ejecting packages");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Lock the unlocked package in vpm-manifest.json again.
+///
+/// The files are compared with the package in the repository and locally modified files are shown.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Adopt {
+    /// Name of Packages to adopt
+    #[arg(required = true)]
+    names: Vec<String>,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl Adopt {
+    pub async fn run(self) {
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
+        let changes = unity
+            .adopt_request(
+                &self.names.iter().map(String::as_ref).collect::<Vec<_>>(),
+                &collection,
+                &installer,
+            )
+            .await
+            .exit_context("collecting packages to be adopted");
+
+        print_prompt_install(&changes);
+
+        let modified = (changes.package_changes().values())
+            .filter_map(|x| x.as_adopt())
+            .any(|x| x.modified_files() != Some(&[]));
+        if modified {
+            prompt_install(self.yes);
+        }
+
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .exit_context("adopting packages");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Change the version range of the package in dependencies without reinstalling packages
+#[derive(Parser)]
+#[command(author, version)]
+pub struct EditRange {
+    /// Name of Package
+    #[arg()]
+    name: String,
+    /// The new version range like `^1.2` or `">=3.5 <4"`
+    #[arg()]
+    range: VersionRange,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl EditRange {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        let range = DependencyRange::range(self.range);
+        let changes = unity
+            .set_dependency_range_request(&self.name, range.clone())
+            .exit_context("editing dependency range");
+        let installer = PackageInstaller::new(&io, None::<&reqwest::Client>);
+
+        let out_of_range = unity
+            .get_locked(&self.name)
+            .map(|locked| locked.version().clone())
+            .filter(|version| !range.matches(version));
+
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .exit_context("editing dependency range");
+
+        message!("changed the range of {} to {}", self.name, range);
+        if let Some(version) = out_of_range {
+            warn!(
+                "the installed version {version} is out of the new range. \
+                run `vrc-get upgrade {}` to install the version in the range.",
+                self.name
+            );
+        }
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Finish or revert the operation interrupted in the middle of modifying packages
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Recover {
+    /// Revert the interrupted operation instead of finishing it
+    #[arg(long)]
+    rollback: bool,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Recover {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        let Some(changes) = unity.unfinished_changes() else {
+            exit_with!(NothingToDo, "nothing to do")
+        };
+
+        if self.rollback && !changes.can_rollback() {
+            exit_with!(
+                CannotRollback,
+                "all packages are already installed so the operation cannot be reverted"
+            );
+        }
+
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct RecoverInfo<'a> {
+                rollback: bool,
+                remove_packages: Vec<&'a str>,
+                install_packages: Vec<PackageVersionInfo<'a>>,
+            }
+
+            #[derive(Serialize)]
+            struct PackageVersionInfo<'a> {
+                name: &'a str,
+                version: &'a Version,
+            }
 
-        let changes = unity
-            .remove_request(&self.names.iter().map(String::as_ref).collect::<Vec<_>>())
-            .await
-            .exit_context("collecting packages to be removed");
-        let installer = PackageInstaller::new(&io, None::<&reqwest::Client>);
+            let info = RecoverInfo {
+                rollback: self.rollback,
+                remove_packages: changes.remove_packages().collect(),
+                install_packages: if self.rollback {
+                    vec![]
+                } else {
+                    (changes.install_packages())
+                        .map(|(name, version)| PackageVersionInfo { name, version })
+                        .collect()
+                },
+            };
+            json::defer_field("recover", info);
+        } else if self.rollback {
+            println!("The following packages will be restored:");
+            for name in changes.remove_packages() {
+                println!("- {name}");
+            }
+        } else {
+            println!("The following packages will be removed or replaced:");
+            for name in changes.remove_packages() {
+                println!("- {name}");
+            }
+            println!("The following packages will be installed:");
+            for (name, version) in changes.install_packages() {
+                println!("- {name}@{version}");
+            }
+        }
 
-        print_prompt_install(&changes);
+        prompt_install(self.yes);
 
-        let confirm =
-            changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
+        if self.rollback {
+            unity
+                .rollback_unfinished_changes()
+                .await
+                .exit_context("reverting changes");
+        } else {
+            let client = crate::create_client(self.env_args.offline);
+            let collection =
+                load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+            let installer = PackageInstaller::new(&io, client.as_ref());
 
-        if confirm {
-            prompt_install(self.yes);
+            unity
+                .roll_forward_unfinished_changes(&collection, &installer)
+                .await
+                .exit_context("finishing changes");
         }
 
-        unity
-            .apply_pending_changes(&installer, changes)
-            .await
-            .exit_context("removing packages");
-
         update_project_last_modified(&io, unity.project_dir()).await;
     }
 }
@@ -633,6 +1647,8 @@ impl Remove {
 #[command(author, version)]
 pub struct Reinstall {
     /// Name of Packages to reinstall
+    ///
+    /// With --all-projects, packages not installed in the project are ignored.
     #[arg()]
     names: Vec<String>,
 
@@ -640,6 +1656,8 @@ pub struct Reinstall {
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
     #[command(flatten)]
+    projects: batch::ProjectsArgs,
+    #[command(flatten)]
     env_args: EnvArgs,
 
     /// skip confirm
@@ -649,12 +1667,16 @@ pub struct Reinstall {
 
 impl Reinstall {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
-        let client = crate::create_client(self.env_args.offline);
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        if self.projects.all_projects {
+            return self.run_all_projects().await;
+        }
 
+        let io = self.env_args.environment_io();
+        let client = crate::create_client(self.env_args.offline);
         let mut unity = load_unity(self.project).await;
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
 
         let names = self.names.iter().map(String::as_ref).collect::<Vec<_>>();
 
@@ -679,18 +1701,49 @@ impl Reinstall {
 
         update_project_last_modified(&io, unity.project_dir()).await;
     }
+
+    async fn run_all_projects(self) {
+        let io = self.env_args.environment_io();
+        let client = crate::create_client(self.env_args.offline);
+        let projects = self.projects.load_projects(&io).await;
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
+        self.projects
+            .apply_changes(projects, &io, &installer, self.yes, async |unity| {
+                let names = (self.names.iter())
+                    .map(String::as_ref)
+                    .filter(|name| unity.get_locked(name).is_some())
+                    .collect::<Vec<_>>();
+
+                unity
+                    .reinstall_request(&collection, &names)
+                    .await
+                    .map_err(|err| format!("collecting packages to be reinstalled: {err}"))
+            })
+            .await;
+    }
 }
 
 /// Update local repository cache
 #[derive(Parser)]
 #[command(author, version)]
-pub struct Update {}
+pub struct Update {
+    /// use the specified folder as the environment folder instead of the default one.
+    #[arg(long, value_name = "PATH")]
+    env_dir: Option<Box<Path>>,
+}
 
 impl Update {
     pub async fn run(self) {
         let client = crate::create_client(false);
-        let io = DefaultEnvironmentIo::new_default();
-        load_collection(&io, client.as_ref(), false).await;
+        let env_args = EnvArgs {
+            refresh: true,
+            env_dir: self.env_dir,
+            ..Default::default()
+        };
+        let io = env_args.environment_io();
+        load_collection(&io, client.as_ref(), &env_args).await;
     }
 }
 
@@ -701,51 +1754,125 @@ pub struct Outdated {
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
+    #[command(flatten)]
+    projects: batch::ProjectsArgs,
     /// Include prerelease
     #[arg(long = "prerelease")]
     prerelease: bool,
 
-    /// With this option, output is printed in json format
-    #[arg(long = "json-format")]
-    json_format: Option<NonZeroU32>,
-
     #[command(flatten)]
     env_args: EnvArgs,
 }
 
-impl Outdated {
-    pub async fn run(self) {
-        let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let unity = load_unity(self.project).await;
+#[derive(Serialize)]
+struct OutdatedInfo<'a> {
+    package_name: &'a str,
+    installed_version: &'a Version,
+    newer_version: &'a Version,
+}
 
-        let mut outdated_packages = HashMap::new();
+/// The package shown in json output of search and list commands
+#[derive(Serialize)]
+struct PackageSummary<'a> {
+    name: &'a str,
+    display_name: Option<&'a str>,
+    version: &'a Version,
+    description: Option<&'a str>,
+}
 
-        let selector = VersionSelector::latest_for(unity.unity_version(), self.prerelease);
+impl<'a> PackageSummary<'a> {
+    fn new(package: &'a PackageManifest) -> Self {
+        Self {
+            name: package.name(),
+            display_name: package.display_name(),
+            version: package.version(),
+            description: package.description(),
+        }
+    }
+}
 
-        for locked in unity.locked_packages() {
-            match collection.find_package_by_name(locked.name(), selector) {
-                None => log::error!("latest version for package {} not found.", locked.name()),
-                // if found version is newer: add to outdated
-                Some(pkg) if locked.version() < pkg.version() => {
-                    outdated_packages.insert(pkg.name(), (pkg, locked.version()));
-                }
-                Some(_) => (),
+/// Collects the locked packages which have newer versions compatible with other packages.
+///
+/// The key is the name of the package, and the value is the newer package and the installed version.
+fn collect_outdated<'env>(
+    unity: &UnityProject,
+    collection: &'env PackageCollection,
+    prerelease: bool,
+) -> HashMap<&'env str, (PackageInfo<'env>, Version)> {
+    let mut outdated_packages = HashMap::new();
+
+    let selector = VersionSelector::latest_for(unity.unity_version(), prerelease);
+
+    for locked in unity.locked_packages() {
+        match collection.find_package_by_name(locked.name(), selector) {
+            None => log::error!("latest version for package {} not found.", locked.name()),
+            // if found version is newer: add to outdated
+            Some(pkg) if locked.version() < pkg.version() => {
+                outdated_packages.insert(pkg.name(), (pkg, locked.version().clone()));
             }
+            Some(_) => (),
         }
+    }
 
-        for locked in unity.all_packages() {
-            for (name, range) in locked.dependencies() {
-                if let Some((outdated, _)) = outdated_packages.get(name.as_ref()) {
-                    if !range.matches(outdated.version()) {
-                        outdated_packages.remove(name.as_ref());
-                    }
+    for locked in unity.all_packages() {
+        for (name, range) in locked.dependencies() {
+            if let Some((outdated, _)) = outdated_packages.get(name.as_ref()) {
+                if !range.matches(outdated.version()) {
+                    outdated_packages.remove(name.as_ref());
                 }
             }
         }
+    }
+
+    outdated_packages
+}
+
+fn outdated_info<'a>(
+    outdated_packages: &'a HashMap<&str, (PackageInfo, Version)>,
+) -> Vec<OutdatedInfo<'a>> {
+    outdated_packages
+        .iter()
+        .map(|(package_name, (found, installed))| OutdatedInfo {
+            package_name,
+            installed_version: installed,
+            newer_version: found.version(),
+        })
+        .collect()
+}
+
+/// Prints the packages affected by advisories in the format of outdated command.
+fn print_advisories(affected: &[audit::AffectedPackage], indent: &str) {
+    for package in affected {
+        for advisory in &package.advisories {
+            println!(
+                "{indent}{}: installed: {}, advisory ({}): {}",
+                package.name,
+                package.version,
+                advisory.severity(),
+                advisory.message()
+            );
+        }
+    }
+    if !affected.is_empty() {
+        println!("{indent}run `vrc-get audit` for details of advisories");
+    }
+}
+
+impl Outdated {
+    pub async fn run(self) {
+        if self.projects.all_projects {
+            return self.run_all_projects().await;
+        }
+
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let unity = load_unity(self.project).await;
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
 
-        match self.json_format.map(|x| x.get()).unwrap_or(0) {
+        let outdated_packages = collect_outdated(&unity, &collection, self.prerelease);
+
+        match json::format() {
             0 => {
                 for (name, (found, installed)) in &outdated_packages {
                     println!(
@@ -755,25 +1882,96 @@ impl Outdated {
                         &found.version()
                     );
                 }
+                print_advisories(&audit::collect_affected(&unity, &collection), "");
             }
-            1 => {
+            1 => json::print(&outdated_info(&outdated_packages)),
+            _ => {
                 #[derive(Serialize)]
-                struct OutdatedInfo<'a> {
-                    package_name: &'a str,
-                    installed_version: &'a Version,
-                    newer_version: &'a Version,
+                struct Report<'a> {
+                    outdated: Vec<OutdatedInfo<'a>>,
+                    advisories: Vec<audit::AffectedPackageInfo<'a>>,
                 }
-                let info = outdated_packages
-                    .into_iter()
-                    .map(|(package_name, (found, installed))| OutdatedInfo {
-                        package_name,
-                        installed_version: installed,
-                        newer_version: found.version(),
-                    })
-                    .collect::<Vec<_>>();
-                println!("{}", serde_json::to_string(&info).unwrap());
+
+                let affected = audit::collect_affected(&unity, &collection);
+                json::print(&Report {
+                    outdated: outdated_info(&outdated_packages),
+                    advisories: affected
+                        .iter()
+                        .map(audit::AffectedPackageInfo::new)
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    async fn run_all_projects(self) {
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+        let projects = self.projects.load_projects(&io).await;
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+
+        let results = self
+            .projects
+            .for_each_project(projects, async |unity| {
+                Ok((
+                    collect_outdated(&unity, &collection, self.prerelease),
+                    audit::collect_affected(&unity, &collection),
+                ))
+            })
+            .await;
+
+        if !json::enabled() {
+            for (project, result) in &results {
+                println!("{} ({}):", project.name, project.path.display());
+                match result {
+                    Err(err) => println!("  failed: {err}"),
+                    Ok((outdated_packages, affected))
+                        if outdated_packages.is_empty() && affected.is_empty() =>
+                    {
+                        println!("  no outdated packages")
+                    }
+                    Ok((outdated_packages, affected)) => {
+                        for (name, (found, installed)) in outdated_packages {
+                            println!(
+                                "  {}: installed: {}, found: {}",
+                                name,
+                                installed,
+                                &found.version()
+                            );
+                        }
+                        print_advisories(affected, "  ");
+                    }
+                }
+            }
+        } else {
+            #[derive(Serialize)]
+            struct ProjectOutdatedInfo<'a> {
+                project_name: &'a str,
+                project_path: &'a Path,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                outdated: Option<Vec<OutdatedInfo<'a>>>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                advisories: Option<Vec<audit::AffectedPackageInfo<'a>>>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                error: Option<&'a str>,
             }
-            v => exit_with!("unsupported json version: {v}"),
+            let info = results
+                .iter()
+                .map(|(project, result)| ProjectOutdatedInfo {
+                    project_name: &project.name,
+                    project_path: &project.path,
+                    outdated: (result.as_ref().ok())
+                        .map(|(outdated_packages, _)| outdated_info(outdated_packages)),
+                    advisories: (result.as_ref().ok()).map(|(_, affected)| {
+                        affected
+                            .iter()
+                            .map(audit::AffectedPackageInfo::new)
+                            .collect()
+                    }),
+                    error: result.as_ref().err().map(String::as_str),
+                })
+                .collect::<Vec<_>>();
+            json::print(&info);
         }
     }
 }
@@ -799,6 +1997,8 @@ pub struct Upgrade {
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
     #[command(flatten)]
+    projects: batch::ProjectsArgs,
+    #[command(flatten)]
     env_args: EnvArgs,
 
     /// skip confirm
@@ -806,29 +2006,63 @@ pub struct Upgrade {
     yes: bool,
 }
 
+/// Finds the latest package to upgrade to.
+///
+/// If the range in dependencies is not a single version, the package is upgraded within the range.
+fn find_upgrade<'env>(
+    collection: &'env PackageCollection,
+    unity: &UnityProject,
+    name: &str,
+    prerelease: bool,
+) -> Option<PackageInfo<'env>> {
+    let range = unity
+        .get_dependency(name)
+        .filter(|range| range.as_single_version().is_none())
+        .map(|range| range.as_range());
+    let version_selector = match &range {
+        None => VersionSelector::latest_for(unity.unity_version(), prerelease),
+        Some(range) => VersionSelector::range_for(
+            unity.unity_version(),
+            range,
+            prerelease_acceptance(prerelease),
+        ),
+    };
+    collection.find_package_by_name(name, version_selector)
+}
+
 impl Upgrade {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        if self.projects.all_projects {
+            return self.run_all_projects().await;
+        }
+
+        let io = self.env_args.environment_io();
         let client = crate::create_client(self.env_args.offline);
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
         let mut unity = load_unity(self.project).await;
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
+        let get_latest = |name: &str| {
+            find_upgrade(&collection, &unity, name, self.prerelease)
+                .unwrap_or_else(|| exit_with!(PackageNotFound, "no matching package not found"))
+        };
 
         let updates = if let Some(name) = &self.name {
-            let version_selector = match self.version {
-                None => VersionSelector::latest_for(unity.unity_version(), self.prerelease),
-                Some(ref version) => VersionSelector::specific_version(version),
+            let package = match self.version {
+                None => get_latest(name),
+                Some(ref version) => get_package(
+                    &collection,
+                    name,
+                    VersionSelector::specific_version(version),
+                ),
             };
-            let package = get_package(&collection, name, version_selector);
 
             vec![package]
         } else {
-            let version_selector =
-                VersionSelector::latest_for(unity.unity_version(), self.prerelease);
-
             unity
                 .locked_packages()
-                .map(|locked| get_package(&collection, locked.name(), version_selector))
+                .map(|locked| get_latest(locked.name()))
                 .collect()
         };
 
@@ -866,11 +2100,67 @@ impl Upgrade {
             .exit_context("upgrading packages");
 
         for (name, version) in updates {
-            println!("upgraded {} to {}", name, version);
+            message!("upgraded {} to {}", name, version);
         }
 
         update_project_last_modified(&io, unity.project_dir()).await;
     }
+
+    async fn run_all_projects(self) {
+        let io = self.env_args.environment_io();
+        let client = crate::create_client(self.env_args.offline);
+        let projects = self.projects.load_projects(&io).await;
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
+
+        self.projects
+            .apply_changes(projects, &io, &installer, self.yes, async |unity| {
+                let updates = if let Some(name) = &self.name {
+                    // projects without the package are not upgraded
+                    if unity.get_locked(name).is_none() {
+                        vec![]
+                    } else {
+                        let package = match &self.version {
+                            None => find_upgrade(&collection, unity, name, self.prerelease),
+                            Some(version) => collection.find_package_by_name(
+                                name,
+                                VersionSelector::specific_version(version),
+                            ),
+                        };
+                        vec![
+                            package
+                                .ok_or_else(|| format!("no matching version of {name} found"))?,
+                        ]
+                    }
+                } else {
+                    unity
+                        .locked_packages()
+                        .filter_map(|locked| {
+                            let found =
+                                find_upgrade(&collection, unity, locked.name(), self.prerelease);
+                            if found.is_none() {
+                                log::error!(
+                                    "latest version for package {} not found.",
+                                    locked.name()
+                                );
+                            }
+                            found
+                        })
+                        .collect()
+                };
+
+                unity
+                    .add_package_request(
+                        &collection,
+                        &updates,
+                        AddPackageOperation::UpgradeLocked,
+                        self.prerelease,
+                    )
+                    .await
+                    .map_err(|err| format!("collecting packages to be upgraded: {err}"))
+            })
+            .await;
+    }
 }
 
 /// Downgrade the specified package specified version.
@@ -904,10 +2194,11 @@ pub struct Downgrade {
 impl Downgrade {
     pub async fn run(self) {
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-        let installer = PackageInstaller::new(&io, client.as_ref());
+        let io = self.env_args.environment_io();
         let mut unity = load_unity(self.project).await;
+        let collection =
+            load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+        let installer = PackageInstaller::new(&io, client.as_ref());
 
         let updates = [get_package(
             &collection,
@@ -943,13 +2234,42 @@ impl Downgrade {
             .exit_context("upgrading packages");
 
         for (name, version) in downgrades {
-            println!("downgraded {} to {}", name, version);
+            message!("downgraded {} to {}", name, version);
         }
 
         update_project_last_modified(&io, unity.project_dir()).await;
     }
 }
 
+/// Finds the latest packages which include all queries in either name, displayName, or description.
+fn search_packages<'env>(
+    collection: &'env PackageCollection,
+    queries: &[String],
+) -> Vec<PackageInfo<'env>> {
+    let queries = (queries.iter())
+        .map(|x| x.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+
+    fn search_targets(pkg: &PackageManifest) -> Vec<String> {
+        let mut sources = Vec::with_capacity(3);
+
+        sources.push(pkg.name().to_ascii_lowercase());
+        sources.extend(pkg.display_name().map(|x| x.to_ascii_lowercase()));
+        sources.extend(pkg.description().map(|x| x.to_ascii_lowercase()));
+
+        sources
+    }
+
+    collection.find_whole_all_packages(VersionSelector::latest_for(None, true), |pkg| {
+        // filtering
+        let search_targets = search_targets(pkg);
+
+        queries
+            .iter()
+            .all(|query| search_targets.iter().any(|x| x.contains(query)))
+    })
+}
+
 /// Search package by the query
 ///
 /// Search for packages that includes query in either name, displayName, or description.
@@ -967,35 +2287,17 @@ pub struct Search {
 impl Search {
     pub async fn run(self) {
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-
-        let mut queries = self.queries;
-        for query in &mut queries {
-            query.make_ascii_lowercase();
-        }
-
-        fn search_targets(pkg: &PackageManifest) -> Vec<String> {
-            let mut sources = Vec::with_capacity(3);
-
-            sources.push(pkg.name().to_ascii_lowercase());
-            sources.extend(pkg.display_name().map(|x| x.to_ascii_lowercase()));
-            sources.extend(pkg.description().map(|x| x.to_ascii_lowercase()));
+        let io = self.env_args.environment_io();
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
 
-            sources
-        }
-
-        let found_packages =
-            collection.find_whole_all_packages(VersionSelector::latest_for(None, true), |pkg| {
-                // filtering
-                let search_targets = search_targets(pkg);
-
-                queries
-                    .iter()
-                    .all(|query| search_targets.iter().any(|x| x.contains(query)))
-            });
+        let found_packages = search_packages(&collection, &self.queries);
 
-        if found_packages.is_empty() {
+        if json::enabled() {
+            let info = (found_packages.iter())
+                .map(|x| PackageSummary::new(x.package_json()))
+                .collect::<Vec<_>>();
+            json::print(&info);
+        } else if found_packages.is_empty() {
             println!("No matching package found!")
         } else {
             for x in found_packages {
@@ -1025,9 +2327,12 @@ pub enum Repo {
     Packages(RepoPackages),
     Import(RepoImport),
     Export(RepoExport),
+    Priority(RepoPriority),
+    Pin(RepoPin),
+    Unpin(RepoUnpin),
 }
 
-multi_command!(Repo is List, Add, Remove, Cleanup, Packages, Import, Export);
+multi_command!(Repo is List, Add, Remove, Cleanup, Packages, Import, Export, Priority, Pin, Unpin);
 
 /// List all repositories
 #[derive(Parser)]
@@ -1039,9 +2344,29 @@ pub struct RepoList {
 
 impl RepoList {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let settings = Settings::load(&io).await.exit_context("loading settings");
 
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct RepositoryInfo<'a> {
+                id: Option<&'a str>,
+                name: Option<&'a str>,
+                url: Option<&'a str>,
+                local_path: &'a Path,
+            }
+
+            let info = (settings.get_user_repos().iter())
+                .map(|repo| RepositoryInfo {
+                    id: repo.id(),
+                    name: repo.name(),
+                    url: repo.url().map(Url::as_str),
+                    local_path: repo.local_path(),
+                })
+                .collect::<Vec<_>>();
+            return json::print(&info);
+        }
+
         for repo in settings.get_user_repos() {
             println!(
                 "{}: {} (from {})",
@@ -1128,10 +2453,15 @@ impl StdError for HeaderPairErr {
 impl RepoAdd {
     pub async fn run(self) {
         let http = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
 
-        if let Ok(url) = Url::parse(&self.path_or_url) {
+        // single letter schemes are drive letters on windows
+        let url = Url::parse(&self.path_or_url)
+            .ok()
+            .filter(|url| url.scheme().len() > 1);
+
+        if let Some(url) = url {
             let mut headers = IndexMap::<Box<str>, Box<str>>::new();
             for HeaderPair(name, value) in self.header {
                 headers.insert(name.as_str().into(), value.to_str().unwrap().into());
@@ -1142,18 +2472,166 @@ impl RepoAdd {
                 self.name.as_deref(),
                 headers,
                 &io,
-                &http.unwrap_or_else(|| exit_with!("offline mode")),
+                http.as_ref(),
             )
             .await
             .exit_context("adding repository")
         } else {
             let normalized = absolute_path(&self.path_or_url);
             if !normalized.exists() {
-                exit_with!("path not found: {}", normalized.display());
+                exit_with!(PathNotFound, "path not found: {}", normalized.display());
+            }
+            if normalized.is_dir() {
+                // directories with package zip files are added as file: repositories
+                let url = Url::from_directory_path(&normalized).unwrap_or_else(|()| {
+                    exit_with!(InvalidArgument, "bad path: {}", normalized.display())
+                });
+                add_remote_repo(
+                    &mut settings,
+                    url,
+                    self.name.as_deref(),
+                    IndexMap::new(),
+                    &io,
+                    http.as_ref(),
+                )
+                .await
+                .exit_context("adding repository")
+            } else if !settings.add_local_repo(&normalized, self.name.as_deref()) {
+                exit_with!(RepositoryAlreadyExists, "repository already exists");
+            }
+        }
+
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}
+
+/// Show or set the priority of repositories
+///
+/// When the same package is provided by multiple repositories,
+/// only the repositories with the highest priority are used.
+/// Repositories not listed have the lowest priority.
+/// The official and curated repositories are `com.vrchat.repos.official` and `com.vrchat.repos.curated`.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct RepoPriority {
+    /// ids of repositories, highest priority first. if not specified, shows current priority
+    #[arg()]
+    ids: Vec<String>,
+
+    /// Clear the priority
+    #[arg(long, conflicts_with = "ids")]
+    clear: bool,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl RepoPriority {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        if !self.clear && self.ids.is_empty() {
+            if json::enabled() {
+                return json::print(&settings.repository_priority());
+            }
+            if settings.repository_priority().is_empty() {
+                println!("No repository priority is set");
+            }
+            for (index, id) in settings.repository_priority().iter().enumerate() {
+                println!("{}: {id}", index + 1);
+            }
+            return;
+        }
+
+        let known_ids = settings
+            .get_user_repos()
+            .iter()
+            .filter_map(|x| x.id().or(x.url().map(Url::as_str)))
+            .chain(["com.vrchat.repos.official", "com.vrchat.repos.curated"])
+            .collect::<HashSet<_>>();
+        for id in &self.ids {
+            if !known_ids.contains(id.as_str()) {
+                warn!("repository {id} is not added");
+            }
+        }
+
+        settings.set_repository_priority(self.ids.into_iter().map(Into::into).collect());
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}
+
+/// Pin packages to the repository, or show pinned packages
+///
+/// Pinned packages are only taken from the repository.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct RepoPin {
+    /// Name of package. `*` at the end matches any suffix like `com.vrchat.*`
+    #[arg(requires = "repository")]
+    pattern: Option<String>,
+    /// id of repository to pin the packages to
+    #[arg()]
+    repository: Option<String>,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl RepoPin {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        let (Some(pattern), Some(repository)) = (self.pattern, self.repository) else {
+            if json::enabled() {
+                #[derive(Serialize)]
+                struct PinInfo<'a> {
+                    pattern: &'a str,
+                    repository: &'a str,
+                }
+
+                let info = (settings.package_pins().iter())
+                    .map(|(pattern, repository)| PinInfo {
+                        pattern,
+                        repository,
+                    })
+                    .collect::<Vec<_>>();
+                return json::print(&info);
+            }
+            if settings.package_pins().is_empty() {
+                println!("No packages are pinned");
             }
-            if !settings.add_local_repo(&normalized, self.name.as_deref()) {
-                exit_with!("repository already exists");
+            for (pattern, repository) in settings.package_pins() {
+                println!("{pattern}: {repository}");
             }
+            return;
+        };
+
+        settings.set_package_pin(&pattern, &repository);
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}
+
+/// Unpin packages pinned with `vrc-get repo pin`
+#[derive(Parser)]
+#[command(author, version)]
+pub struct RepoUnpin {
+    /// The pattern specified with `vrc-get repo pin`
+    #[arg()]
+    pattern: String,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl RepoUnpin {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        if !settings.remove_package_pin(&self.pattern) {
+            exit_with!(PackageNotPinned, "{} is not pinned", self.pattern);
         }
 
         settings.save(&io).await.exit_context("saving settings");
@@ -1236,7 +2714,7 @@ impl RepoSearcher {
 
 impl RepoRemove {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
 
         // we're using OsStr for paths.
@@ -1252,7 +2730,7 @@ impl RepoRemove {
         )
         .await;
 
-        println!("removed {} repositories with {}", removed.len(), searcher);
+        message!("removed {} repositories with {}", removed.len(), searcher);
 
         settings.save(&io).await.exit_context("saving settings");
     }
@@ -1271,7 +2749,7 @@ pub struct RepoCleanup {
 
 impl RepoCleanup {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let settings = Settings::load(&io).await.exit_context("loading settings");
         cleanup_repos_folder(&settings, &io)
             .await
@@ -1292,6 +2770,10 @@ pub struct RepoPackages {
 impl RepoPackages {
     pub async fn run(self) {
         fn print_repo(packages: &RemoteRepository) {
+            if json::enabled() {
+                return print_repo_json(packages);
+            }
+
             for versions in packages.get_packages() {
                 if let Some(pkg) =
                     versions.get_latest_may_yanked(VersionSelector::latest_for(None, true))
@@ -1318,9 +2800,52 @@ impl RepoPackages {
             }
         }
 
-        if let Ok(url) = Url::parse(&self.name_or_url) {
+        fn print_repo_json(packages: &RemoteRepository) {
+            #[derive(Serialize)]
+            struct RepositoryPackageInfo<'a> {
+                #[serde(flatten)]
+                latest: PackageSummary<'a>,
+                versions: Vec<PackageVersionInfo<'a>>,
+            }
+
+            #[derive(Serialize)]
+            struct PackageVersionInfo<'a> {
+                version: &'a Version,
+                url: Option<&'a str>,
+                is_yanked: bool,
+            }
+
+            let info = (packages.get_packages())
+                .filter_map(|versions| {
+                    let latest =
+                        versions.get_latest_may_yanked(VersionSelector::latest_for(None, true))?;
+                    let mut versions = versions.all_versions().collect::<Vec<_>>();
+                    versions.sort_by_key(|pkg| pkg.version());
+                    Some(RepositoryPackageInfo {
+                        latest: PackageSummary::new(latest),
+                        versions: (versions.into_iter())
+                            .map(|pkg| PackageVersionInfo {
+                                version: pkg.version(),
+                                url: pkg.url().map(Url::as_str),
+                                is_yanked: pkg.is_yanked(),
+                            })
+                            .collect(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            json::print(&info);
+        }
+
+        if let Some(url) = Url::parse(&self.name_or_url).ok().filter(is_file_url) {
+            let io = self.env_args.environment_io();
+            let (repo, _) = RemoteRepository::read_file(&io, &url)
+                .await
+                .exit_context("reading repository");
+
+            print_repo(&repo);
+        } else if let Ok(url) = Url::parse(&self.name_or_url) {
             if self.env_args.offline {
-                exit_with!("remote repository specified but offline mode.");
+                exit_with!(Offline, "remote repository specified but offline mode.");
             }
             let client = crate::create_client(self.env_args.offline).unwrap();
             let (repo, _) = RemoteRepository::download(&client, &url, &IndexMap::new())
@@ -1330,8 +2855,8 @@ impl RepoPackages {
             print_repo(&repo);
         } else {
             let client = crate::create_client(self.env_args.offline);
-            let io = DefaultEnvironmentIo::new_default();
-            let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
+            let io = self.env_args.environment_io();
+            let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
 
             let some_name = Some(self.name_or_url.as_str());
             let mut found = false;
@@ -1344,7 +2869,11 @@ impl RepoPackages {
             }
 
             if !found {
-                exit_with!("no repository named {} found!", self.name_or_url);
+                exit_with!(
+                    RepositoryNotFound,
+                    "no repository named {} found!",
+                    self.name_or_url
+                );
             }
         }
     }
@@ -1367,7 +2896,7 @@ pub struct RepoImport {
 impl RepoImport {
     pub async fn run(self) {
         let http = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
         let repositories_file = read_to_string(self.repositories_file)
             .await
@@ -1375,23 +2904,23 @@ impl RepoImport {
 
         let result = RepositoriesFile::parse(&repositories_file);
 
-        println!("You're importing the following repositories:");
+        message!("You're importing the following repositories:");
         for repository in result.parsed().repositories() {
             if repository.headers().is_empty() {
-                println!("- {}", repository.url());
+                message!("- {}", repository.url());
             } else {
-                println!("- {} (with headers)", repository.url());
+                message!("- {} (with headers)", repository.url());
             }
         }
-        println!("The following lines are invalid and will be ignored:");
+        message!("The following lines are invalid and will be ignored:");
         for line in result.unparseable_lines() {
-            println!("- {}", line);
+            message!("- {}", line);
         }
 
         if self.yes {
-            println!("--yes is set. skipping confirm");
-        } else if !confirm_prompt("Do you want to install those repositories?") {
-            exit(1);
+            message!("--yes is set. skipping confirm");
+        } else {
+            confirm_or_exit("Do you want to install those repositories?");
         }
 
         for repository in result.parsed().repositories() {
@@ -1401,7 +2930,7 @@ impl RepoImport {
                 None,
                 repository.headers().clone(),
                 &io,
-                http.as_ref().unwrap_or_else(|| exit_with!("offline mode")),
+                http.as_ref(),
             )
             .await
             {
@@ -1413,10 +2942,8 @@ impl RepoImport {
                     );
                 }
                 Err(err) => {
-                    exit_with!(
-                        "error adding repository {url}: {err}",
-                        url = repository.url()
-                    );
+                    let context = format!("adding repository {}", repository.url());
+                    json::exit_with_error(json::ErrorCode::OperationFailed, Some(&context), err);
                 }
             }
         }
@@ -1435,7 +2962,7 @@ pub struct RepoExport {
 
 impl RepoExport {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let settings = Settings::load(&io).await.exit_context("loading settings");
         print!("{}", settings.export_repositories());
     }
@@ -1455,14 +2982,34 @@ multi_command!(UserPackage is List, Add, Remove);
 /// List all user packages
 #[derive(Parser)]
 #[command(author, version)]
-pub struct UserPackageList {}
+pub struct UserPackageList {
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
 
 impl UserPackageList {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let settings = Settings::load(&io).await.exit_context("loading settings");
         let packages = UserPackageCollection::load(&settings, &io).await;
 
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct UserPackageInfo<'a> {
+                #[serde(flatten)]
+                package: PackageSummary<'a>,
+                path: &'a Path,
+            }
+
+            let info = (packages.packages())
+                .map(|(path, package)| UserPackageInfo {
+                    package: PackageSummary::new(package),
+                    path,
+                })
+                .collect::<Vec<_>>();
+            return json::print(&info);
+        }
+
         for (path, package) in packages.packages() {
             println!(
                 "{}: {} version {} at {}",
@@ -1482,20 +3029,27 @@ pub struct UserPackageAdd {
     /// Path to package
     #[arg()]
     path: Box<Path>,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
 }
 
 impl UserPackageAdd {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
 
         let path = absolute_path(&self.path);
         match settings.add_user_package(&path, &io).await {
             AddUserPackageResult::BadPackage => {
-                exit_with!("bad package: {}", self.path.display())
+                exit_with!(BadPackage, "bad package: {}", self.path.display())
             }
             AddUserPackageResult::AlreadyAdded => {
-                exit_with!("package already added: {}", self.path.display())
+                exit_with!(
+                    UserPackageAlreadyExists,
+                    "package already added: {}",
+                    self.path.display()
+                )
             }
             AddUserPackageResult::Success => {}
             AddUserPackageResult::NonAbsolute => unreachable!("absolute path"),
@@ -1512,11 +3066,14 @@ pub struct UserPackageRemove {
     /// Path to package
     #[arg()]
     path: Box<Path>,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
 }
 
 impl UserPackageRemove {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
 
         let path = absolute_path(&self.path);
@@ -1545,7 +3102,7 @@ pub struct CacheClear {
 
 impl CacheClear {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         clear_package_cache(&io)
             .await
             .exit_context("clearing package cache");
@@ -1563,7 +3120,7 @@ impl Completion {
         use std::env::args;
 
         let Some(shell) = self.shell.or_else(clap_complete::Shell::from_env) else {
-            exit_with!("shell not specified")
+            exit_with!(InvalidArgument, "shell not specified")
         };
         let mut bin_name = args().next().expect("bin name");
         if let Some(slash) = bin_name.rfind(['/', '\\']) {
@@ -1574,12 +3131,7 @@ impl Completion {
             }
         }
 
-        clap_complete::generate(
-            shell,
-            &mut Command::command(),
-            bin_name,
-            &mut std::io::stdout(),
-        );
+        clap_complete::generate(shell, &mut Cli::command(), bin_name, &mut std::io::stdout());
     }
 }
 
@@ -1594,10 +3146,12 @@ pub struct FakeVcc {
 #[cfg(not(feature = "experimental-vcc"))]
 impl FakeVcc {
     pub async fn run(self) {
-        eprintln!("vrc-get vcc is not enabled in this build of vrc-get.");
-        eprintln!("experimental features are disabled for prebuilt binaries.");
-        eprintln!("If you want to use vrc-get vcc command, please install vrc-get with ");
-        eprintln!("cargo install --features experimental-vcc vrc-get");
-        exit(1);
+        exit_with!(
+            FeatureDisabled,
+            "vrc-get vcc is not enabled in this build of vrc-get.\n\
+            experimental features are disabled for prebuilt binaries.\n\
+            If you want to use vrc-get vcc command, please install vrc-get with \n\
+            cargo install --features experimental-vcc vrc-get"
+        );
     }
 }
@@ -0,0 +1,223 @@
+use super::batch::{BatchProject, ProjectsArgs};
+use super::{
+    EnvArgs, UnityProject, json, load_collection, load_collection_for_project, load_unity,
+};
+use clap::Parser;
+use reqwest::Url;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::path::Path;
+use std::process::exit;
+use vrc_get_vpm::environment::PackageCollection;
+use vrc_get_vpm::repository::{Advisory, AdvisorySeverity};
+use vrc_get_vpm::version::{Version, VersionRange};
+
+/// A package installed in the project affected by advisories
+pub(super) struct AffectedPackage<'env> {
+    pub name: Box<str>,
+    pub version: Version,
+    /// advisories sorted by severity, most severe first
+    pub advisories: Vec<&'env Advisory>,
+}
+
+/// Collects the locked or installed packages affected by the advisories in the repositories.
+pub(super) fn collect_affected<'env>(
+    unity: &UnityProject,
+    collection: &'env PackageCollection,
+) -> Vec<AffectedPackage<'env>> {
+    let mut affected = unity
+        .all_packages()
+        .filter_map(|package| {
+            let mut advisories = collection
+                .find_advisories(package.name(), package.version())
+                .collect::<Vec<_>>();
+            if advisories.is_empty() {
+                return None;
+            }
+            advisories.sort_by_key(|x| Reverse(x.severity()));
+            Some(AffectedPackage {
+                name: package.name().into(),
+                version: package.version().clone(),
+                advisories,
+            })
+        })
+        .collect::<Vec<_>>();
+    affected.sort_by(|a, b| a.name.cmp(&b.name));
+    affected
+}
+
+/// Show packages in the project affected by advisories published by repositories
+///
+/// Exits with non-zero status if any affected package is found.
+/// With --json-format, this will emit machine-readable information with json
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Audit {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    projects: ProjectsArgs,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl Audit {
+    pub async fn run(self) {
+        let client = crate::create_client(self.env_args.offline);
+        let io = self.env_args.environment_io();
+
+        let found = if self.projects.all_projects {
+            let projects = self.projects.load_projects(&io).await;
+            let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+
+            let results = self
+                .projects
+                .for_each_project(projects, async |unity| {
+                    Ok(collect_affected(&unity, &collection))
+                })
+                .await;
+
+            match json::format() {
+                0 => Self::human_readable_projects(&results),
+                _ => Self::version1_projects(&results),
+            }
+
+            results
+                .iter()
+                .any(|(_, result)| result.as_ref().map_or(true, |x| !x.is_empty()))
+        } else {
+            let unity = load_unity(self.project).await;
+            let collection =
+                load_collection_for_project(&io, client.as_ref(), &self.env_args, &unity).await;
+
+            let affected = collect_affected(&unity, &collection);
+
+            match json::format() {
+                0 => Self::human_readable(&affected, ""),
+                _ => Self::version1(&affected),
+            }
+
+            !affected.is_empty()
+        };
+
+        if found {
+            exit(1);
+        }
+    }
+
+    fn human_readable(affected: &[AffectedPackage], indent: &str) {
+        if affected.is_empty() {
+            println!("{indent}No affected packages found");
+            return;
+        }
+
+        for package in affected {
+            println!("{indent}{} version {}", package.name, package.version);
+            for advisory in &package.advisories {
+                match advisory.id() {
+                    Some(id) => println!(
+                        "{indent}  {}: {id}: {}",
+                        advisory.severity(),
+                        advisory.message()
+                    ),
+                    None => println!("{indent}  {}: {}", advisory.severity(), advisory.message()),
+                }
+                println!("{indent}    affected versions: {}", advisory.versions());
+                if let Some(url) = advisory.url() {
+                    println!("{indent}    details: {url}");
+                }
+            }
+        }
+    }
+
+    fn human_readable_projects(results: &[(BatchProject, Result<Vec<AffectedPackage>, String>)]) {
+        for (project, result) in results {
+            println!("{} ({}):", project.name, project.path.display());
+            match result {
+                Ok(affected) => Self::human_readable(affected, "  "),
+                Err(err) => println!("  failed: {err}"),
+            }
+        }
+    }
+
+    fn version1(affected: &[AffectedPackage]) {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            packages: Vec<AffectedPackageInfo<'a>>,
+        }
+
+        let report = Report {
+            packages: affected.iter().map(AffectedPackageInfo::new).collect(),
+        };
+
+        println!("{}", serde_json::to_string(&report).unwrap());
+    }
+
+    fn version1_projects(results: &[(BatchProject, Result<Vec<AffectedPackage>, String>)]) {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            projects: Vec<ProjectInfo<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct ProjectInfo<'a> {
+            project_name: &'a str,
+            project_path: &'a Path,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            packages: Option<Vec<AffectedPackageInfo<'a>>>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<&'a str>,
+        }
+
+        let report = Report {
+            projects: results
+                .iter()
+                .map(|(project, result)| ProjectInfo {
+                    project_name: &project.name,
+                    project_path: &project.path,
+                    packages: (result.as_ref().ok())
+                        .map(|affected| affected.iter().map(AffectedPackageInfo::new).collect()),
+                    error: result.as_ref().err().map(String::as_str),
+                })
+                .collect(),
+        };
+
+        println!("{}", serde_json::to_string(&report).unwrap());
+    }
+}
+
+#[derive(Serialize)]
+pub(super) struct AffectedPackageInfo<'a> {
+    package_name: &'a str,
+    version: &'a Version,
+    advisories: Vec<AdvisoryInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct AdvisoryInfo<'a> {
+    id: Option<&'a str>,
+    severity: AdvisorySeverity,
+    message: &'a str,
+    versions: &'a VersionRange,
+    url: Option<&'a Url>,
+}
+
+impl<'a> AffectedPackageInfo<'a> {
+    pub(super) fn new(package: &'a AffectedPackage) -> Self {
+        Self {
+            package_name: &package.name,
+            version: &package.version,
+            advisories: (package.advisories.iter())
+                .map(|advisory| AdvisoryInfo {
+                    id: advisory.id(),
+                    severity: advisory.severity(),
+                    message: advisory.message(),
+                    versions: advisory.versions(),
+                    url: advisory.url(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use crate::commands::json::ChangesInfo;
+use crate::commands::{
+    UnityProject, json, print_changes, prompt_install, update_project_last_modified,
+};
+use clap::{Args, ValueEnum};
+use futures::{StreamExt, stream};
+use serde::Serialize;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::exit;
+use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use vrc_get_vpm::unity_project::PendingProjectChanges;
+
+/// Arguments to run the command on the projects registered to vrc-get instead of a single project.
+///
+/// Project-local repositories and packages are not used for batch operations.
+#[derive(Args)]
+pub(super) struct ProjectsArgs {
+    /// Run the command on all projects registered to vrc-get (or VCC) instead of a single project
+    #[arg(long, conflicts_with = "project")]
+    pub all_projects: bool,
+    /// With --all-projects, only process favorite projects
+    #[arg(long, requires = "all_projects")]
+    favorite: bool,
+    /// With --all-projects, only process projects of the specified type. can be specified multiple times
+    #[arg(long, value_enum, requires = "all_projects")]
+    project_type: Vec<ProjectTypeFilter>,
+    /// With --all-projects, only process projects whose name contains the text (case insensitive)
+    #[arg(long, value_name = "TEXT", requires = "all_projects")]
+    name_contains: Option<String>,
+    /// The number of projects processed at once with --all-projects
+    #[arg(long, default_value = "4")]
+    jobs: NonZeroUsize,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum ProjectTypeFilter {
+    /// Avatar projects
+    Avatars,
+    /// World projects
+    Worlds,
+    /// Projects neither for avatars nor worlds
+    Other,
+}
+
+pub(super) struct BatchProject {
+    pub name: Box<str>,
+    pub path: Box<Path>,
+}
+
+impl Default for ProjectsArgs {
+    fn default() -> Self {
+        Self {
+            all_projects: false,
+            favorite: false,
+            project_type: vec![],
+            name_contains: None,
+            jobs: NonZeroUsize::new(4).unwrap(),
+        }
+    }
+}
+
+enum BatchOutcome {
+    UpToDate,
+    Applied(usize),
+    Failed(String),
+}
+
+impl ProjectsArgs {
+    /// Loads the projects matching the filters from the project database.
+    pub async fn load_projects(&self, io: &DefaultEnvironmentIo) -> Vec<BatchProject> {
+        let projects = self.load_projects_impl(io).await;
+        if projects.is_empty() {
+            exit_with!(NoProjectsMatched, "no projects matched");
+        }
+        projects
+    }
+
+    #[cfg(feature = "experimental-vcc")]
+    async fn load_projects_impl(&self, io: &DefaultEnvironmentIo) -> Vec<BatchProject> {
+        use crate::commands::ResultExt;
+        use vrc_get_vpm::ProjectType;
+        use vrc_get_vpm::environment::VccDatabaseConnection;
+
+        let connection = VccDatabaseConnection::connect(io)
+            .await
+            .exit_context("connecting to database");
+
+        let projects = connection
+            .get_projects()
+            .await
+            .exit_context("getting projects");
+
+        connection
+            .dispose()
+            .await
+            .exit_context("disposing database");
+
+        let name_contains = self.name_contains.as_ref().map(|x| x.to_lowercase());
+
+        projects
+            .iter()
+            .filter(|project| !self.favorite || project.favorite())
+            .filter(|project| {
+                if self.project_type.is_empty() {
+                    return true;
+                }
+                let project_type = match project.project_type() {
+                    ProjectType::LegacyAvatars | ProjectType::UpmAvatars | ProjectType::Avatars => {
+                        ProjectTypeFilter::Avatars
+                    }
+                    ProjectType::LegacyWorlds | ProjectType::UpmWorlds | ProjectType::Worlds => {
+                        ProjectTypeFilter::Worlds
+                    }
+                    _ => ProjectTypeFilter::Other,
+                };
+                self.project_type.contains(&project_type)
+            })
+            .filter_map(|project| {
+                let path = project.path()?;
+                let name = project.name()?;
+                let name_matches = (name_contains.as_ref())
+                    .is_none_or(|name_contains| name.to_lowercase().contains(name_contains));
+                if !name_matches {
+                    return None;
+                }
+                Some(BatchProject {
+                    name: name.into(),
+                    path: Path::new(path).into(),
+                })
+            })
+            .filter(|project| project.path.is_dir())
+            .collect()
+    }
+
+    #[cfg(not(feature = "experimental-vcc"))]
+    async fn load_projects_impl(&self, _: &DefaultEnvironmentIo) -> Vec<BatchProject> {
+        exit_with!(
+            FeatureDisabled,
+            "--all-projects is not enabled in this build of vrc-get.\n\
+            experimental features are disabled for prebuilt binaries.\n\
+            If you want to use --all-projects, please install vrc-get with \n\
+            cargo install --features experimental-vcc vrc-get"
+        );
+    }
+
+    /// Loads each project and calls `f` with the loaded project, processing `--jobs` projects at once.
+    ///
+    /// The results are in the same order as `projects`.
+    pub async fn for_each_project<T>(
+        &self,
+        projects: Vec<BatchProject>,
+        f: impl AsyncFn(UnityProject) -> Result<T, String>,
+    ) -> Vec<(BatchProject, Result<T, String>)> {
+        let f = &f;
+        stream::iter(projects)
+            .map(|project| async move {
+                let result =
+                    match UnityProject::load(DefaultProjectIo::new(project.path.clone())).await {
+                        Ok(unity) => f(unity).await,
+                        Err(err) => Err(format!("loading unity project: {err}")),
+                    };
+                (project, result)
+            })
+            .buffered(self.jobs.get())
+            .collect()
+            .await
+    }
+
+    /// Collects the changes for each project, prompts once for all projects,
+    /// applies the changes, and prints the report.
+    pub async fn apply_changes<'env>(
+        &self,
+        projects: Vec<BatchProject>,
+        io: &DefaultEnvironmentIo,
+        installer: &PackageInstaller<'_, reqwest::Client, DefaultEnvironmentIo>,
+        yes: bool,
+        collect: impl AsyncFn(&UnityProject) -> Result<PendingProjectChanges<'env>, String>,
+    ) {
+        let collect = &collect;
+        let collected = self
+            .for_each_project(projects, async |unity| {
+                let changes = collect(&unity).await?;
+                Ok((unity, changes))
+            })
+            .await;
+
+        let mut has_changes = false;
+        // changes are consumed when applied so keep them as json for the report
+        let mut changes_json = Vec::new();
+        for (project, result) in &collected {
+            let changes = match result {
+                Err(err) => {
+                    message!("{}: failed: {err}", project.name);
+                    None
+                }
+                Ok((_, changes)) if changes.package_changes().is_empty() => {
+                    message!("{}: nothing to do", project.name);
+                    None
+                }
+                Ok((_, changes)) => {
+                    has_changes = true;
+                    if !json::enabled() {
+                        println!("{} ({}):", project.name, project.path.display());
+                        print_changes(changes);
+                    }
+                    Some(changes)
+                }
+            };
+            if json::enabled() {
+                let changes = changes.map(|x| serde_json::to_value(ChangesInfo::new(x)).unwrap());
+                changes_json.push(changes);
+            }
+        }
+
+        if json::enabled() {
+            let preview = (collected.iter().zip(&changes_json))
+                .map(|((project, result), changes)| ProjectReport {
+                    project_name: &project.name,
+                    project_path: &project.path,
+                    changes: changes.as_ref(),
+                    result: None,
+                    error: result.as_ref().err().map(String::as_str),
+                })
+                .collect::<Vec<_>>();
+            json::defer_field("projects", preview);
+        }
+
+        let outcomes = if has_changes {
+            prompt_install(yes);
+
+            stream::iter(collected)
+                .map(|(project, result)| async move {
+                    let outcome = match result {
+                        Err(err) => BatchOutcome::Failed(err),
+                        Ok((_, changes)) if changes.package_changes().is_empty() => {
+                            BatchOutcome::UpToDate
+                        }
+                        Ok((mut unity, changes)) => {
+                            let count = changes.package_changes().len();
+                            match unity.apply_pending_changes(installer, changes).await {
+                                Ok(()) => BatchOutcome::Applied(count),
+                                Err(err) => {
+                                    BatchOutcome::Failed(format!("applying changes: {err}"))
+                                }
+                            }
+                        }
+                    };
+                    (project, outcome)
+                })
+                .buffered(self.jobs.get())
+                .collect::<Vec<_>>()
+                .await
+        } else {
+            collected
+                .into_iter()
+                .map(|(project, result)| match result {
+                    Err(err) => (project, BatchOutcome::Failed(err)),
+                    Ok(_) => (project, BatchOutcome::UpToDate),
+                })
+                .collect()
+        };
+
+        // the project database is updated one by one to avoid conflicting writes
+        for (project, outcome) in &outcomes {
+            if let BatchOutcome::Applied(_) = outcome {
+                update_project_last_modified(io, &project.path).await;
+            }
+        }
+
+        if json::enabled() {
+            print_json_report(&outcomes, &changes_json);
+        } else {
+            print_report(&outcomes);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ProjectReport<'a> {
+    project_name: &'a str,
+    project_path: &'a Path,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changes: Option<&'a serde_json::Value>,
+    /// None before applying the changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+fn print_json_report(
+    outcomes: &[(BatchProject, BatchOutcome)],
+    changes_json: &[Option<serde_json::Value>],
+) {
+    let report = (outcomes.iter().zip(changes_json))
+        .map(|((project, outcome), changes)| {
+            let (result, error) = match outcome {
+                BatchOutcome::UpToDate => ("up_to_date", None),
+                BatchOutcome::Applied(_) => ("applied", None),
+                BatchOutcome::Failed(err) => ("failed", Some(err.as_str())),
+            };
+            ProjectReport {
+                project_name: &project.name,
+                project_path: &project.path,
+                changes: changes.as_ref(),
+                result: Some(result),
+                error,
+            }
+        })
+        .collect::<Vec<_>>();
+    json::defer_field("projects", report);
+
+    if (outcomes.iter()).any(|(_, outcome)| matches!(outcome, BatchOutcome::Failed(_))) {
+        json::finish();
+        exit(1);
+    }
+}
+
+fn print_report(outcomes: &[(BatchProject, BatchOutcome)]) {
+    let mut applied = 0;
+    let mut up_to_date = 0;
+    let mut failed = 0;
+
+    println!("Summary:");
+    for (project, outcome) in outcomes {
+        match outcome {
+            BatchOutcome::UpToDate => {
+                up_to_date += 1;
+                println!("- {}: up to date", project.name);
+            }
+            BatchOutcome::Applied(count) => {
+                applied += 1;
+                println!("- {}: applied {count} package change(s)", project.name);
+            }
+            BatchOutcome::Failed(err) => {
+                failed += 1;
+                println!("- {}: failed: {err}", project.name);
+            }
+        }
+    }
+    println!("{applied} updated, {up_to_date} up to date, {failed} failed");
+
+    if failed != 0 {
+        exit(1);
+    }
+}
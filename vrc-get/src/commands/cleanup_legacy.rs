@@ -0,0 +1,117 @@
+use super::{ResultExt, confirm_or_exit, json, load_unity};
+use clap::Parser;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::unity_project::{LegacyAsset, LegacyAssetMatch};
+
+/// Remove legacy assets of the installed packages remaining in the project
+///
+/// Packages can define files and folders replaced by the package as legacy assets,
+/// which are removed when the package is installed.
+/// This command finds the legacy assets of all installed packages by the path or by the GUID,
+/// and removes them with the `.meta` files after confirmation.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct CleanupLegacy {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    /// Move the legacy assets to the folder instead of removing them.
+    /// The folder is relative to the project and must not be in Assets or Packages
+    #[arg(long, value_name = "FOLDER")]
+    quarantine: Option<PathBuf>,
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+}
+
+#[derive(Serialize)]
+struct LegacyAssetInfo<'a> {
+    path: &'a Path,
+    package: &'a str,
+    kind: &'static str,
+    matched_by: &'static str,
+    size: u64,
+}
+
+impl<'a> LegacyAssetInfo<'a> {
+    fn new(asset: &'a LegacyAsset) -> Self {
+        Self {
+            path: asset.path(),
+            package: asset.package_name(),
+            kind: if asset.is_file() { "file" } else { "folder" },
+            matched_by: match asset.matched_by() {
+                LegacyAssetMatch::Path => "path",
+                LegacyAssetMatch::Guid => "guid",
+            },
+            size: asset.size(),
+        }
+    }
+}
+
+fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if size < 1024 {
+        return format!("{size} B");
+    }
+    let mut size = size as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+impl CleanupLegacy {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let assets = unity.find_legacy_assets().await;
+
+        if json::enabled() {
+            let infos = assets.iter().map(LegacyAssetInfo::new).collect::<Vec<_>>();
+            json::defer_field("legacy_assets", infos);
+        }
+
+        if assets.is_empty() {
+            message!("No legacy assets found");
+            return;
+        }
+
+        message!("Found {} legacy asset(s):", assets.len());
+        for asset in &assets {
+            let kind = if asset.is_file() { "file" } else { "folder" };
+            let matched_by = match asset.matched_by() {
+                LegacyAssetMatch::Path => "path",
+                LegacyAssetMatch::Guid => "GUID",
+            };
+            message!(
+                "- {} ({kind}, {}, found by {matched_by}) of {}",
+                asset.path().display(),
+                format_size(asset.size()),
+                asset.package_name()
+            );
+        }
+        let total = assets.iter().map(LegacyAsset::size).sum::<u64>();
+        message!("Total: {}", format_size(total));
+
+        let question = match &self.quarantine {
+            Some(folder) => format!("Do you want to move those assets to {}?", folder.display()),
+            None => "Do you want to remove those assets?".to_owned(),
+        };
+        if self.yes {
+            message!("--yes is set. skipping confirm");
+        } else {
+            confirm_or_exit(&question);
+        }
+
+        match &self.quarantine {
+            Some(folder) => unity
+                .move_legacy_assets(&assets, folder)
+                .await
+                .exit_context("moving legacy assets"),
+            None => unity.remove_legacy_assets(&assets).await,
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use super::{
+    EnvArgs, ResultExt, UnityProject, absolute_path, json, load_collection_for_project, load_unity,
+    print_changes,
+};
+use clap::Parser;
+use log::info;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::environment::PackageInstaller;
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, EnvironmentIo};
+use vrc_get_vpm::unity_project::PendingProjectChanges;
+
+/// Copy the project as a new project
+///
+/// Folders generated by Unity like `Library` and `Temp`, and `.git` folders are not copied,
+/// so Unity imports the assets again when the copy is opened first time.
+/// The copy has a new product GUID and product name so that Unity and VRChat treat it as a different project.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct CloneProject {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    /// The folder to create the copy in. The folder must not exist or must be empty
+    dest: PathBuf,
+    /// The product name of the copy. defaults to the name of the folder
+    #[arg(long)]
+    name: Option<String>,
+    /// Replace the packages with the ones of the template in `Templates` folder of the environment,
+    /// and install them
+    #[arg(long)]
+    template: Option<String>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+#[derive(Serialize)]
+struct CloneInfo<'a> {
+    path: &'a Path,
+    product_name: &'a str,
+}
+
+impl CloneProject {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let dest = absolute_path(&self.dest);
+
+        let product_name = match &self.name {
+            Some(name) => name.as_str(),
+            None => match dest.file_name().and_then(|x| x.to_str()) {
+                Some(name) => name,
+                None => exit_with!(
+                    InvalidArgument,
+                    "cannot get the product name from {}. specify with --name",
+                    dest.display()
+                ),
+            },
+        };
+
+        let template = self.template.as_deref().map(|name| {
+            let template = PathBuf::from(format!("Templates/{name}"));
+            if !std::fs::metadata(io.resolve(&template)).is_ok_and(|x| x.is_dir()) {
+                exit_with!(PathNotFound, "template {name} not found");
+            }
+            template
+        });
+
+        let is_empty = match std::fs::read_dir(&dest) {
+            Ok(mut dir) => dir.next().is_none(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+            Err(e) => exit_with!(OperationFailed, "reading {}: {e}", dest.display()),
+        };
+        if !is_empty {
+            exit_with!(InvalidArgument, "{} is not empty", dest.display());
+        }
+
+        let unity = load_unity(self.project).await;
+
+        // copying into the project would copy the copy itself again
+        let project_dir = canonicalize_existing(unity.project_dir());
+        if canonicalize_existing(&dest).starts_with(&project_dir) {
+            exit_with!(
+                InvalidArgument,
+                "{} is inside the project {}",
+                dest.display(),
+                unity.project_dir().display()
+            );
+        }
+
+        info!(
+            "copying {} to {}",
+            unity.project_dir().display(),
+            dest.display()
+        );
+        let mut cloned = unity
+            .clone_project(DefaultProjectIo::new(dest.clone().into()), product_name)
+            .await
+            .exit_context("copying project");
+
+        if let Some(template) = template {
+            cloned = cloned
+                .reset_packages_to_template(&io, &template)
+                .await
+                .exit_context("copying packages of the template");
+            install_packages(&io, &self.env_args, &mut cloned).await;
+        }
+
+        register_project(&io, &cloned).await;
+
+        if json::enabled() {
+            json::defer_field(
+                "project",
+                CloneInfo {
+                    path: &dest,
+                    product_name,
+                },
+            );
+        } else {
+            println!("Created {product_name} at {}", dest.display());
+        }
+    }
+}
+
+/// Canonicalizes the path, resolving the nearest existing ancestor if the path does not exist.
+fn canonicalize_existing(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            return rest
+                .into_iter()
+                .rev()
+                .fold(canonical, |path, x| path.join(x));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return path.to_owned(),
+        }
+    }
+}
+
+async fn install_packages(io: &DefaultEnvironmentIo, env_args: &EnvArgs, unity: &mut UnityProject) {
+    let client = crate::create_client(env_args.offline);
+    let collection = load_collection_for_project(io, client.as_ref(), env_args, unity).await;
+    let installer = PackageInstaller::new(io, client.as_ref());
+
+    let changes = unity
+        .resolve_request(&collection)
+        .await
+        .exit_context("collecting packages to be installed");
+
+    print_clone_changes(&changes);
+
+    unity
+        .apply_pending_changes(&installer, changes)
+        .await
+        .exit_context("installing packages");
+}
+
+fn print_clone_changes(changes: &PendingProjectChanges) {
+    if changes.package_changes().is_empty() {
+        return;
+    }
+    if json::enabled() {
+        json::defer_field("changes", json::ChangesInfo::new(changes));
+    } else {
+        print_changes(changes);
+    }
+}
+
+#[cfg(feature = "experimental-vcc")]
+async fn register_project(io: &DefaultEnvironmentIo, project: &UnityProject) {
+    use super::vcc::migrate_sanitize_projects;
+    use vrc_get_vpm::environment::{Settings, VccDatabaseConnection};
+
+    let mut settings = Settings::load(io).await.exit_context("loading settings");
+    let mut connection = VccDatabaseConnection::connect(io)
+        .await
+        .exit_context("connecting to database");
+
+    migrate_sanitize_projects(&mut connection, io, &settings).await;
+
+    connection
+        .add_project(project)
+        .await
+        .exit_context("adding project");
+
+    connection.save(io).await.exit_context("saving database");
+    settings
+        .load_from_db(&connection)
+        .await
+        .exit_context("saving database");
+    settings.save(io).await.exit_context("saving settings");
+    connection
+        .dispose()
+        .await
+        .exit_context("disposing database");
+}
+
+#[cfg(not(feature = "experimental-vcc"))]
+async fn register_project(_: &DefaultEnvironmentIo, _: &UnityProject) {}
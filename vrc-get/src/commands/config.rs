@@ -0,0 +1,337 @@
+use super::{EnvArgs, ResultExt, json};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::time::Duration;
+use vrc_get_vpm::environment::Settings;
+use vrc_get_vpm::io::{DefaultEnvironmentIo, EnvironmentIo, IoTrait};
+
+/// Show or change the settings of the environment
+///
+/// The settings are shared with VCC and the GUI, and keys not listed are kept as is.
+/// Run `vrc-get config list` to see all keys and the current values.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Config {
+    List(ConfigList),
+    Get(ConfigGet),
+    Set(ConfigSet),
+    Unset(ConfigUnset),
+}
+
+multi_command!(Config is List, Get, Set, Unset);
+
+/// The settings file of the GUI. This is only read since the GUI owns the format.
+const GUI_CONFIG_PATH: &str = "vrc-get/gui-config.json";
+
+/// The prefix of the keys for the settings of the GUI
+const GUI_PREFIX: &str = "gui.";
+
+struct ConfigKey {
+    name: &'static str,
+    get: fn(&Settings) -> Value,
+    set: fn(&mut Settings, &str) -> Result<(), String>,
+    unset: fn(&mut Settings),
+}
+
+const KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        name: "show-prerelease-packages",
+        get: |settings| settings.show_prerelease_packages().into(),
+        set: |settings, value| {
+            settings.set_show_prerelease_packages(parse_bool(value)?);
+            Ok(())
+        },
+        unset: |settings| settings.set_show_prerelease_packages(false),
+    },
+    ConfigKey {
+        name: "default-project-path",
+        get: |settings| settings.default_project_path().into(),
+        set: |settings, value| {
+            settings.set_default_project_path(&parse_path(value)?);
+            Ok(())
+        },
+        unset: Settings::clear_default_project_path,
+    },
+    ConfigKey {
+        name: "project-backup-path",
+        get: |settings| settings.project_backup_path().into(),
+        set: |settings, value| {
+            settings.set_project_backup_path(&parse_path(value)?);
+            Ok(())
+        },
+        unset: Settings::clear_project_backup_path,
+    },
+    ConfigKey {
+        name: "unity-hub-path",
+        get: |settings| {
+            Some(settings.unity_hub_path())
+                .filter(|x| !x.is_empty())
+                .into()
+        },
+        set: |settings, value| {
+            let path = parse_path(value)?;
+            if !std::path::Path::new(&path).is_file() {
+                return Err(format!("{path} is not a file"));
+            }
+            settings.set_unity_hub_path(&path);
+            Ok(())
+        },
+        unset: |settings| settings.set_unity_hub_path(""),
+    },
+    ConfigKey {
+        name: "ignore-official-repository",
+        get: |settings| settings.ignore_official_repository().into(),
+        set: |settings, value| {
+            settings.set_ignore_official_repository(parse_bool(value)?);
+            Ok(())
+        },
+        unset: |settings| settings.set_ignore_official_repository(false),
+    },
+    ConfigKey {
+        name: "ignore-curated-repository",
+        get: |settings| settings.ignore_curated_repository().into(),
+        set: |settings, value| {
+            settings.set_ignore_curated_repository(parse_bool(value)?);
+            Ok(())
+        },
+        unset: |settings| settings.set_ignore_curated_repository(false),
+    },
+    ConfigKey {
+        name: "repository-cache-ttl",
+        get: |settings| settings.repository_cache_ttl().as_secs().into(),
+        set: |settings, value| {
+            settings.set_repository_cache_ttl(parse_seconds(value)?);
+            Ok(())
+        },
+        unset: Settings::clear_repository_cache_ttl,
+    },
+    ConfigKey {
+        name: "repository-fetch-timeout",
+        get: |settings| {
+            settings
+                .repository_fetch_timeout()
+                .map(|x| x.as_secs())
+                .into()
+        },
+        set: |settings, value| {
+            settings.set_repository_fetch_timeout(Some(parse_seconds(value)?));
+            Ok(())
+        },
+        unset: |settings| settings.set_repository_fetch_timeout(None),
+    },
+];
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false but was {value}")),
+    }
+}
+
+fn parse_path(value: &str) -> Result<String, String> {
+    if value.is_empty() {
+        return Err("path must not be empty".into());
+    }
+    let path = std::path::absolute(value).map_err(|e| e.to_string())?;
+    path.into_os_string()
+        .into_string()
+        .map_err(|path| format!("{} is not a valid unicode path", path.display()))
+}
+
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    value
+        .parse::<u64>()
+        .map(Duration::from_secs)
+        .map_err(|_| format!("expected seconds but was {value}"))
+}
+
+fn find_key(key: &str) -> &'static ConfigKey {
+    let Some(key) = KEYS.iter().find(|x| x.name == key) else {
+        if key.starts_with(GUI_PREFIX) {
+            exit_with!(
+                InvalidArgument,
+                "the settings of the GUI cannot be changed with vrc-get. use the settings page of the GUI"
+            );
+        }
+        exit_with!(
+            UnknownConfigKey,
+            "unknown key: {key}. run `vrc-get config list` to see all keys"
+        );
+    };
+    key
+}
+
+async fn load_gui_config(io: &DefaultEnvironmentIo) -> Map<String, Value> {
+    let path = GUI_CONFIG_PATH.as_ref();
+    if !io.is_file(path).await {
+        return Map::new();
+    }
+    let json = tokio::fs::read(io.resolve(path))
+        .await
+        .exit_context("reading the settings of the GUI");
+    serde_json::from_slice(&json).exit_context("parsing the settings of the GUI")
+}
+
+#[derive(Serialize)]
+struct ConfigEntry {
+    key: String,
+    value: Value,
+}
+
+/// Formats the value without quotes for strings. `None` if the value is not set
+fn format_value(value: &Value) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    Some(
+        value
+            .as_str()
+            .map_or_else(|| value.to_string(), str::to_owned),
+    )
+}
+
+/// List the settings with the current values
+#[derive(Parser)]
+#[command(author, version)]
+pub struct ConfigList {
+    /// Also list the settings of the GUI. Those settings are read only
+    #[arg(long)]
+    gui: bool,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl ConfigList {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let settings = Settings::load(&io).await.exit_context("loading settings");
+
+        let mut entries = KEYS
+            .iter()
+            .map(|key| ConfigEntry {
+                key: key.name.into(),
+                value: (key.get)(&settings),
+            })
+            .collect::<Vec<_>>();
+
+        if self.gui {
+            let gui_config = load_gui_config(&io).await;
+            entries.extend(gui_config.into_iter().map(|(key, value)| ConfigEntry {
+                key: format!("{GUI_PREFIX}{key}"),
+                value,
+            }));
+        }
+
+        if json::enabled() {
+            return json::print(&entries);
+        }
+
+        for entry in &entries {
+            let value = format_value(&entry.value);
+            println!("{}: {}", entry.key, value.as_deref().unwrap_or("(not set)"));
+        }
+    }
+}
+
+/// Show the value of the setting
+///
+/// The settings of the GUI can be shown with `gui.` prefix, like `gui.language`
+#[derive(Parser)]
+#[command(author, version)]
+pub struct ConfigGet {
+    /// The key of the setting
+    key: String,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl ConfigGet {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+
+        let value = if let Some(gui_key) = self.key.strip_prefix(GUI_PREFIX) {
+            let mut gui_config = load_gui_config(&io).await;
+            gui_config.remove(gui_key).unwrap_or(Value::Null)
+        } else {
+            let key = find_key(&self.key);
+            let settings = Settings::load(&io).await.exit_context("loading settings");
+            (key.get)(&settings)
+        };
+
+        if json::enabled() {
+            return json::print(&ConfigEntry {
+                key: self.key,
+                value,
+            });
+        }
+
+        if let Some(value) = format_value(&value) {
+            println!("{value}");
+        }
+    }
+}
+
+/// Change the value of the setting
+///
+/// The following keys are available:
+///
+/// - `show-prerelease-packages`: show prerelease versions of packages. `true` or `false`
+/// - `default-project-path`: the folder to create new projects in
+/// - `project-backup-path`: the folder to save backups of projects in
+/// - `unity-hub-path`: the path to the Unity Hub executable
+/// - `ignore-official-repository`: do not use the official repository (experimental). `true` or `false`
+/// - `ignore-curated-repository`: do not use the curated repository (experimental). `true` or `false`
+/// - `repository-cache-ttl`: seconds to use the cache of remote repositories without fetching
+/// - `repository-fetch-timeout`: seconds to wait for each remote repository before using the cache
+#[derive(Parser)]
+#[command(author, version)]
+pub struct ConfigSet {
+    /// The key of the setting
+    key: String,
+    /// The new value. Relative paths are resolved from the current directory
+    value: String,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl ConfigSet {
+    pub async fn run(self) {
+        let key = find_key(&self.key);
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        if let Err(e) = (key.set)(&mut settings, &self.value) {
+            exit_with!(InvalidArgument, "invalid value for {}: {e}", key.name);
+        }
+
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}
+
+/// Reset the setting to the default value
+#[derive(Parser)]
+#[command(author, version)]
+pub struct ConfigUnset {
+    /// The key of the setting
+    key: String,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl ConfigUnset {
+    pub async fn run(self) {
+        let key = find_key(&self.key);
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        (key.unset)(&mut settings);
+
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}
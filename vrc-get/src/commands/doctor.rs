@@ -0,0 +1,105 @@
+use super::{ResultExt, json, load_unity_with_io, project_io};
+use clap::Parser;
+use serde::Serialize;
+use std::path::Path;
+use std::process::exit;
+use vrc_get_vpm::unity_project::{ProblemSeverity, ProjectProblem, diagnose_manifest_files};
+
+/// Check the project for problems and suggest how to fix them
+///
+/// Exits with non-zero status if any error is found.
+/// With --json-format, this will emit machine-readable information with json
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Doctor {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl Doctor {
+    pub async fn run(self) {
+        let io = project_io(self.project);
+
+        // malformed manifest prevents loading the project so check them first
+        let mut problems = diagnose_manifest_files(&io)
+            .await
+            .exit_context("checking manifest files");
+
+        if problems.is_empty() {
+            let unity = load_unity_with_io(io).await;
+            problems = unity.diagnose().await;
+        }
+
+        match json::format() {
+            0 => Self::human_readable(&problems),
+            _ => Self::version1(&problems),
+        }
+
+        if problems
+            .iter()
+            .any(|x| x.severity() == ProblemSeverity::Error)
+        {
+            exit(1);
+        }
+    }
+
+    fn human_readable(problems: &[ProjectProblem]) {
+        if problems.is_empty() {
+            println!("No problems found");
+            return;
+        }
+
+        for problem in problems {
+            let severity = match problem.severity() {
+                ProblemSeverity::Error => "error",
+                ProblemSeverity::Warning => "warning",
+            };
+            println!("{severity}: {problem}");
+            println!("  fix: {}", problem.suggested_fix());
+        }
+    }
+
+    fn version1(problems: &[ProjectProblem]) {
+        #[derive(Serialize)]
+        struct Report {
+            problems: Vec<ProblemInfo>,
+        }
+
+        #[derive(Serialize)]
+        struct ProblemInfo {
+            kind: &'static str,
+            severity: &'static str,
+            message: String,
+            suggestion: &'static str,
+        }
+
+        let problems = problems
+            .iter()
+            .map(|problem| ProblemInfo {
+                kind: match problem {
+                    ProjectProblem::MalformedManifest { .. } => "malformed_manifest",
+                    ProjectProblem::UnfinishedChanges => "unfinished_changes",
+                    ProjectProblem::MissingLockedPackage { .. } => "missing_locked_package",
+                    ProjectProblem::LockedVersionMismatch { .. } => "locked_version_mismatch",
+                    ProjectProblem::MissingDependency { .. } => "missing_dependency",
+                    ProjectProblem::MissingUnlockedDependency { .. } => {
+                        "missing_unlocked_dependency"
+                    }
+                    ProjectProblem::UnlockedPackage { .. } => "unlocked_package",
+                    ProjectProblem::LegacyAsset { .. } => "legacy_asset",
+                    ProjectProblem::DuplicateGuid { .. } => "duplicate_guid",
+                    _ => "unknown",
+                },
+                severity: match problem.severity() {
+                    ProblemSeverity::Error => "error",
+                    ProblemSeverity::Warning => "warning",
+                },
+                message: problem.to_string(),
+                suggestion: problem.suggested_fix(),
+            })
+            .collect();
+
+        println!("{}", serde_json::to_string(&Report { problems }).unwrap());
+    }
+}
@@ -0,0 +1,160 @@
+use super::{json, load_unity};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::Path;
+use vrc_get_vpm::unity_project::IndexedAsset;
+
+/// Inspect the GUIDs of the assets in the project
+///
+/// The GUIDs are read from the `.meta` files in Assets and the folders in Packages.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Guid {
+    Check(GuidCheck),
+    Find(GuidFind),
+}
+
+multi_command!(Guid is Check, Find);
+
+#[derive(Serialize)]
+struct AssetInfo<'a> {
+    path: &'a Path,
+    package: Option<&'a str>,
+}
+
+impl<'a> AssetInfo<'a> {
+    fn list(assets: &'a [IndexedAsset]) -> Vec<Self> {
+        assets
+            .iter()
+            .map(|x| AssetInfo {
+                path: x.path(),
+                package: x.package(),
+            })
+            .collect()
+    }
+}
+
+fn print_asset(asset: &IndexedAsset) {
+    match asset.package() {
+        Some(package) => println!("- {} (package {package})", asset.path().display()),
+        None => println!("- {}", asset.path().display()),
+    }
+}
+
+/// List GUIDs used by multiple assets and assets without `.meta` files
+///
+/// Duplicate GUIDs usually come from a copy of a package imported to Assets with unitypackage
+/// while the package is also installed with vpm. Unity assigns a new GUID to one of them,
+/// which breaks references to the asset.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct GuidCheck {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl GuidCheck {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+        let index = unity.build_guid_index().await;
+        let duplicates = index.duplicates().collect::<Vec<_>>();
+
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct DuplicateInfo<'a> {
+                guid: &'a str,
+                assets: Vec<AssetInfo<'a>>,
+            }
+
+            #[derive(Serialize)]
+            struct Report<'a> {
+                duplicates: Vec<DuplicateInfo<'a>>,
+                missing_meta_files: &'a [Box<Path>],
+            }
+
+            return json::print(&Report {
+                duplicates: (duplicates.iter())
+                    .map(|(guid, assets)| DuplicateInfo {
+                        guid,
+                        assets: AssetInfo::list(assets),
+                    })
+                    .collect(),
+                missing_meta_files: index.missing_meta_files(),
+            });
+        }
+
+        if duplicates.is_empty() && index.missing_meta_files().is_empty() {
+            println!("No problems found");
+            return;
+        }
+
+        for (guid, assets) in &duplicates {
+            println!("GUID {guid} is used by multiple assets:");
+            for asset in *assets {
+                print_asset(asset);
+            }
+        }
+
+        if !index.missing_meta_files().is_empty() {
+            println!("Assets without .meta files:");
+            for path in index.missing_meta_files() {
+                println!("- {}", path.display());
+            }
+        }
+    }
+}
+
+/// Show the assets with the GUID and the package containing them
+#[derive(Parser)]
+#[command(author, version)]
+pub struct GuidFind {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    /// The GUID in the `.meta` file, like `0123456789abcdef0123456789abcdef`
+    guid: String,
+}
+
+impl GuidFind {
+    pub async fn run(self) {
+        if self.guid.len() != 32 || !self.guid.bytes().all(|x| x.is_ascii_hexdigit()) {
+            exit_with!(
+                InvalidArgument,
+                "{} is not a GUID. GUID is 32 hexadecimal digits",
+                self.guid
+            );
+        }
+
+        let unity = load_unity(self.project).await;
+        let index = unity.build_guid_index().await;
+        let assets = index.get(&self.guid);
+
+        if assets.is_empty() {
+            exit_with!(PathNotFound, "no asset with GUID {} found", self.guid);
+        }
+
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct FindInfo<'a> {
+                guid: &'a str,
+                package: Option<&'a str>,
+                assets: Vec<AssetInfo<'a>>,
+            }
+
+            return json::print(&FindInfo {
+                guid: &self.guid,
+                package: index.find_package(&self.guid),
+                assets: AssetInfo::list(assets),
+            });
+        }
+
+        match index.find_package(&self.guid) {
+            Some(package) => println!("GUID {} belongs to package {package}", self.guid),
+            None => println!("GUID {} does not belong to any package", self.guid),
+        }
+        for asset in assets {
+            print_asset(asset);
+        }
+    }
+}
@@ -1,13 +1,12 @@
-use super::{UnityProject, load_collection};
+use super::{UnityProject, json, load_collection};
 use crate::commands::load_unity;
 use clap::{Parser, Subcommand};
+use indexmap::IndexMap;
 use itertools::Itertools;
 use serde::Serialize;
 use std::collections::HashSet;
-use std::num::NonZeroU32;
 use std::path::Path;
 use vrc_get_vpm::PackageCollection;
-use vrc_get_vpm::io::DefaultEnvironmentIo;
 use vrc_get_vpm::version::{UnityVersion, Version, VersionRange};
 
 /// Shows information for other program.
@@ -30,24 +29,19 @@ pub struct Project {
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
-
-    /// Output json format
-    #[arg(long = "json-format")]
-    json_format: Option<NonZeroU32>,
 }
 
 impl Project {
     pub async fn run(self) {
         let unity = load_unity(self.project).await;
 
-        match self.json_format.map(|x| x.get()).unwrap_or_default() {
+        match json::format() {
             0 => {
                 Self::human_readable(&unity).await;
             }
-            1 => {
+            _ => {
                 Self::version1(&unity).await;
             }
-            unsupported => exit_with!("unsupported json version: {unsupported}"),
         };
     }
 
@@ -58,6 +52,14 @@ impl Project {
         } else {
             eprintln!("Using unknown unity");
         }
+        let mut overrides = unity.dependency_overrides().peekable();
+        if overrides.peek().is_some() {
+            eprintln!();
+            eprintln!("Dependency overrides are active:");
+            for (key, range) in overrides {
+                eprintln!("{key}: {range}");
+            }
+        }
         eprintln!();
         eprintln!("Locked Packages:");
         for locked in unity.locked_packages() {
@@ -91,13 +93,43 @@ impl Project {
                 );
             }
         }
+
+        eprintln!();
+        eprintln!("UPM Packages:");
+        for (package, dependency) in unity.upm_dependencies() {
+            eprintln!("{package}: {dependency}");
+        }
+
+        let scoped_registries = unity.upm_scoped_registries();
+        if !scoped_registries.is_empty() {
+            eprintln!();
+            eprintln!("Scoped Registries:");
+            for registry in scoped_registries {
+                eprintln!(
+                    "{name} at {url} for {scopes}",
+                    name = registry.name(),
+                    url = registry.url(),
+                    scopes = registry.scopes().iter().join(", "),
+                );
+            }
+        }
     }
 
     pub async fn version1(unity: &UnityProject) {
+        json::print(&Self::version1_value(unity));
+    }
+
+    /// Builds the project information in the version 1 format
+    pub(super) fn version1_value(unity: &UnityProject) -> serde_json::Value {
         #[derive(Serialize)]
         struct Project<'a> {
             unity_version: Option<UnityVersion>,
             packages: &'a [PackageInfo<'a>],
+            upm_packages: &'a [UpmPackageInfo<'a>],
+            scoped_registries: &'a [ScopedRegistryInfo<'a>],
+            /// The overrides of dependency ranges in vpm-manifest.json
+            #[serde(skip_serializing_if = "IndexMap::is_empty")]
+            dependency_overrides: IndexMap<&'a str, &'a VersionRange>,
         }
 
         #[derive(Serialize)]
@@ -108,6 +140,22 @@ impl Project {
             requested: Vec<&'a VersionRange>,
         }
 
+        #[derive(Serialize)]
+        struct UpmPackageInfo<'a> {
+            name: &'a str,
+            /// The version if the package is from registry
+            version: Option<&'a Version>,
+            /// The raw value in manifest.json
+            requested: String,
+        }
+
+        #[derive(Serialize)]
+        struct ScopedRegistryInfo<'a> {
+            name: &'a str,
+            url: &'a str,
+            scopes: &'a [Box<str>],
+        }
+
         let mut packages = vec![];
 
         for locked in unity.locked_packages() {
@@ -156,12 +204,34 @@ impl Project {
             });
         }
 
+        let upm_packages = unity
+            .upm_dependencies()
+            .map(|(name, dependency)| UpmPackageInfo {
+                name,
+                version: dependency.as_version(),
+                requested: dependency.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let scoped_registries = unity
+            .upm_scoped_registries()
+            .iter()
+            .map(|registry| ScopedRegistryInfo {
+                name: registry.name(),
+                url: registry.url(),
+                scopes: registry.scopes(),
+            })
+            .collect::<Vec<_>>();
+
         let project = Project {
             unity_version: unity.unity_version(),
             packages: packages.as_slice(),
+            upm_packages: upm_packages.as_slice(),
+            scoped_registries: scoped_registries.as_slice(),
+            dependency_overrides: unity.dependency_overrides().collect(),
         };
 
-        println!("{}", serde_json::to_string(&project).unwrap());
+        serde_json::to_value(project).unwrap()
     }
 }
 
@@ -173,51 +243,54 @@ pub struct Package {
     package: String,
     #[command(flatten)]
     env_args: super::EnvArgs,
-
-    /// Output json format
-    #[arg(long = "json-format")]
-    json_format: Option<NonZeroU32>,
 }
 
 impl Package {
     pub async fn run(self) {
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
-
-        let format_version = match self.json_format.map(|x| x.get()).unwrap_or_default() {
-            0 => {
-                eprintln!("warning: no --json-format is specified! using lastest version 1");
-                1
-            }
-            supported @ 1..=1 => supported,
-            unsupported => exit_with!("unsupported json version: {unsupported}"),
-        };
+        let io = self.env_args.environment_io();
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
 
-        debug_assert_eq!(format_version, 1);
+        if !json::enabled() {
+            eprintln!(
+                "warning: no --json-format is specified! using latest version {}",
+                json::LATEST_FORMAT
+            );
+        }
 
+        // all format versions print the same document as version 1
         let versions: Vec<_> = collection
             .find_packages(&self.package)
             .map(|x| PackageVersionInfo {
                 version: x.version(),
                 // since 1.5.0
                 is_yanked: x.is_yanked(),
+                // since 1.9.1
+                repository: x
+                    .repo()
+                    .and_then(|x| x.id().or(x.url().map(|x| x.as_str()))),
             })
             .collect();
 
         #[derive(Serialize)]
         struct PackageInfo<'a> {
             versions: &'a [PackageVersionInfo<'a>],
+            pinned_repository: Option<&'a str>,
         }
 
         #[derive(Serialize)]
         struct PackageVersionInfo<'a> {
             version: &'a Version,
             is_yanked: bool,
+            repository: Option<&'a str>,
         }
 
         let package_info = PackageInfo {
             versions: versions.as_slice(),
+            // since 1.9.1
+            pinned_repository: collection
+                .repository_policy()
+                .pinned_repository(&self.package),
         };
 
         println!("{}", serde_json::to_string(&package_info).unwrap());
@@ -0,0 +1,283 @@
+//! Machine-readable output enabled with the global `--json-format` option.
+//!
+//! The schema of each format version is documented in docs/json-output.md.
+//! Fields may be added to the existing format version, but never be removed or changed.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt::Display;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use vrc_get_vpm::unity_project::PendingProjectChanges;
+use vrc_get_vpm::unity_project::pending_project_changes::{PackageChange, RemoveReason};
+use vrc_get_vpm::version::Version;
+
+/// The latest version of the json format
+pub(super) const LATEST_FORMAT: u32 = 2;
+
+static FORMAT: AtomicU32 = AtomicU32::new(0);
+
+/// The fields of the document printed when the command finishes.
+static DEFERRED: Mutex<Option<Map<String, Value>>> = Mutex::new(None);
+
+pub(super) fn init(format: Option<NonZeroU32>) {
+    let format = format.map_or(0, NonZeroU32::get);
+    if format > LATEST_FORMAT {
+        // report the error with the latest format
+        FORMAT.store(LATEST_FORMAT, Ordering::Relaxed);
+        exit_with!(UnsupportedJsonFormat, "unsupported json version: {format}");
+    }
+    FORMAT.store(format, Ordering::Relaxed);
+}
+
+/// Returns the version specified with `--json-format`, or 0 for human-readable output.
+pub(super) fn format() -> u32 {
+    FORMAT.load(Ordering::Relaxed)
+}
+
+pub(super) fn enabled() -> bool {
+    format() != 0
+}
+
+pub(super) fn print(value: &impl Serialize) {
+    println!("{}", serde_json::to_string(value).unwrap());
+}
+
+/// Sets the field of the document printed when the command finishes.
+///
+/// This is used for the information shown before asking the user to confirm,
+/// so that the output is a single document.
+/// If the command fails, the field is printed along with the error object.
+pub(super) fn defer_field(key: &str, value: impl Serialize) {
+    let value = serde_json::to_value(value).unwrap();
+    (DEFERRED.lock().unwrap())
+        .get_or_insert_default()
+        .insert(key.into(), value);
+}
+
+/// Prints the deferred document if any. Called after the command finishes successfully.
+pub(super) fn finish() {
+    if let Some(document) = DEFERRED.lock().unwrap().take() {
+        print(&document);
+    }
+}
+
+/// The stable code of the error, used as `error.code` in json output.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ErrorCode {
+    /// Some operation failed. `error.context` describes what was being done.
+    OperationFailed,
+    UnsupportedJsonFormat,
+    InvalidArgument,
+    NothingToDo,
+    Cancelled,
+    ConfirmationRequired,
+    Offline,
+    FeatureDisabled,
+    PackageNotFound,
+    BadPackage,
+    PathNotFound,
+    CannotRollback,
+    RepositoryNotFound,
+    RepositoryAlreadyExists,
+    PackageNotPinned,
+    UserPackageAlreadyExists,
+    NoProjectsMatched,
+    #[cfg_attr(not(feature = "experimental-vcc"), allow(dead_code))]
+    UnityNotFound,
+    UnityFailed,
+    UpmPackageNotFound,
+    ScopedRegistryNotFound,
+    /// The changes to apply with `vrc-get serve` are not planned or replaced by newer changes.
+    ChangesNotFound,
+    /// The key specified with `vrc-get config` is not known.
+    UnknownConfigKey,
+}
+
+/// Prints the error and exits the process.
+///
+/// With `--json-format`, the error object is printed to stdout instead of the message to stderr.
+pub(super) fn exit_with_error(code: ErrorCode, context: Option<&str>, message: impl Display) -> ! {
+    if enabled() {
+        #[derive(Serialize)]
+        struct ErrorInfo<'a> {
+            code: ErrorCode,
+            message: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            context: Option<&'a str>,
+        }
+
+        let error = ErrorInfo {
+            code,
+            message: message.to_string(),
+            context,
+        };
+
+        let mut document = DEFERRED.lock().unwrap().take().unwrap_or_default();
+        document.insert("error".into(), serde_json::to_value(error).unwrap());
+        print(&document);
+    } else {
+        match context {
+            Some(context) => eprintln!("error {context}: {message}"),
+            None => eprintln!("{message}"),
+        }
+    }
+    std::process::exit(1)
+}
+
+#[derive(Serialize)]
+pub(super) struct ChangesInfo<'a> {
+    install: Vec<InstallInfo<'a>>,
+    add_to_dependencies: Vec<DependencyInfo<'a>>,
+    remove: Vec<RemoveInfo<'a>>,
+    eject: Vec<PackageVersion<'a>>,
+    adopt: Vec<AdoptInfo<'a>>,
+    remove_legacy_folders: Vec<LegacyAssetInfo<'a>>,
+    remove_legacy_files: Vec<LegacyAssetInfo<'a>>,
+    dependency_overrides: Vec<OverrideInfo<'a>>,
+    conflicts: Vec<ConflictInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct InstallInfo<'a> {
+    name: &'a str,
+    version: &'a Version,
+    is_yanked: bool,
+}
+
+#[derive(Serialize)]
+struct DependencyInfo<'a> {
+    name: &'a str,
+    range: String,
+    replaced_range: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RemoveInfo<'a> {
+    name: &'a str,
+    reason: &'static str,
+}
+
+#[derive(Serialize)]
+struct PackageVersion<'a> {
+    name: &'a str,
+    version: &'a Version,
+}
+
+#[derive(Serialize)]
+struct AdoptInfo<'a> {
+    name: &'a str,
+    version: &'a Version,
+    /// None if the package was not ejected by vrc-get
+    modified_files: Option<&'a [Box<str>]>,
+}
+
+#[derive(Serialize)]
+struct LegacyAssetInfo<'a> {
+    path: &'a Path,
+    package: &'a str,
+}
+
+#[derive(Serialize)]
+struct OverrideInfo<'a> {
+    key: &'a str,
+    range: String,
+}
+
+#[derive(Serialize)]
+struct ConflictInfo<'a> {
+    package: &'a str,
+    conflicting_packages: &'a [Box<str>],
+    conflicts_with_unity: bool,
+    unlocked_names: &'a [Box<str>],
+}
+
+impl<'a> ChangesInfo<'a> {
+    pub fn new(changes: &'a PendingProjectChanges) -> Self {
+        let mut info = ChangesInfo {
+            install: vec![],
+            add_to_dependencies: vec![],
+            remove: vec![],
+            eject: vec![],
+            adopt: vec![],
+            remove_legacy_folders: legacy_assets(changes.remove_legacy_folders()),
+            remove_legacy_files: legacy_assets(changes.remove_legacy_files()),
+            dependency_overrides: (changes.dependency_overrides().iter())
+                .map(|(key, range)| OverrideInfo {
+                    key,
+                    range: range.to_string(),
+                })
+                .collect(),
+            conflicts: (changes.conflicts().iter())
+                .map(|(package, conflicts)| ConflictInfo {
+                    package,
+                    conflicting_packages: conflicts.conflicting_packages(),
+                    conflicts_with_unity: conflicts.conflicts_with_unity(),
+                    unlocked_names: conflicts.unlocked_names(),
+                })
+                .collect(),
+        };
+
+        for (name, change) in changes.package_changes() {
+            match change {
+                PackageChange::Install(change) => {
+                    if let Some(package) = change.install_package() {
+                        info.install.push(InstallInfo {
+                            name: package.name(),
+                            version: package.version(),
+                            is_yanked: package.is_yanked(),
+                        });
+                    }
+                    if let Some(range) = change.to_dependencies() {
+                        info.add_to_dependencies.push(DependencyInfo {
+                            name,
+                            range: range.to_string(),
+                            replaced_range: change.replaced_dependency().map(|x| x.to_string()),
+                        });
+                    }
+                }
+                PackageChange::Remove(change) => {
+                    let reason = match change.reason() {
+                        RemoveReason::Requested => "requested",
+                        RemoveReason::Legacy => "legacy",
+                        RemoveReason::Unused => "unused",
+                    };
+                    info.remove.push(RemoveInfo { name, reason });
+                }
+                PackageChange::Eject(change) => {
+                    info.eject.push(PackageVersion {
+                        name,
+                        version: change.ejected_package().version(),
+                    });
+                }
+                PackageChange::Adopt(change) => {
+                    info.adopt.push(AdoptInfo {
+                        name,
+                        version: change.version(),
+                        modified_files: change.modified_files(),
+                    });
+                }
+            }
+        }
+
+        // package changes are in a hash map so sort them for stable output
+        info.install.sort_by_key(|x| x.name);
+        info.add_to_dependencies.sort_by_key(|x| x.name);
+        info.remove.sort_by_key(|x| x.name);
+        info.eject.sort_by_key(|x| x.name);
+        info.adopt.sort_by_key(|x| x.name);
+        info.conflicts.sort_by_key(|x| x.package);
+
+        info
+    }
+}
+
+fn legacy_assets<'a>(assets: &'a [(Box<Path>, &'a str)]) -> Vec<LegacyAssetInfo<'a>> {
+    assets
+        .iter()
+        .map(|(path, package)| LegacyAssetInfo { path, package })
+        .collect()
+}
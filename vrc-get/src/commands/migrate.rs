@@ -1,13 +1,11 @@
 use crate::commands::{
-    EnvArgs, ResultExt, confirm_prompt, load_collection, load_unity, update_project_last_modified,
+    EnvArgs, ResultExt, confirm_or_exit, load_collection, load_unity, update_project_last_modified,
 };
 use clap::{Parser, Subcommand};
 use log::info;
 use std::path::{Path, PathBuf};
-use std::process::exit;
 use tokio::process::Command;
 use vrc_get_vpm::environment::PackageInstaller;
-use vrc_get_vpm::io::DefaultEnvironmentIo;
 
 /// Migrate Unity Project
 #[derive(Subcommand)]
@@ -39,18 +37,16 @@ pub struct Unity2022 {
 
 impl Unity2022 {
     pub async fn run(self) {
-        println!("You're migrating your project to Unity 2022 in-place.");
-        println!("It's hard to undo this command.");
-        println!("You MUST create backup of your project before running this command.");
-        if !confirm_prompt("Do you want to continue?") {
-            exit(1);
-        }
+        message!("You're migrating your project to Unity 2022 in-place.");
+        message!("It's hard to undo this command.");
+        message!("You MUST create backup of your project before running this command.");
+        confirm_or_exit("Do you want to continue?");
 
         let mut project = load_unity(self.project).await;
 
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
+        let io = self.env_args.environment_io();
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
         let installer = PackageInstaller::new(&io, client.as_ref());
 
         #[cfg(feature = "experimental-vcc")]
@@ -81,6 +77,7 @@ impl Unity2022 {
                     .exit_context("getting unity 2022 path")
                 else {
                     exit_with!(
+                        UnityNotFound,
                         "Unity 2022 not found. please load from unity hub with `vrc-get vcc unity update` or specify path with `--unity` option."
                     )
                 };
@@ -115,7 +112,7 @@ impl Unity2022 {
             .exit_context("launching unity to finalize migration");
 
         if !status.success() {
-            exit_with!("Unity exited with status {}", status);
+            exit_with!(UnityFailed, "Unity exited with status {}", status);
         }
 
         info!("Unity exited successfully. Migration finished.");
@@ -136,18 +133,16 @@ pub struct Vpm {
 
 impl Vpm {
     pub async fn run(self) {
-        println!("You're migrating your project to vpm in-place.");
-        println!("It's hard to undo this command.");
-        println!("You MUST create backup of your project before running this command.");
-        if !confirm_prompt("Do you want to continue?") {
-            exit(1);
-        }
+        message!("You're migrating your project to vpm in-place.");
+        message!("It's hard to undo this command.");
+        message!("You MUST create backup of your project before running this command.");
+        confirm_or_exit("Do you want to continue?");
 
         let mut project = load_unity(self.project).await;
 
         let client = crate::create_client(self.env_args.offline);
-        let io = DefaultEnvironmentIo::new_default();
-        let collection = load_collection(&io, client.as_ref(), self.env_args.no_update).await;
+        let io = self.env_args.environment_io();
+        let collection = load_collection(&io, client.as_ref(), &self.env_args).await;
         let installer = PackageInstaller::new(&io, client.as_ref());
 
         project
@@ -0,0 +1,550 @@
+use super::json::{ChangesInfo, ErrorCode};
+use super::{
+    EnvArgs, PackageSummary, UnityProject, VersionOrRange, add_project_packages, find_upgrade,
+    info, json, load_collection, prerelease_acceptance, search_packages,
+    update_project_last_modified, warn_untrusted_project_repositories,
+};
+use clap::Parser;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+use vrc_get_vpm::environment::{PackageCollection, PackageInstaller, Settings};
+use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
+use vrc_get_vpm::version::{DependencyRange, Version};
+use vrc_get_vpm::{PackageCollection as _, PackageInfo, VersionSelector};
+
+/// Run JSON-RPC server for editor integrations
+///
+/// Each JSON-RPC 2.0 request and response is a single line of json.
+/// The repositories are loaded once and kept between requests.
+/// See docs/json-rpc-server.md in the repository for the methods.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Serve {
+    /// Communicate over stdin and stdout
+    #[arg(long, required = true)]
+    stdio: bool,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl Serve {
+    pub async fn run(self) {
+        // stdin and stdout is the only transport for now
+        debug_assert!(self.stdio);
+
+        let client = crate::create_client(self.env_args.offline);
+        let http = client.as_ref().filter(|_| !self.env_args.no_update);
+        let io = self.env_args.environment_io();
+        let mut collection = load_collection(&io, client.as_ref(), &self.env_args).await;
+        let mut requests = BufReader::new(tokio::io::stdin()).lines();
+        let mut next_changes_id = 1;
+        // the collection with the packages of the project, and the request to be handled with it
+        let mut project_collection = None;
+        let mut replay = None;
+
+        loop {
+            // the planned changes borrow the collection so they are discarded when reloading
+            let stopped = {
+                let mut server = Server {
+                    io: &io,
+                    http: client.as_ref(),
+                    collection: &collection,
+                    project_collection: project_collection.as_ref(),
+                    pending: None,
+                    next_changes_id,
+                };
+                let stopped = server.serve(&mut requests, replay.take()).await;
+                next_changes_id = server.next_changes_id;
+                stopped
+            };
+
+            match stopped {
+                Stopped::Eof => return,
+                Stopped::Refresh { id } => {
+                    project_collection = None;
+                    let result = match reload_collection(&io, http).await {
+                        Ok(reloaded) => {
+                            collection = reloaded;
+                            Ok(Value::Null)
+                        }
+                        Err(err) => Err(err),
+                    };
+                    if let Some(id) = id {
+                        respond(id, result);
+                    }
+                }
+                Stopped::LoadProject { request } => {
+                    let loaded =
+                        load_project_collection(&collection, &io, http, request.params.clone())
+                            .await;
+                    match loaded {
+                        Ok(loaded) => {
+                            project_collection = Some(loaded);
+                            replay = Some(request);
+                        }
+                        Err(err) => {
+                            if let Some(id) = request.id {
+                                respond(id, Err(err));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn reload_collection(
+    io: &DefaultEnvironmentIo,
+    http: Option<&reqwest::Client>,
+) -> Result<PackageCollection, RpcError> {
+    let settings = Settings::load(io)
+        .await
+        .map_err(|err| RpcError::failed("loading settings", err))?;
+    PackageCollection::load_force(&settings, io, http)
+        .await
+        .map_err(|err| RpcError::failed("loading repositories", err))
+}
+
+/// Loads the collection with the packages of the project specified with `project` of the params.
+///
+/// Repositories listed in the project but not added to vrc-get are not used
+/// since the user cannot be asked to accept them.
+async fn load_project_collection(
+    collection: &PackageCollection,
+    io: &DefaultEnvironmentIo,
+    http: Option<&reqwest::Client>,
+    params: Value,
+) -> Result<PackageCollection, RpcError> {
+    let params = parse_params::<ProjectParams>(params)?;
+    let unity = load_project(&params.project).await?;
+
+    warn_untrusted_project_repositories(io, &unity)
+        .await
+        .map_err(|err| RpcError::failed("loading project repositories", err))?;
+
+    let mut collection = collection.clone();
+    add_project_packages(&mut collection, io, http, &unity)
+        .await
+        .map_err(|err| RpcError::failed("loading project-local packages", err))?;
+    Ok(collection)
+}
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+/// The error of the operation. `data.code` is the stable error code same as `--json-format`.
+const APPLICATION_ERROR: i32 = -32000;
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<ErrorData>,
+}
+
+#[derive(Serialize)]
+struct ErrorData {
+    code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'static str>,
+}
+
+impl RpcError {
+    fn protocol(code: i32, message: impl Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    fn new(code: ErrorCode, message: impl Display) -> Self {
+        Self {
+            code: APPLICATION_ERROR,
+            message: message.to_string(),
+            data: Some(ErrorData {
+                code,
+                context: None,
+            }),
+        }
+    }
+
+    fn failed(context: &'static str, err: impl Display) -> Self {
+        Self {
+            code: APPLICATION_ERROR,
+            message: err.to_string(),
+            data: Some(ErrorData {
+                code: ErrorCode::OperationFailed,
+                context: Some(context),
+            }),
+        }
+    }
+}
+
+fn respond(id: Value, result: Result<Value, RpcError>) {
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    };
+    json::print(&response);
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // params can be omitted for methods without required params
+    let params = if params.is_null() {
+        Value::Object(Default::default())
+    } else {
+        params
+    };
+    serde_json::from_value(params).map_err(|err| RpcError::protocol(INVALID_PARAMS, err))
+}
+
+async fn load_project(path: &Path) -> Result<UnityProject, RpcError> {
+    UnityProject::load(DefaultProjectIo::new(path.into()))
+        .await
+        .map_err(|err| RpcError::failed("loading unity project", err))
+}
+
+enum Stopped {
+    /// stdin is closed
+    Eof,
+    /// `refresh` is requested
+    Refresh { id: Option<Value> },
+    /// The request needs the packages of the project, which are loaded before handling it
+    LoadProject { request: Request },
+}
+
+struct Server<'env> {
+    io: &'env DefaultEnvironmentIo,
+    http: Option<&'env reqwest::Client>,
+    collection: &'env PackageCollection,
+    /// The collection loaded for the project of the replayed request
+    project_collection: Option<&'env PackageCollection>,
+    pending: Option<Pending<'env>>,
+    next_changes_id: u32,
+}
+
+/// The changes planned with `plan_changes` or `resolve` and not applied yet
+struct Pending<'env> {
+    id: u32,
+    unity: UnityProject,
+    changes: PendingProjectChanges<'env>,
+}
+
+#[derive(Deserialize)]
+struct ProjectParams {
+    project: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct ListPackagesParams {
+    #[serde(default)]
+    prerelease: bool,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct PlanChangesParams {
+    project: PathBuf,
+    action: PlanAction,
+    packages: Vec<PackageRequest>,
+    #[serde(default)]
+    prerelease: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PlanAction {
+    Install,
+    Upgrade,
+    Remove,
+    Reinstall,
+}
+
+#[derive(Deserialize)]
+struct PackageRequest {
+    name: String,
+    /// Version or version range for install, version for upgrade
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApplyParams {
+    changes_id: u32,
+}
+
+#[derive(Serialize)]
+struct PackageEntry<'a> {
+    #[serde(flatten)]
+    package: PackageSummary<'a>,
+    is_yanked: bool,
+    repository: Option<&'a str>,
+}
+
+impl<'a> PackageEntry<'a> {
+    fn new(package: &PackageInfo<'a>) -> Self {
+        Self {
+            package: PackageSummary::new(package.package_json()),
+            is_yanked: package.is_yanked(),
+            repository: package
+                .repo()
+                .and_then(|x| x.id().or(x.url().map(Url::as_str))),
+        }
+    }
+}
+
+impl<'env> Server<'env> {
+    async fn serve(
+        &mut self,
+        requests: &mut Lines<BufReader<Stdin>>,
+        replay: Option<Request>,
+    ) -> Stopped {
+        if let Some(request) = replay {
+            self.handle_request(request).await;
+        }
+
+        loop {
+            let line = match requests.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => return Stopped::Eof,
+                Err(err) => {
+                    log::error!("error reading request: {err}");
+                    return Stopped::Eof;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let request = match serde_json::from_str::<Value>(&line) {
+                Ok(request) => request,
+                Err(err) => {
+                    respond(Value::Null, Err(RpcError::protocol(PARSE_ERROR, err)));
+                    continue;
+                }
+            };
+            let request = match serde_json::from_value::<Request>(request) {
+                Ok(request) => request,
+                Err(err) => {
+                    respond(Value::Null, Err(RpcError::protocol(INVALID_REQUEST, err)));
+                    continue;
+                }
+            };
+
+            match request.method.as_str() {
+                "refresh" => return Stopped::Refresh { id: request.id },
+                "plan_changes" | "resolve" => return Stopped::LoadProject { request },
+                _ => self.handle_request(request).await,
+            }
+        }
+    }
+
+    async fn handle_request(&mut self, request: Request) {
+        let result = self.handle(&request.method, request.params).await;
+        // no response for notifications
+        if let Some(id) = request.id {
+            respond(id, result);
+        }
+    }
+
+    async fn handle(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "load_project" => self.load_project(parse_params(params)?).await,
+            "list_packages" => self.list_packages(parse_params(params)?),
+            "search" => self.search(parse_params(params)?),
+            "plan_changes" => self.plan_changes(parse_params(params)?).await,
+            "resolve" => self.resolve(parse_params(params)?).await,
+            "apply" => self.apply(parse_params(params)?).await,
+            _ => Err(RpcError::protocol(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        }
+    }
+
+    async fn load_project(&self, params: ProjectParams) -> Result<Value, RpcError> {
+        let unity = load_project(&params.project).await?;
+        Ok(info::Project::version1_value(&unity))
+    }
+
+    fn list_packages(&self, params: ListPackagesParams) -> Result<Value, RpcError> {
+        let packages = self
+            .collection
+            .find_whole_all_packages(VersionSelector::latest_for(None, params.prerelease), |_| {
+                true
+            });
+        let packages = packages.iter().map(PackageEntry::new).collect::<Vec<_>>();
+        Ok(serde_json::to_value(packages).unwrap())
+    }
+
+    fn search(&self, params: SearchParams) -> Result<Value, RpcError> {
+        let queries = (params.query.split_whitespace())
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        let packages = search_packages(self.collection, &queries);
+        let packages = packages.iter().map(PackageEntry::new).collect::<Vec<_>>();
+        Ok(serde_json::to_value(packages).unwrap())
+    }
+
+    async fn plan_changes(&mut self, params: PlanChangesParams) -> Result<Value, RpcError> {
+        let unity = load_project(&params.project).await?;
+        let collection = self.project_collection();
+        let names = (params.packages.iter())
+            .map(|x| x.name.as_str())
+            .collect::<Vec<_>>();
+
+        let changes = match params.action {
+            PlanAction::Install => {
+                let mut packages = Vec::with_capacity(params.packages.len());
+                for request in &params.packages {
+                    let version = (request.version.as_deref())
+                        .map(VersionOrRange::from_str)
+                        .transpose()
+                        .map_err(|err| RpcError::protocol(INVALID_PARAMS, err))?;
+                    let selector = match &version {
+                        None => {
+                            VersionSelector::latest_for(unity.unity_version(), params.prerelease)
+                        }
+                        Some(VersionOrRange::Version(version)) => {
+                            VersionSelector::specific_version(version)
+                        }
+                        Some(VersionOrRange::Range(range)) => VersionSelector::range_for(
+                            unity.unity_version(),
+                            range,
+                            prerelease_acceptance(params.prerelease),
+                        ),
+                    };
+                    let package = collection
+                        .find_package_by_name(&request.name, selector)
+                        .ok_or_else(|| package_not_found(&request.name))?;
+                    let range = match version {
+                        Some(VersionOrRange::Range(range)) => DependencyRange::range(range),
+                        _ => DependencyRange::version(package.version().clone()),
+                    };
+                    packages.push((package, range));
+                }
+
+                unity
+                    .add_package_request_with_ranges(collection, &packages, params.prerelease)
+                    .await
+                    .map_err(|err| RpcError::failed("collecting packages to be installed", err))?
+            }
+            PlanAction::Upgrade => {
+                let mut packages = Vec::with_capacity(params.packages.len());
+                for request in &params.packages {
+                    let package = match request.version.as_deref() {
+                        None => find_upgrade(collection, &unity, &request.name, params.prerelease),
+                        Some(version) => {
+                            let version = Version::from_str(version)
+                                .map_err(|err| RpcError::protocol(INVALID_PARAMS, err))?;
+                            collection.find_package_by_name(
+                                &request.name,
+                                VersionSelector::specific_version(&version),
+                            )
+                        }
+                    };
+                    packages.push(package.ok_or_else(|| package_not_found(&request.name))?);
+                }
+
+                unity
+                    .add_package_request(
+                        collection,
+                        &packages,
+                        AddPackageOperation::UpgradeLocked,
+                        params.prerelease,
+                    )
+                    .await
+                    .map_err(|err| RpcError::failed("collecting packages to be upgraded", err))?
+            }
+            PlanAction::Remove => unity
+                .remove_request(&names)
+                .await
+                .map_err(|err| RpcError::failed("collecting packages to be removed", err))?,
+            PlanAction::Reinstall => unity
+                .reinstall_request(collection, &names)
+                .await
+                .map_err(|err| RpcError::failed("collecting packages to be reinstalled", err))?,
+        };
+
+        Ok(self.set_pending(unity, changes))
+    }
+
+    async fn resolve(&mut self, params: ProjectParams) -> Result<Value, RpcError> {
+        let unity = load_project(&params.project).await?;
+        let changes = unity
+            .resolve_request(self.project_collection())
+            .await
+            .map_err(|err| RpcError::failed("collecting packages to be installed", err))?;
+
+        Ok(self.set_pending(unity, changes))
+    }
+
+    /// The collection for `plan_changes` and `resolve`, which are handled after loading the project.
+    fn project_collection(&self) -> &'env PackageCollection {
+        self.project_collection.unwrap_or(self.collection)
+    }
+
+    /// Keeps the changes to be applied, discarding previously planned changes.
+    fn set_pending(&mut self, unity: UnityProject, changes: PendingProjectChanges<'env>) -> Value {
+        let id = self.next_changes_id;
+        self.next_changes_id += 1;
+
+        let result = json!({ "changes_id": id, "changes": ChangesInfo::new(&changes) });
+        self.pending = Some(Pending { id, unity, changes });
+        result
+    }
+
+    async fn apply(&mut self, params: ApplyParams) -> Result<Value, RpcError> {
+        let Some(pending) = (self.pending).take_if(|x| x.id == params.changes_id) else {
+            return Err(RpcError::new(
+                ErrorCode::ChangesNotFound,
+                format!("changes {} are not planned or outdated", params.changes_id),
+            ));
+        };
+
+        let Pending {
+            mut unity, changes, ..
+        } = pending;
+        let installer = PackageInstaller::new(self.io, self.http);
+        unity
+            .apply_pending_changes(&installer, changes)
+            .await
+            .map_err(|err| RpcError::failed("applying changes", err))?;
+
+        update_project_last_modified(self.io, unity.project_dir()).await;
+
+        Ok(Value::Null)
+    }
+}
+
+fn package_not_found(name: &str) -> RpcError {
+    RpcError::new(
+        ErrorCode::PackageNotFound,
+        format!("no matching version of {name} found"),
+    )
+}
@@ -0,0 +1,306 @@
+use super::{ResultExt, json, load_unity, update_project_last_modified};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::Path;
+use vrc_get_vpm::unity_project::{ScopedRegistry, UpmDependency};
+use vrc_get_vpm::version::Version;
+
+/// Commands around UPM packages in Packages/manifest.json
+///
+/// Those commands only modify manifest.json. Unity will download packages when the project is opened.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Upm {
+    List(UpmList),
+    Add(UpmAdd),
+    Remove(UpmRemove),
+    Upgrade(UpmUpgrade),
+    #[command(subcommand)]
+    Registry(Registry),
+}
+
+multi_command!(Upm is List, Add, Remove, Upgrade, Registry);
+
+/// List UPM packages in manifest.json
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UpmList {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl UpmList {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct UpmPackageInfo<'a> {
+                name: &'a str,
+                version: String,
+            }
+
+            let info = (unity.upm_dependencies())
+                .map(|(name, dependency)| UpmPackageInfo {
+                    name,
+                    version: dependency.to_string(),
+                })
+                .collect::<Vec<_>>();
+            return json::print(&info);
+        }
+
+        for (name, dependency) in unity.upm_dependencies() {
+            println!("{name}: {dependency}");
+        }
+    }
+}
+
+/// Add UPM package to manifest.json
+///
+/// If the package is already in manifest.json, the version will be replaced.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UpmAdd {
+    /// Name of package
+    #[arg()]
+    name: String,
+    /// Version, git url, or 'file:' path of package
+    #[arg(id = "VERSION")]
+    version: UpmDependency,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+}
+
+impl UpmAdd {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        if let Some(existing) = unity.get_upm_dependency(&self.name) {
+            message!("replacing {} {} with {}", self.name, existing, self.version);
+        } else {
+            message!("adding {} {}", self.name, self.version);
+        }
+
+        unity.add_upm_dependency(&self.name, self.version);
+
+        unity.save().await.exit_context("saving manifest file");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Remove UPM packages from manifest.json
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UpmRemove {
+    /// Name of packages to remove
+    #[arg(required = true)]
+    names: Vec<String>,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+}
+
+impl UpmRemove {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        for name in &self.names {
+            if !unity.remove_upm_dependency(name) {
+                exit_with!(
+                    UpmPackageNotFound,
+                    "UPM package {name} is not in manifest.json"
+                );
+            }
+        }
+
+        unity.save().await.exit_context("saving manifest file");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Upgrade UPM package installed from registry to the specified version
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UpmUpgrade {
+    /// Name of package
+    #[arg()]
+    name: String,
+    /// Version to upgrade to
+    #[arg(id = "VERSION")]
+    version: Version,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+}
+
+impl UpmUpgrade {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        let previous = unity
+            .upgrade_upm_dependency(&self.name, self.version.clone())
+            .exit_context("upgrading package");
+
+        if previous == self.version {
+            message!("{} is already version {}", self.name, self.version);
+            return;
+        }
+
+        message!(
+            "upgraded {} from {} to {}",
+            self.name,
+            previous,
+            self.version
+        );
+
+        unity.save().await.exit_context("saving manifest file");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Commands around scoped registries in manifest.json
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Registry {
+    List(RegistryList),
+    Add(RegistryAdd),
+    Remove(RegistryRemove),
+}
+
+multi_command!(Registry is List, Add, Remove);
+
+/// List scoped registries in manifest.json
+#[derive(Parser)]
+#[command(author, version)]
+pub struct RegistryList {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl RegistryList {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct RegistryInfo<'a> {
+                name: &'a str,
+                url: &'a str,
+                scopes: &'a [Box<str>],
+            }
+
+            let info = (unity.upm_scoped_registries().iter())
+                .map(|registry| RegistryInfo {
+                    name: registry.name(),
+                    url: registry.url(),
+                    scopes: registry.scopes(),
+                })
+                .collect::<Vec<_>>();
+            return json::print(&info);
+        }
+
+        for registry in unity.upm_scoped_registries() {
+            println!("{}: {}", registry.name(), registry.url());
+            for scope in registry.scopes() {
+                println!("  - {scope}");
+            }
+        }
+    }
+}
+
+/// Add scoped registry to manifest.json
+///
+/// If the registry with same url already exists, scopes are added to the existing registry.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct RegistryAdd {
+    /// Name of registry
+    #[arg()]
+    name: String,
+    /// URL of registry
+    #[arg()]
+    url: String,
+    /// Scopes of packages to be fetched from the registry
+    #[arg(short, long = "scope", required = true)]
+    scopes: Vec<String>,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+}
+
+impl RegistryAdd {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        unity.add_upm_scoped_registry(ScopedRegistry::new(
+            self.name.into(),
+            self.url.into(),
+            self.scopes.into_iter().map(Into::into).collect(),
+        ));
+
+        unity.save().await.exit_context("saving manifest file");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
+
+/// Remove scoped registry from manifest.json
+#[derive(Parser)]
+#[command(author, version)]
+pub struct RegistryRemove {
+    /// Name or url of registry
+    #[arg()]
+    name_or_url: String,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+}
+
+impl RegistryRemove {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut unity = load_unity(self.project).await;
+
+        if !unity.remove_upm_scoped_registry(&self.name_or_url) {
+            exit_with!(
+                ScopedRegistryNotFound,
+                "scoped registry {} not found",
+                self.name_or_url
+            );
+        }
+
+        unity.save().await.exit_context("saving manifest file");
+
+        update_project_last_modified(&io, unity.project_dir()).await;
+    }
+}
@@ -1,11 +1,15 @@
-use crate::commands::{ResultExt, absolute_path};
+use crate::commands::{ResultExt, absolute_path, json};
 use clap::{Parser, Subcommand, ValueEnum};
 use log::warn;
+use serde::Serialize;
 use std::cmp::Reverse;
 use std::fmt::{Display, Formatter};
-use std::path::Path;
-use vrc_get_vpm::environment::{Settings, VccDatabaseConnection, find_unity_hub};
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::environment::{
+    Settings, VccDatabaseConnection, find_unity_hub, find_unity_in_search_roots,
+};
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
+use vrc_get_vpm::version::UnityVersion;
 use vrc_get_vpm::{UnityProject, unity_hub};
 
 /// Experimental VCC commands
@@ -38,7 +42,7 @@ pub enum Project {
 
 multi_command!(Project is List, Add, Remove);
 
-async fn migrate_sanitize_projects(
+pub(super) async fn migrate_sanitize_projects(
     connection: &mut VccDatabaseConnection,
     io: &DefaultEnvironmentIo,
     settings: &Settings,
@@ -64,7 +68,7 @@ pub struct ProjectList {
 
 impl ProjectList {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let settings = Settings::load(&io).await.exit_context("loading settings");
 
         let mut connection = VccDatabaseConnection::connect(&io)
@@ -90,6 +94,30 @@ impl ProjectList {
 
         projects.sort_by_key(|x| Reverse(x.last_modified()));
 
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct ProjectInfo<'a> {
+                name: &'a str,
+                path: &'a str,
+                unity_version: Option<UnityVersion>,
+                project_type: String,
+                favorite: bool,
+            }
+
+            let info = (projects.iter())
+                .filter_map(|project| {
+                    Some(ProjectInfo {
+                        name: project.name()?,
+                        path: project.path()?,
+                        unity_version: project.unity_version(),
+                        project_type: project.project_type().to_string(),
+                        favorite: project.favorite(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            return json::print(&info);
+        }
+
         for project in projects.iter() {
             let Some(path) = project.path() else { continue };
             let Some(name) = project.name() else { continue };
@@ -118,7 +146,7 @@ pub struct ProjectAdd {
 
 impl ProjectAdd {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
         let mut connection = VccDatabaseConnection::connect(&io)
             .await
@@ -165,7 +193,7 @@ pub struct ProjectRemove {
 
 impl ProjectRemove {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
         let mut connection = VccDatabaseConnection::connect(&io)
             .await
@@ -176,7 +204,8 @@ impl ProjectRemove {
             .await
             .exit_context("getting projects")
         else {
-            return println!("No project found at {}", self.path);
+            message!("No project found at {}", self.path);
+            return;
         };
 
         migrate_sanitize_projects(&mut connection, &io, &settings).await;
@@ -207,9 +236,11 @@ pub enum Unity {
     Add(UnityAdd),
     Remove(UnityRemove),
     Update(UnityUpdate),
+    #[command(subcommand)]
+    SearchRoot(UnitySearchRoot),
 }
 
-multi_command!(Unity is List, Add, Remove, Update);
+multi_command!(Unity is List, Add, Remove, Update, SearchRoot);
 
 /// List registered Unity installations
 #[derive(Parser)]
@@ -221,7 +252,7 @@ pub struct UnityList {
 
 impl UnityList {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let connection = VccDatabaseConnection::connect(&io)
             .await
             .exit_context("connecting to database");
@@ -238,6 +269,24 @@ impl UnityList {
 
         unity_installations.sort_by_key(|x| Reverse(x.version()));
 
+        if json::enabled() {
+            #[derive(Serialize)]
+            struct UnityInfo<'a> {
+                version: Option<UnityVersion>,
+                path: &'a str,
+            }
+
+            let info = (unity_installations.iter())
+                .filter_map(|unity| {
+                    Some(UnityInfo {
+                        version: unity.version(),
+                        path: unity.path()?,
+                    })
+                })
+                .collect::<Vec<_>>();
+            return json::print(&info);
+        }
+
         for unity in unity_installations.iter() {
             if let Some(path) = unity.path() {
                 if let Some(unity_version) = unity.version() {
@@ -261,7 +310,7 @@ pub struct UnityAdd {
 
 impl UnityAdd {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut connection = VccDatabaseConnection::connect(&io)
             .await
             .exit_context("connecting to database");
@@ -281,7 +330,7 @@ impl UnityAdd {
             .await
             .exit_context("disposing database");
 
-        println!("Added version {} at {}", unity_version, self.path);
+        message!("Added version {} at {}", unity_version, self.path);
     }
 }
 
@@ -296,7 +345,7 @@ pub struct UnityRemove {
 
 impl UnityRemove {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut connection = VccDatabaseConnection::connect(&io)
             .await
             .exit_context("connecting to database");
@@ -324,10 +373,11 @@ impl UnityRemove {
     }
 }
 
-/// Update Unity installation list from file system and Unity Hub.
+/// Update Unity installation list from file system, Unity Hub, and search roots.
 ///
 /// If the installation is not found in the file system, it will be removed from the list.
-/// If the installation is found from Unity Hub, it will be added to the list.
+/// If the installation is found from Unity Hub or in the search roots, it will be added to the list.
+/// The search roots can be configured with `vrc-get vcc unity search-root` commands.
 #[derive(Parser)]
 #[command(author, version)]
 pub struct UnityUpdate {
@@ -336,6 +386,12 @@ pub struct UnityUpdate {
     /// The method to get the list of Unity from Unity Hub.
     #[arg(long, default_value_t)]
     method: UnityHubAccessMethod,
+    /// Additional folder to find Unity in. This can be specified multiple times.
+    #[arg(long = "search-root")]
+    search_roots: Vec<PathBuf>,
+    /// Do not load the list of Unity from Unity Hub.
+    #[arg(long)]
+    skip_unity_hub: bool,
 }
 
 #[derive(Default, Copy, Clone, Eq, Ord, PartialOrd, PartialEq, ValueEnum)]
@@ -358,35 +414,46 @@ impl Display for UnityHubAccessMethod {
 
 impl UnityUpdate {
     pub async fn run(self) {
-        let io = DefaultEnvironmentIo::new_default();
+        let io = self.env_args.environment_io();
         let mut settings = Settings::load(&io).await.exit_context("loading settings");
 
-        let unity_hub_path = find_unity_hub(&mut settings, &io)
+        let unity_list = if self.skip_unity_hub {
+            None
+        } else if let Some(unity_hub_path) = find_unity_hub(&mut settings, &io)
             .await
             .exit_context("loading unity hub path")
-            .unwrap_or_else(|| exit_with!("Unity Hub not found"));
-
-        let unity_list = match self.method {
-            UnityHubAccessMethod::ReadConfig => unity_hub::load_unity_by_loading_unity_hub_files()
-                .await
-                .exit_context("loading list of unity from config file")
-                .into_iter()
-                .map(|x| (x.version, x.path))
-                .collect::<Vec<_>>(),
-            UnityHubAccessMethod::CallHub => {
-                unity_hub::load_unity_by_calling_unity_hub(unity_hub_path.as_ref())
-                    .await
-                    .exit_context("loading unity list from unity hub")
-            }
+        {
+            Some(match self.method {
+                UnityHubAccessMethod::ReadConfig => {
+                    unity_hub::load_unity_by_loading_unity_hub_files()
+                        .await
+                        .exit_context("loading list of unity from config file")
+                        .into_iter()
+                        .map(|x| (x.version, x.path))
+                        .collect::<Vec<_>>()
+                }
+                UnityHubAccessMethod::CallHub => {
+                    unity_hub::load_unity_by_calling_unity_hub(unity_hub_path.as_ref())
+                        .await
+                        .exit_context("loading unity list from unity hub")
+                }
+            })
+        } else {
+            warn!("Unity Hub not found. only search roots are used");
+            None
         };
 
+        let mut search_roots = settings.unity_search_roots();
+        search_roots.extend(self.search_roots.iter().map(absolute_path));
+        let found_in_search_roots = find_unity_in_search_roots(&io, &search_roots).await;
+
         let mut connection = VccDatabaseConnection::connect(&io)
             .await
             .exit_context("connecting to database");
         connection
-            .update_unity_from_unity_hub_and_fs(&unity_list, &io)
+            .update_unity_installations(unity_list.as_deref(), &found_in_search_roots, &io)
             .await
-            .exit_context("updating unity from unity hub");
+            .exit_context("updating unity list");
 
         connection.save(&io).await.exit_context("saving database");
         settings.save(&io).await.exit_context("saving settings");
@@ -396,3 +463,85 @@ impl UnityUpdate {
             .exit_context("disposing database");
     }
 }
+
+/// Manage folders to find Unity in with `vrc-get vcc unity update`
+///
+/// If not configured, the default install location of Unity Hub is used.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum UnitySearchRoot {
+    List(UnitySearchRootList),
+    Add(UnitySearchRootAdd),
+    Remove(UnitySearchRootRemove),
+}
+
+multi_command!(UnitySearchRoot is List, Add, Remove);
+
+/// List folders to find Unity in
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UnitySearchRootList {
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+}
+
+impl UnitySearchRootList {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let settings = Settings::load(&io).await.exit_context("loading settings");
+
+        if json::enabled() {
+            return json::print(&settings.unity_search_roots());
+        }
+
+        for root in settings.unity_search_roots() {
+            println!("{}", root.display());
+        }
+    }
+}
+
+/// Add a folder to find Unity in
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UnitySearchRootAdd {
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+    path: PathBuf,
+}
+
+impl UnitySearchRootAdd {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        let path = absolute_path(&self.path);
+        if !settings.add_unity_search_root(&path) {
+            return eprintln!("{} is already a search root", path.display());
+        }
+
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}
+
+/// Remove a folder to find Unity in
+#[derive(Parser)]
+#[command(author, version)]
+pub struct UnitySearchRootRemove {
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+    path: PathBuf,
+}
+
+impl UnitySearchRootRemove {
+    pub async fn run(self) {
+        let io = self.env_args.environment_io();
+        let mut settings = Settings::load(&io).await.exit_context("loading settings");
+
+        let path = absolute_path(&self.path);
+        if !settings.remove_unity_search_root(&path) {
+            return eprintln!("{} is not a search root", path.display());
+        }
+
+        settings.save(&io).await.exit_context("saving settings");
+    }
+}